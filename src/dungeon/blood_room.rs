@@ -0,0 +1,255 @@
+use crate::dungeon::boss_room::find_floor_y;
+use crate::dungeon::dungeon::DUNGEON_ORIGIN;
+use crate::dungeon::floor_profile::FloorProfile;
+use crate::dungeon::score::format_ticks;
+use crate::dungeon::room::room::Room;
+use crate::server::entity::dungeons_loadouts::dungeons_loadouts;
+use crate::server::entity::entity::{EntityId, NoEntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::entity::equipment::Equipment;
+use crate::server::entity::spawn_equipped::{spawn_equipped_zombie, spawn_following_nametag, SpawnOpts};
+use crate::server::utils::aabb::AABB;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::world::World;
+use rand::Rng;
+
+// The blood room: once the blood door is opened the Watcher starts talking and spawning mobs in waves,
+// the room counts as cleared once every mob he spawned is dead.
+//
+// Timings are ticks since the blood door was opened and roughly match Hypixel, so blood camping can be practised.
+// The time until the Watcher is done spawning and the time until blood is cleared are sent in chat.
+
+const WATCHER_PREFIX: &str = "§c[BOSS] The Watcher§r§f: ";
+
+const OPENING_LINES: [(u64, &str); 3] = [
+    (20, "Congratulations, you made it through the Entrance."),
+    (60, "Ah, you've finally arrived."),
+    (100, "Let's see how you can handle this."),
+];
+const DONE_SPAWNING_LINE: &str = "That will be enough for now.";
+const CLEARED_LINE: &str = "You have proven yourself. You may pass.";
+
+const FIRST_SPAWN_TICK: u64 = 100;
+const FIRST_WAVE_SIZE: usize = 4;
+const SPAWN_INTERVAL: u64 = 40;
+
+/// Health of a blood mob on F1, multiplied by the mob strength of the floor.
+const BASE_MOB_HEALTH: f64 = 100_000.0;
+/// Damage a melee hit does to a blood mob, a single hit on F1 and a few on the higher floors.
+pub const MELEE_DAMAGE: f64 = 150_000.0;
+
+type Loadout = fn() -> Equipment;
+
+const BLOOD_MOBS: [(&str, Loadout); 6] = [
+    ("Revoker", dungeons_loadouts::zombie_commander),
+    ("Psycho", dungeons_loadouts::zombie_grunt),
+    ("Reaper", dungeons_loadouts::zombie_custom),
+    ("Cannibal", dungeons_loadouts::zombie_grunt),
+    ("Ooze", dungeons_loadouts::zombie_commander),
+    ("Mute", dungeons_loadouts::zombie_custom),
+];
+
+pub struct BloodMob {
+    pub entity_id: EntityId,
+    pub nametag_id: Option<EntityId>,
+    pub name: &'static str,
+    pub health: f64,
+}
+
+pub struct BloodRoom {
+    /// World tick the blood door was opened at, None while it is still closed.
+    pub opened_at: Option<u64>,
+    pub done_spawning_at: Option<u64>,
+    pub cleared: bool,
+
+    pub watcher_id: Option<EntityId>,
+    pub mobs: Vec<BloodMob>,
+    pub spawned: usize,
+
+    // middle of the room, on top of the floor
+    center: DVec3,
+}
+
+/// How many mobs the Watcher spawns in total.
+pub fn blood_mob_count(floor: &FloorProfile) -> usize {
+    4 + floor.floor as usize * 2 + if floor.master_mode { 4 } else { 0 }
+}
+
+fn mob_name(name: &str, health: f64) -> String {
+    format!("§c{} §a{}§c❤", name, format_health(health))
+}
+
+fn format_health(health: f64) -> String {
+    if health >= 1_000_000.0 {
+        format!("{:.1}M", health / 1_000_000.0)
+    } else {
+        format!("{}k", (health / 1000.0).round())
+    }
+}
+
+fn broadcast(world: &mut World, message: &str) {
+    for player in world.players.values_mut() {
+        player.send_message(message);
+    }
+}
+
+impl Default for BloodRoom {
+    fn default() -> Self {
+        Self {
+            opened_at: None,
+            done_spawning_at: None,
+            cleared: false,
+            watcher_id: None,
+            mobs: Vec::new(),
+            spawned: 0,
+            center: DVec3::ZERO,
+        }
+    }
+}
+
+impl BloodRoom {
+    /// Starts the Watcher, does nothing if the blood door has already been opened.
+    pub fn open(&mut self, world: &mut World, room: &Room) {
+        if self.opened_at.is_some() {
+            return;
+        }
+        self.opened_at = Some(world.tick_count);
+
+        let segment = &room.segments[0];
+        let x = segment.x as i32 * 32 + 15 + DUNGEON_ORIGIN.0;
+        let z = segment.z as i32 * 32 + 15 + DUNGEON_ORIGIN.1;
+        let y = find_floor_y(world, x, z, room.room_data.bottom, room.room_data.bottom + room.room_data.height).unwrap_or(69);
+        self.center = DVec3::new(x as f64 + 0.5, y as f64, z as f64 + 0.5);
+
+        let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
+        metadata.is_invisible = true;
+        metadata.custom_name = Some("§c§lThe Watcher".to_string());
+        metadata.custom_name_visible = true;
+        metadata.ai_disabled = true;
+
+        match world.spawn_entity(self.center + DVec3::new(0.0, 4.0, 0.0), metadata, NoEntityImpl) {
+            Ok(id) => self.watcher_id = Some(id),
+            Err(e) => eprintln!("Failed to spawn the Watcher: {}", e),
+        }
+    }
+
+    /// Returns true on the tick blood gets cleared.
+    pub fn tick(&mut self, world: &mut World, floor: &FloorProfile) -> bool {
+        let Some(opened_at) = self.opened_at else {
+            return false;
+        };
+        if self.cleared {
+            return false;
+        }
+        let ticks = world.tick_count - opened_at;
+
+        for (tick, line) in OPENING_LINES {
+            if ticks == tick {
+                broadcast(world, &format!("{}{}", WATCHER_PREFIX, line));
+            }
+        }
+
+        let total = blood_mob_count(floor);
+        if self.spawned < total && ticks >= FIRST_SPAWN_TICK && (ticks - FIRST_SPAWN_TICK).is_multiple_of(SPAWN_INTERVAL) {
+            let amount = if self.spawned == 0 { FIRST_WAVE_SIZE } else { 1 };
+            for _ in 0..amount.min(total - self.spawned) {
+                self.spawn_mob(world, floor);
+            }
+
+            if self.spawned == total {
+                self.done_spawning_at = Some(world.tick_count);
+                broadcast(world, &format!("{}{}", WATCHER_PREFIX, DONE_SPAWNING_LINE));
+                broadcast(world, &format!("§8[Blood] §7The Watcher finished spawning §a{} §7after the blood door opened.", format_ticks(ticks)));
+            }
+        }
+
+        // mobs can also be removed without going through damage_mob
+        self.mobs.retain(|mob| world.entities.contains_key(&mob.entity_id));
+
+        if self.done_spawning_at.is_some() && self.mobs.is_empty() {
+            self.cleared = true;
+            if let Some(watcher_id) = self.watcher_id.take() {
+                world.despawn_entity(watcher_id);
+            }
+            broadcast(world, &format!("{}{}", WATCHER_PREFIX, CLEARED_LINE));
+            broadcast(world, &format!("§8[Blood] §7Blood cleared in §a{}§7.", format_ticks(ticks)));
+            return true;
+        }
+        false
+    }
+
+    fn spawn_mob(&mut self, world: &mut World, floor: &FloorProfile) {
+        let (name, loadout) = BLOOD_MOBS[self.spawned % BLOOD_MOBS.len()];
+        self.spawned += 1;
+
+        let mut rng = rand::rng();
+        let x = self.center.x + rng.random_range(-8.0..=8.0);
+        let z = self.center.z + rng.random_range(-8.0..=8.0);
+        let y = find_floor_y(world, x.floor() as i32, z.floor() as i32, self.center.y as i32 - 3, self.center.y as i32 + 4)
+            .map(|y| y as f64)
+            .unwrap_or(self.center.y);
+        let position = DVec3::new(x, y, z);
+
+        // the Watcher floats over to where the mob spawns
+        if let Some((watcher, _)) = self.watcher_id.and_then(|id| world.entities.get_mut(&id)) {
+            watcher.position = position + DVec3::new(0.0, 4.0, 0.0);
+        }
+
+        let health = BASE_MOB_HEALTH * floor.mob_strength;
+        let entity_id = spawn_equipped_zombie(
+            world,
+            loadout(),
+            SpawnOpts {
+                pos: position,
+                yaw: rng.random_range(0.0..360.0),
+                pitch: 0.0,
+                hp: Some(health as f32),
+                tags: &["dungeons", "blood"],
+            },
+        );
+
+        let nametag_id = spawn_following_nametag(world, entity_id, &mob_name(name, health), 0.1).ok();
+        self.mobs.push(BloodMob { entity_id, nametag_id, name, health });
+    }
+
+    /// Damages the blood mob with the entity id, killing it once its health runs out.
+    /// Returns false if it isn't a blood mob.
+    pub fn damage_mob(&mut self, world: &mut World, entity_id: EntityId, damage: f64) -> bool {
+        let Some(index) = self.mobs.iter().position(|mob| mob.entity_id == entity_id) else {
+            return false;
+        };
+
+        let mob = &mut self.mobs[index];
+        mob.health -= damage;
+        if mob.health > 0.0 {
+            if let Some(nametag_id) = mob.nametag_id {
+                if let Some((entity, _)) = world.entities.get_mut(&nametag_id) {
+                    entity.metadata.custom_name = Some(mob_name(mob.name, mob.health));
+                    world.send_metadata_update(nametag_id);
+                }
+            }
+            return true;
+        }
+
+        let mob = self.mobs.remove(index);
+        world.despawn_entity(mob.entity_id);
+        if let Some(nametag_id) = mob.nametag_id {
+            world.despawn_entity(nametag_id);
+        }
+        true
+    }
+
+    /// Entity ids of the blood mobs inside the area.
+    pub fn mobs_in(&self, world: &World, area: &AABB) -> Vec<EntityId> {
+        self.mobs.iter()
+            .filter_map(|mob| world.entities.get(&mob.entity_id).map(|(entity, _)| (mob.entity_id, entity.position)))
+            .filter(|(_, position)| {
+                area.intersects(&AABB {
+                    min: DVec3::new(position.x - 0.3, position.y, position.z - 0.3),
+                    max: DVec3::new(position.x + 0.3, position.y + 1.95, position.z + 0.3),
+                })
+            })
+            .map(|(entity_id, _)| entity_id)
+            .collect()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::dungeon::floor_profile::FloorProfile;
+use crate::dungeon::room::room_data::{RoomData, RoomType};
+use crate::dungeon::room::room_pool::normalize_room_name;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::world::World;
+use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
+
+// The boss room sits outside of the dungeon grid, right next to it at a fixed corner.
+// It gets placed into the chunk grid along with the rest of the dungeon,
+// so its chunks get sent to players like any other chunk once they get close or are teleported in.
+//
+// Boss rooms are regular room jsons with the "boss" type, the one named after the floors boss is picked if it exists.
+
+pub const BOSS_ROOM_CORNER: BlockPos = BlockPos { x: -8, y: 0, z: -8 };
+
+#[derive(Debug)]
+pub struct BossRoom {
+    pub room_data: RoomData,
+    pub corner: BlockPos,
+    /// Where the party gets teleported to, set once the room is loaded into the world.
+    pub spawn: DVec3,
+}
+
+impl BossRoom {
+    pub fn from_storage(floor: &FloorProfile, room_data_storage: &DeterministicHashMap<usize, RoomData>) -> Option<BossRoom> {
+        // sorted by id so the pick doesn't depend on the storage order
+        let mut boss_rooms = room_data_storage.iter()
+            .filter(|(_, room_data)| room_data.room_type == RoomType::Boss)
+            .collect::<Vec<(&usize, &RoomData)>>();
+        boss_rooms.sort_by_key(|(id, _)| **id);
+
+        let boss_name = normalize_room_name(floor.boss);
+        let (_, room_data) = boss_rooms.iter()
+            .find(|(_, room_data)| normalize_room_name(&room_data.name) == boss_name)
+            .or(boss_rooms.first())?;
+
+        Some(BossRoom {
+            room_data: (*room_data).clone(),
+            corner: BOSS_ROOM_CORNER,
+            spawn: DVec3::new(
+                BOSS_ROOM_CORNER.x as f64 + room_data.width as f64 / 2.0,
+                room_data.bottom as f64,
+                BOSS_ROOM_CORNER.z as f64 + room_data.length as f64 / 2.0,
+            ),
+        })
+    }
+
+    /// Places the blocks as they are in the room data (boss rooms are never rotated),
+    /// then puts the spawn on top of the floor in the middle of the room.
+    pub fn load_into_world(&mut self, world: &mut World) {
+        let room_data = &self.room_data;
+
+        for (i, block) in room_data.block_data.iter().enumerate() {
+            if *block == Blocks::Air {
+                continue;
+            }
+
+            let index = i as i32;
+            let x = index % room_data.width;
+            let z = (index / room_data.width) % room_data.length;
+            let y = room_data.bottom + index / (room_data.width * room_data.length);
+
+            world.set_block_at(*block, self.corner.x + x, y, self.corner.z + z);
+        }
+
+        let (x, z) = (self.spawn.x.floor() as i32, self.spawn.z.floor() as i32);
+        if let Some(y) = find_floor_y(world, x, z, room_data.bottom, room_data.bottom + room_data.height) {
+            self.spawn.y = y as f64;
+        }
+    }
+
+    pub fn contains(&self, position: &DVec3) -> bool {
+        let (x, z) = (position.x.floor() as i32, position.z.floor() as i32);
+        x >= self.corner.x
+            && x < self.corner.x + self.room_data.width
+            && z >= self.corner.z
+            && z < self.corner.z + self.room_data.length
+    }
+}
+
+/// The first y level between bottom and top a player can stand at, two blocks of air on top of a solid block.
+pub fn find_floor_y(world: &World, x: i32, z: i32, bottom: i32, top: i32) -> Option<i32> {
+    (bottom + 1..top).find(|y| {
+        world.get_block_at(x, *y, z) == Blocks::Air
+            && world.get_block_at(x, y + 1, z) == Blocks::Air
+            && world.get_block_at(x, y - 1, z) != Blocks::Air
+    })
+}
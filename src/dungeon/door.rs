@@ -13,6 +13,7 @@ use crate::server::utils::sounds::Sounds;
 use crate::net::protocol::play::clientbound::SoundEffect;
 use crate::utils::seeded_rng::seeded_rng;
 use rand::prelude::IndexedRandom;
+use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -34,6 +35,52 @@ impl DoorType {
     }
 }
 
+/// The blocks making up each kind of door, loaded from door_data/doors.txt
+pub static DOOR_TYPE_BLOCKS: Lazy<HashMap<DoorType, Vec<Vec<Blocks>>>> = Lazy::new(|| {
+    // Might be a good idea to make a new format for storing doors so that indexes etc don't need to be hard coded.
+    // But this works for now...
+    let door_data: Vec<Vec<Blocks>> = include_str!("../door_data/doors.txt")
+        .split("\n")
+        .map(|line| {
+            let mut blocks: Vec<Blocks> = Vec::new();
+
+            for i in (0..line.len() - 1).step_by(4) {
+                if let Some(substr) = line.get(i..i + 4) {
+                    if let Ok(state) = u16::from_str_radix(substr, 16) {
+                        blocks.push(Blocks::from(state));
+                    }
+                }
+            }
+
+            blocks
+        })
+        .collect();
+
+    HashMap::from_iter(
+        vec![
+            (DoorType::BLOOD, vec![door_data[0].clone()]),
+            (DoorType::ENTRANCE, vec![door_data[1].clone()]),
+            (DoorType::WITHER, vec![
+                door_data[2].clone(),
+                door_data[3].clone(),
+                door_data[4].clone(),
+            ]),
+            (
+                DoorType::NORMAL,
+                vec![
+                    door_data[1].clone(),
+                    door_data[2].clone(),
+                    door_data[3].clone(),
+                    door_data[4].clone(),
+                    door_data[5].clone(),
+                    door_data[6].clone(),
+                    door_data[7].clone(),
+                ],
+            ),
+        ],
+    )
+});
+
 #[derive(Debug, PartialEq)]
 pub struct Door {
     pub x: i32,
@@ -1,19 +1,34 @@
-use crate::dungeon::door::{Door, DoorType};
+use crate::dungeon::blood_room::{BloodRoom, MELEE_DAMAGE};
+use crate::dungeon::fairy_room::FairyRoom;
+use crate::dungeon::boss_room::{find_floor_y, BossRoom};
+use crate::dungeon::door::{Door, DoorType, DOOR_TYPE_BLOCKS};
+use crate::dungeon::dungeon_parse_error::DungeonParseError;
+use crate::dungeon::dungeon_seed::DungeonSeed;
 use crate::dungeon::dungeon_state::DungeonState;
+use crate::dungeon::floor_profile::FloorProfile;
 use crate::dungeon::map::DungeonMap;
+use crate::dungeon::mort::spawn_mort;
 use crate::dungeon::room::room::{Room, RoomNeighbour, RoomSegment};
 use crate::dungeon::room::room_data::{get_random_data_with_type, RoomData, RoomShape, RoomType};
-use crate::net::protocol::play::clientbound::Maps;
+use crate::dungeon::room::room_pool::{normalize_room_name, RoomPool};
+use crate::dungeon::puzzle::{puzzle_from_room_name, PuzzleClick, PuzzleOutcome, PuzzleState, RoomPuzzle};
+use crate::dungeon::score::{format_run_time, format_ticks, DungeonScore};
+use crate::net::protocol::play::clientbound::{Maps, PositionLook};
 use crate::server::block::block_interact_action::BlockInteractAction;
-use crate::server::block::block_parameter::Axis;
+use crate::server::block::block_parameter::{Axis, TorchDirection};
 use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::entity::entity::EntityId;
 use crate::server::player::player::Player;
 use crate::server::server::Server;
 use crate::server::utils::dvec3::DVec3;
 use crate::server::world;
+use crate::server::world::World;
 use crate::server::utils::sounds::Sounds;
 use crate::net::protocol::play::clientbound::SoundEffect;
 use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
+use crate::utils::seeded_rng::SeededRng;
 use anyhow::bail;
 use std::collections::HashMap;
 // use crate::server::block::block_interact_action::BlockInteractAction::*;
@@ -21,9 +36,6 @@ use std::collections::HashMap;
 // The top leftmost corner of the dungeon
 pub const DUNGEON_ORIGIN: (i32, i32) = (-200, -200);
 
-// The positions of the doors in the world
-pub const DOOR_POSITIONS: [(i32, i32); 60] = [(DUNGEON_ORIGIN.0 + 31, DUNGEON_ORIGIN.1 + 15), (DUNGEON_ORIGIN.0 + 63, DUNGEON_ORIGIN.1 + 15), (DUNGEON_ORIGIN.0 + 95, DUNGEON_ORIGIN.1 + 15), (DUNGEON_ORIGIN.0 + 127, DUNGEON_ORIGIN.1 + 15), (DUNGEON_ORIGIN.0 + 159, DUNGEON_ORIGIN.1 + 15), (DUNGEON_ORIGIN.0 + 15, DUNGEON_ORIGIN.1 + 31), (DUNGEON_ORIGIN.0 + 47, DUNGEON_ORIGIN.1 + 31), (DUNGEON_ORIGIN.0 + 79, DUNGEON_ORIGIN.1 + 31), (DUNGEON_ORIGIN.0 + 111, DUNGEON_ORIGIN.1 + 31), (DUNGEON_ORIGIN.0 + 143, DUNGEON_ORIGIN.1 + 31), (DUNGEON_ORIGIN.0 + 175, DUNGEON_ORIGIN.1 + 31), (DUNGEON_ORIGIN.0 + 31, DUNGEON_ORIGIN.1 + 47), (DUNGEON_ORIGIN.0 + 63, DUNGEON_ORIGIN.1 + 47), (DUNGEON_ORIGIN.0 + 95, DUNGEON_ORIGIN.1 + 47), (DUNGEON_ORIGIN.0 + 127, DUNGEON_ORIGIN.1 + 47), (DUNGEON_ORIGIN.0 + 159, DUNGEON_ORIGIN.1 + 47), (DUNGEON_ORIGIN.0 + 15, DUNGEON_ORIGIN.1 + 63), (DUNGEON_ORIGIN.0 + 47, DUNGEON_ORIGIN.1 + 63), (DUNGEON_ORIGIN.0 + 79, DUNGEON_ORIGIN.1 + 63), (DUNGEON_ORIGIN.0 + 111, DUNGEON_ORIGIN.1 + 63), (DUNGEON_ORIGIN.0 + 143, DUNGEON_ORIGIN.1 + 63), (DUNGEON_ORIGIN.0 + 175, DUNGEON_ORIGIN.1 + 63), (DUNGEON_ORIGIN.0 + 31, DUNGEON_ORIGIN.1 + 79), (DUNGEON_ORIGIN.0 + 63, DUNGEON_ORIGIN.1 + 79), (DUNGEON_ORIGIN.0 + 95, DUNGEON_ORIGIN.1 + 79), (DUNGEON_ORIGIN.0 + 127, DUNGEON_ORIGIN.1 + 79), (DUNGEON_ORIGIN.0 + 159, DUNGEON_ORIGIN.1 + 79), (DUNGEON_ORIGIN.0 + 15, DUNGEON_ORIGIN.1 + 95), (DUNGEON_ORIGIN.0 + 47, DUNGEON_ORIGIN.1 + 95), (DUNGEON_ORIGIN.0 + 79, DUNGEON_ORIGIN.1 + 95), (DUNGEON_ORIGIN.0 + 111, DUNGEON_ORIGIN.1 + 95), (DUNGEON_ORIGIN.0 + 143, DUNGEON_ORIGIN.1 + 95), (DUNGEON_ORIGIN.0 + 175, DUNGEON_ORIGIN.1 + 95), (DUNGEON_ORIGIN.0 + 31, DUNGEON_ORIGIN.1 + 111), (DUNGEON_ORIGIN.0 + 63, DUNGEON_ORIGIN.1 + 111), (DUNGEON_ORIGIN.0 + 95, DUNGEON_ORIGIN.1 + 111), (DUNGEON_ORIGIN.0 + 127, DUNGEON_ORIGIN.1 + 111), (DUNGEON_ORIGIN.0 + 159, DUNGEON_ORIGIN.1 + 111), (DUNGEON_ORIGIN.0 + 15, DUNGEON_ORIGIN.1 + 127), (DUNGEON_ORIGIN.0 + 47, DUNGEON_ORIGIN.1 + 127), (DUNGEON_ORIGIN.0 + 79, DUNGEON_ORIGIN.1 + 127), (DUNGEON_ORIGIN.0 + 111, DUNGEON_ORIGIN.1 + 127), (DUNGEON_ORIGIN.0 + 143, DUNGEON_ORIGIN.1 + 127), (DUNGEON_ORIGIN.0 + 175, DUNGEON_ORIGIN.1 + 127), (DUNGEON_ORIGIN.0 + 31, DUNGEON_ORIGIN.1 + 143), (DUNGEON_ORIGIN.0 + 63, DUNGEON_ORIGIN.1 + 143), (DUNGEON_ORIGIN.0 + 95, DUNGEON_ORIGIN.1 + 143), (DUNGEON_ORIGIN.0 + 127, DUNGEON_ORIGIN.1 + 143), (DUNGEON_ORIGIN.0 + 159, DUNGEON_ORIGIN.1 + 143), (DUNGEON_ORIGIN.0 + 15, DUNGEON_ORIGIN.1 + 159), (DUNGEON_ORIGIN.0 + 47, DUNGEON_ORIGIN.1 + 159), (DUNGEON_ORIGIN.0 + 79, DUNGEON_ORIGIN.1 + 159), (DUNGEON_ORIGIN.0 + 111, DUNGEON_ORIGIN.1 + 159), (DUNGEON_ORIGIN.0 + 143, DUNGEON_ORIGIN.1 + 159), (DUNGEON_ORIGIN.0 + 175, DUNGEON_ORIGIN.1 + 159), (DUNGEON_ORIGIN.0 + 31, DUNGEON_ORIGIN.1 + 175), (DUNGEON_ORIGIN.0 + 63, DUNGEON_ORIGIN.1 + 175), (DUNGEON_ORIGIN.0 + 95, DUNGEON_ORIGIN.1 + 175), (DUNGEON_ORIGIN.0 + 127, DUNGEON_ORIGIN.1 + 175), (DUNGEON_ORIGIN.0 + 159, DUNGEON_ORIGIN.1 + 175)];
-
 /// State for a locked chest
 #[derive(Debug, Clone)]
 pub struct LockedChestState {
@@ -39,8 +51,9 @@ pub struct Dungeon {
     pub server: *mut Server,
     pub doors: Vec<Door>,
     pub rooms: Vec<Room>,
+    pub floor: FloorProfile,
 
-    pub room_grid: [Option<usize>; 36],
+    pub room_grid: Vec<Option<usize>>,
     pub state: DungeonState,
     pub map: DungeonMap,
 
@@ -53,17 +66,24 @@ pub struct Dungeon {
     // Maps lever world position to all chests it unlocks
     pub lever_to_chests: HashMap<BlockPos, Vec<BlockPos>>,
     
-    // Boss room data
-    // pub boss_room_corner: BlockPos,
-    // pub boss_room_width: i32,
-    // pub boss_room_length: i32,
-    // pub boss_room_height: i32,
-    
+    // None if there is no boss room data for the floor, the run just ends when blood is cleared then
+    pub boss_room: Option<BossRoom>,
+    pub blood_room: BloodRoom,
+    pub fairy_room: FairyRoom,
+    pub puzzles: Vec<RoomPuzzle>,
+    // Centers of the portals that teleport the party into the boss room, placed once blood is cleared.
+    // One is in the blood room, the other in the fairy room if the dungeon has one
+    pub boss_portals: Vec<BlockPos>,
+
+    // Only used for the score. Deaths are counted when trap hazards kill a player,
+    // there is no mimic yet so mimic_killed stays false and the score never gets the mimic bonus
+    pub deaths: u32,
+    pub mimic_killed: bool,
 }
 
 impl Dungeon {
-    pub fn from_layout(doors: Vec<Door>, mut rooms: Vec<Room>) -> anyhow::Result<Dungeon> {
-        let mut room_grid: [Option<usize>; 36] = [const { None }; 36];
+    pub fn from_layout(doors: Vec<Door>, mut rooms: Vec<Room>, floor: FloorProfile) -> anyhow::Result<Dungeon> {
+        let mut room_grid: Vec<Option<usize>> = vec![None; floor.room_count()];
         let mut grid_max_x = 0;
         let mut grid_max_y = 0;
 
@@ -71,9 +91,9 @@ impl Dungeon {
             for segment in room.segments.iter() {
                 let x = segment.x;
                 let z = segment.z;
-                let segment_index = (x + z * 6) as usize;
+                let segment_index = floor.grid_index(x, z);
     
-                if segment_index > room_grid.len() - 1 {
+                if !floor.is_in_grid(x, z) {
                     bail!("Segment index for {},{} out of bounds: {}", x, z, segment_index);
                 }
                 if room_grid[segment_index].is_some() {
@@ -122,7 +142,7 @@ impl Dungeon {
                     if let Some((door_index, _)) = door {
                         segment.neighbours[index] = Some(RoomNeighbour {
                             door_index: door_index,
-                            room_index: room_grid[(nx + nz * 6) as usize].expect("Neighbor should be Some")
+                            room_index: room_grid[floor.grid_index(nx as usize, nz as usize)].expect("Neighbor should be Some")
                         });
                     }
                 }
@@ -136,28 +156,158 @@ impl Dungeon {
             server: std::ptr::null_mut(),
             doors,
             rooms,
+            floor,
             room_grid: room_grid,
             state: DungeonState::NotReady,
             map: DungeonMap::new(map_offset_x, map_offset_y),
             temp_player_mushroom_up: HashMap::new(),
             locked_chests: HashMap::new(),
             lever_to_chests: HashMap::new(),
-            // boss_room_corner: BlockPos { x: -8, y: 254, z: -8 },
-            // boss_room_width: 0, // Will be set when boss room is loaded
-            // boss_room_length: 0, // Will be set when boss room is loaded
-            // boss_room_height: 30, // Default height
+            boss_room: None,
+            blood_room: BloodRoom::default(),
+            fairy_room: FairyRoom::default(),
+            puzzles: Vec::new(),
+            boss_portals: Vec::new(),
+            deaths: 0,
+            mimic_killed: false,
         })
     }
 
 
-    pub fn from_str(layout_str: &str, room_data_storage: &DeterministicHashMap<usize, RoomData>) -> anyhow::Result<Dungeon> {
+    /// Parses either a legacy dungeon string for the floor (using the current state of the seeded rng)
+    /// or a [DungeonSeed] string, which sets the rng seed and floor itself.
+    /// The server goes through [DungeonSeed::parse] since it keeps the seed around, this is for anything that only has the string.
+    #[allow(dead_code)]
+    pub fn from_str(layout_str: &str, floor: FloorProfile, room_data_storage: &DeterministicHashMap<usize, RoomData>, room_pool: &RoomPool) -> Result<Dungeon, DungeonParseError> {
+        let layout_str = layout_str.trim();
+        if DungeonSeed::is_seed_string(layout_str) {
+            let seed = DungeonSeed::parse(layout_str, floor)?;
+            return Self::from_seed(&seed, room_data_storage, room_pool)
+        }
+        Self::from_layout_str(layout_str, floor, &[], room_data_storage, room_pool)
+    }
+
+    pub fn from_seed(seed: &DungeonSeed, room_data_storage: &DeterministicHashMap<usize, RoomData>, room_pool: &RoomPool) -> Result<Dungeon, DungeonParseError> {
+        SeededRng::set_seed(seed.rng_seed);
+        Self::from_layout_str(&seed.layout, seed.floor, &seed.pinned_rooms, room_data_storage, room_pool)
+    }
+
+    /// Looks up the room pinned to any of the segments, either by id from the seed or by name from the room pool.
+    /// The pinned room has to have the same type and shape as the spot it's pinned to.
+    fn get_pinned_room_data(
+        floor: &FloorProfile,
+        segments: &[RoomSegment],
+        room_type: RoomType,
+        shape: &RoomShape,
+        pinned_rooms: &[(usize, usize)],
+        room_pool: &RoomPool,
+        room_data_storage: &DeterministicHashMap<usize, RoomData>,
+    ) -> Result<Option<RoomData>, DungeonParseError> {
+        let pinned_id = pinned_rooms.iter().find(|(grid_index, _)| {
+            segments.iter().any(|segment| floor.grid_index(segment.x, segment.z) == *grid_index)
+        });
+        let pinned_name = segments.iter().find_map(|segment| {
+            room_pool.get_pinned_name(segment.x, segment.z).map(|name| (segment, name))
+        });
+
+        let (x, z, room_data) = match (pinned_id, pinned_name) {
+            (Some((grid_index, room_id)), _) => {
+                let (x, z) = (grid_index % floor.width, grid_index / floor.width);
+                match room_data_storage.get(room_id) {
+                    Some(room_data) => (x, z, room_data),
+                    None => return Err(DungeonParseError::MissingPinnedRoom { x, z, room_id: *room_id }),
+                }
+            }
+            (None, Some((segment, name))) => {
+                let room_data = room_data_storage.values()
+                    .find(|room_data| normalize_room_name(&room_data.name) == *name)
+                    .ok_or_else(|| DungeonParseError::UnknownPinnedRoom { x: segment.x, z: segment.z, name: name.clone() })?;
+                (segment.x, segment.z, room_data)
+            }
+            (None, None) => return Ok(None),
+        };
+
+        if room_data.room_type != room_type || room_data.shape != *shape {
+            return Err(DungeonParseError::PinnedRoomMismatch {
+                x,
+                z,
+                name: room_data.name.clone(),
+                room_type,
+                shape: shape.clone(),
+            })
+        }
+
+        Ok(Some(room_data.clone()))
+    }
+
+    /// Makes sure the segments of a normal room are all connected and form a shape that actually exists.
+    fn validate_segments(room_id: usize, segments: &[RoomSegment]) -> Result<(), DungeonParseError> {
+        let first = &segments[0];
+
+        if segments.len() > 4 {
+            return Err(DungeonParseError::ImpossibleShape { room_id, x: first.x, z: first.z, segments: segments.len() })
+        }
+
+        let mut connected = vec![first];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for segment in segments.iter() {
+                let touching = connected.iter().any(|other| segment.x.abs_diff(other.x) + segment.z.abs_diff(other.z) == 1);
+                if touching && !connected.iter().any(|other| other.x == segment.x && other.z == segment.z) {
+                    connected.push(segment);
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some(segment) = segments.iter().find(|segment| !connected.iter().any(|other| other.x == segment.x && other.z == segment.z)) {
+            return Err(DungeonParseError::DisconnectedSegments { room_id, x: segment.x, z: segment.z })
+        }
+
+        // Connected shapes of 1-3 segments always exist, 4 segments has to be either a line or a square.
+        if segments.len() == 4 {
+            let width = segments.iter().map(|s| s.x).max().unwrap() - segments.iter().map(|s| s.x).min().unwrap() + 1;
+            let length = segments.iter().map(|s| s.z).max().unwrap() - segments.iter().map(|s| s.z).min().unwrap() + 1;
+
+            if !matches!((width, length), (1, 4) | (4, 1) | (2, 2)) {
+                return Err(DungeonParseError::ImpossibleShape { room_id, x: first.x, z: first.z, segments: segments.len() })
+            }
+        }
+
+        Ok(())
+    }
+
+    fn from_layout_str(
+        layout_str: &str,
+        floor: FloorProfile,
+        pinned_rooms: &[(usize, usize)],
+        room_data_storage: &DeterministicHashMap<usize, RoomData>,
+        room_pool: &RoomPool,
+    ) -> Result<Dungeon, DungeonParseError> {
+        if layout_str.len() != floor.layout_length() || !layout_str.is_ascii() {
+            return Err(DungeonParseError::WrongLength { length: layout_str.chars().count(), expected: floor.layout_length() })
+        }
+
         let mut rooms: Vec<Room> = Vec::new();
         let mut doors: Vec<Door> = Vec::new();
 
         let mut room_id_map: DeterministicHashMap<usize, Vec<RoomSegment>> = DeterministicHashMap::default();
 
-        for (index, (x, z)) in DOOR_POSITIONS.into_iter().enumerate() {
-            let type_str = layout_str.get(index + 72..index+73).unwrap();
+        let mut id_grid = vec![0usize; floor.room_count()];
+        for (i, id) in id_grid.iter_mut().enumerate() {
+            let substr = &layout_str[i*2..i*2+2];
+
+            *id = substr.parse::<usize>().map_err(|_| DungeonParseError::InvalidRoomId {
+                x: i % floor.width,
+                z: i / floor.width,
+                value: substr.to_string(),
+            })?;
+        }
+
+        let doors_start = floor.room_count() * 2;
+        for (index, (x, z)) in floor.door_positions().into_iter().enumerate() {
+            let type_str = &layout_str[doors_start + index..doors_start + index + 1];
 
             let door_type = match type_str {
                 "0" => Some(DoorType::NORMAL),
@@ -168,6 +318,15 @@ impl Dungeon {
             };
 
             if let Some(door_type) = door_type {
+                // Doors need a room on both sides, and can't be inside of a single (normal) room
+                let (from, to) = floor.door_cells(index);
+                let from_id = id_grid[floor.grid_index(from.0, from.1)];
+                let to_id = id_grid[floor.grid_index(to.0, to.1)];
+
+                if from_id == 0 || to_id == 0 || (from_id == to_id && from_id > 6) {
+                    return Err(DungeonParseError::InvalidDoor { door_index: index, from, to })
+                }
+
                 let direction = match ((x - DUNGEON_ORIGIN.0) / 16) % 2 {
                     0 => Axis::Z,
                     1 => Axis::X,
@@ -185,25 +344,24 @@ impl Dungeon {
             }
         }
 
-        for i in 0..36 {
-            let substr = layout_str.get(i*2..i*2+2);
-            let x = i % 6;
-            let z = i / 6;
-
-            // Shouldn't happen if data is not corrupted
-            if substr.is_none() {
-                panic!("Failed to parse dungeon string: too small.")
+        // Normal rooms, add segments to their specific room id
+        for (i, id) in id_grid.iter().copied().enumerate() {
+            if id > 6 {
+                let entry = room_id_map.entry(id).or_default();
+                entry.push(RoomSegment { x: i % floor.width, z: i / floor.width, neighbours: [const { None }; 4] });
             }
+        }
 
-            let id = substr.unwrap().parse::<usize>()?;
+        for (room_id, segments) in room_id_map.iter() {
+            Self::validate_segments(*room_id, segments)?;
+        }
 
-            // No room here
-            if id == 0 {
-                continue;
-            }
+        // Special rooms
+        for (i, id) in id_grid.into_iter().enumerate() {
+            let x = i % floor.width;
+            let z = i / floor.width;
 
-            // Special rooms
-            if id <= 6 {
+            if (1..=6).contains(&id) {
                 let room_type = match id {
                     1 => RoomType::Entrance,
                     2 => RoomType::Fairy,
@@ -214,6 +372,10 @@ impl Dungeon {
                     _ => unreachable!()
                 };
 
+                if !floor.allowed_room_types().contains(&room_type) {
+                    return Err(DungeonParseError::RoomTypeNotAllowed { x, z, room_type, floor: floor.name })
+                }
+
                 // Fairy can have a varying number of doors, all other special rooms are fixed to just one.
                 let shape = match room_type {
                     RoomType::Fairy => RoomShape::OneByOne,
@@ -221,47 +383,56 @@ impl Dungeon {
                     _ => RoomShape::OneByOneEnd,
                 };
 
-                let mut room_data = get_random_data_with_type(
-                    room_type,
-                    shape,
-                    room_data_storage,
-                    &rooms
-                );
+                let segments = vec![RoomSegment { x, z, neighbours: [const { None }; 4] }];
+
+                let mut room_data = match Self::get_pinned_room_data(&floor, &segments, room_type, &shape, pinned_rooms, room_pool, room_data_storage)? {
+                    Some(room_data) => room_data,
+                    None => get_random_data_with_type(
+                        room_type,
+                        shape.clone(),
+                        room_data_storage,
+                        &rooms,
+                        room_pool,
+                    ).ok_or(DungeonParseError::MissingRoomData { x, z, room_type, shape })?,
+                };
 
                 room_data.room_type = room_type;
 
                 rooms.push(Room::new(
-                    vec![RoomSegment { x, z, neighbours: [const { None }; 4] }],
+                    segments,
                     &doors,
                     room_data
                 ));
-
-                continue
             }
-
-            // Normal rooms, add segments to this specific room id
-            let entry = room_id_map.entry(id).or_default();
-            entry.push(RoomSegment { x, z, neighbours: [const { None }; 4] });
         }
 
         // Make the normal rooms
         rooms.reserve(room_id_map.len());
         for (_, segments) in room_id_map {
             let shape = RoomShape::from_segments(&segments, &doors);
+            let (x, z) = (segments[0].x, segments[0].z);
+
+            let room_data = match Self::get_pinned_room_data(&floor, &segments, RoomType::Normal, &shape, pinned_rooms, room_pool, room_data_storage)? {
+                Some(room_data) => room_data,
+                None => get_random_data_with_type(
+                    RoomType::Normal,
+                    shape.clone(),
+                    room_data_storage,
+                    &rooms,
+                    room_pool,
+                ).ok_or(DungeonParseError::MissingRoomData { x, z, room_type: RoomType::Normal, shape })?,
+            };
 
             rooms.push(Room::new(
                 segments,
                 &doors,
-                get_random_data_with_type(
-                    RoomType::Normal,
-                    shape,
-                    room_data_storage,
-                    &rooms
-                )
+                room_data
             ));
         }
 
-        Self::from_layout(doors, rooms)
+        let mut dungeon = Self::from_layout(doors, rooms, floor).map_err(|e| DungeonParseError::InvalidLayout(e.to_string()))?;
+        dungeon.boss_room = BossRoom::from_storage(&floor, room_data_storage);
+        Ok(dungeon)
     }
 
     // pub fn with_rooms_and_doors(rooms: Vec<Room>, doors: Vec<Door>) -> anyhow::Result<Dungeon> {
@@ -474,6 +645,96 @@ impl Dungeon {
         unsafe { self.server.as_mut().expect("server is null") }
     }
 
+    /// Places the rooms and doors into the world, sets the spawn point and spawns mort.
+    pub fn load_into_world(&mut self, world: &mut World) -> anyhow::Result<()> {
+        for room in &mut self.rooms {
+            room.load_into_world(world);
+
+            // Immediately scan crypts on world load for debug visibility
+            if !room.crypt_patterns.is_empty() && !room.crypts_checked {
+                let count = room.detect_crypts(world);
+                if count == 0 {
+                    room.debug_crypt_mismatch(world);
+                }
+            }
+
+            // Set the spawn point to be inside of the spawn room
+            if room.room_data.room_type == RoomType::Entrance {
+                world.set_spawn_point(
+                    room.get_world_block_pos(&BlockPos {
+                        x: 15,
+                        y: 72,
+                        z: 18,
+                    })
+                    .as_dvec3()
+                    .add_x(0.5)
+                    .add_z(0.5),
+                    180.0.rotate(room.rotation),
+                    0.0,
+                );
+
+                spawn_mort(world, room)?;
+            }
+        }
+
+        // Spawn locked chests for all rooms
+        for room in &self.rooms {
+            room.spawn_locked_chests(
+                world,
+                &mut self.locked_chests,
+                &mut self.lever_to_chests,
+            );
+        }
+
+        for (room_index, room) in self.rooms.iter().enumerate() {
+            if room.room_data.room_type != RoomType::Puzzle {
+                continue;
+            }
+            if let Some(puzzle) = puzzle_from_room_name(&room.room_data.name) {
+                self.puzzles.push(RoomPuzzle::new(world, room_index, room, puzzle, &mut self.locked_chests));
+            }
+        }
+
+        if let Some(room) = self.rooms.iter().find(|room| room.room_data.room_type == RoomType::Fairy) {
+            self.fairy_room.load(world, room);
+        }
+
+        // Remove vines from specific rooms and add special blocks after all rooms are loaded
+        for room in &self.rooms {
+            let corner = room.get_corner_pos();
+
+            if room.room_data.name == "Rails" {
+                // Remove vines at 15 58 15, 15 57 15, 15 56 15, 15 55 15, 15 54 15 (no rotation)
+                for y in 54..=58 {
+                    world.set_block_at(Blocks::Air, corner.x + 15, y, corner.z + 15);
+                }
+            } else if room.room_data.name == "Flags" {
+                // Remove vine at 55 88 47 (no rotation)
+                world.set_block_at(Blocks::Air, corner.x + 55, 88, corner.z + 47);
+            } else if room.room_data.name == "Grand Library" {
+                // Spawn torch at 47 86 15 (no rotation, like vines)
+                world.set_block_at(
+                    Blocks::Torch {
+                        direction: TorchDirection::Up,
+                    },
+                    corner.x + 47,
+                    86,
+                    corner.z + 15,
+                );
+            }
+        }
+
+        for door in &self.doors {
+            door.load_into_world(world, &DOOR_TYPE_BLOCKS);
+        }
+
+        if let Some(boss_room) = &mut self.boss_room {
+            boss_room.load_into_world(world);
+        }
+
+        Ok(())
+    }
+
     pub fn get_room_at(&self, x: i32, z: i32) -> Option<usize> {
         if x < DUNGEON_ORIGIN.0 || z < DUNGEON_ORIGIN.1 {
             return None;
@@ -482,7 +743,11 @@ impl Dungeon {
         let grid_x = ((x - DUNGEON_ORIGIN.0) / 32) as usize;
         let grid_z = ((z - DUNGEON_ORIGIN.1) / 32) as usize;
 
-        let entry = self.room_grid.get(grid_x + (grid_z * 6));
+        if !self.floor.is_in_grid(grid_x, grid_z) {
+            return None;
+        }
+
+        let entry = self.room_grid.get(self.floor.grid_index(grid_x, grid_z));
         entry.and_then(|e| *e)
     }
     
@@ -519,21 +784,248 @@ impl Dungeon {
         }
     }
 
-    // /// Check if a player is inside the boss room
-    // pub fn is_player_in_boss_room(&self, player: &Player) -> bool {
-    //     let player_x = player.position.x as i32;
-    //     let player_y = player.position.y as i32;
-    //     let player_z = player.position.z as i32;
-    //     
-    //     // Check if player is within boss room bounds using stored dimensions
-    //     // Boss room spans from Y=0 to Y=height (254), regardless of corner.y
-    //     player_x >= self.boss_room_corner.x 
-    //         && player_x < self.boss_room_corner.x + self.boss_room_width
-    //         && player_z >= self.boss_room_corner.z 
-    //         && player_z < self.boss_room_corner.z + self.boss_room_length
-    //         && player_y >= 0  // Boss room starts from Y=0 (bottom)
-    //         && player_y <= self.boss_room_height  // Up to the height of the room (254)
-    // }
+    /// Check if a player is inside the boss room
+    pub fn is_player_in_boss_room(&self, player: &Player) -> bool {
+        self.boss_room.as_ref().is_some_and(|boss_room| boss_room.contains(&player.position))
+    }
+
+    /// Starts the Watcher once the blood door is opened.
+    pub fn open_blood(&mut self) {
+        let world = &mut self.server_mut().world;
+        if let Some(room) = self.rooms.iter().find(|room| room.room_data.room_type == RoomType::Blood) {
+            self.blood_room.open(world, room);
+        }
+    }
+
+    /// Opens the portal to the boss room in the middle of the blood room,
+    /// and a second one in the fairy room if there is a boss room to go to.
+    pub fn clear_blood(&mut self) {
+        if !self.boss_portals.is_empty() {
+            return;
+        }
+
+        let server = self.server_mut();
+        let Some(room) = self.rooms.iter().find(|room| room.room_data.room_type == RoomType::Blood) else {
+            return;
+        };
+
+        let segment = &room.segments[0];
+        let x = segment.x as i32 * 32 + 15 + DUNGEON_ORIGIN.0;
+        let z = segment.z as i32 * 32 + 15 + DUNGEON_ORIGIN.1;
+        let y = find_floor_y(&server.world, x, z, room.room_data.bottom, room.room_data.bottom + room.room_data.height).unwrap_or(69);
+        self.boss_portals.push(BlockPos::new(x, y - 1, z));
+
+        let fairy_portal = self.fairy_room.portal.filter(|_| self.boss_room.is_some());
+        self.boss_portals.extend(fairy_portal);
+
+        // the portals replace the floor, so players walk into them instead of on top of them
+        for portal in self.boss_portals.iter() {
+            for dx in -1..=1 {
+                for dz in -1..=1 {
+                    server.world.set_block_at(Blocks::EndPortal, portal.x + dx, portal.y, portal.z + dz);
+                }
+            }
+        }
+
+        for player in server.world.players.values_mut() {
+            player.send_message("§cA portal has opened in the middle of the blood room.");
+            if fairy_portal.is_some() {
+                player.send_message("§dA portal has opened in the fairy room as well.");
+            }
+        }
+    }
+
+    /// Teleports the party into the boss room and switches to the boss phase,
+    /// if the floor has no boss room the run is finished instead.
+    pub fn enter_boss(&mut self) {
+        let DungeonState::Started { current_ticks } = self.state else {
+            return;
+        };
+        let Some(boss_room) = &self.boss_room else {
+            self.finish();
+            return;
+        };
+        self.teleport_to_boss(boss_room.spawn);
+        self.state = DungeonState::Boss { current_ticks, clear_ticks: current_ticks };
+    }
+
+    fn teleport_to_boss(&mut self, spawn: DVec3) {
+        let server = self.server_mut();
+        let mut players = std::mem::take(&mut server.world.players);
+        for player in players.values_mut() {
+            player.current_room_index = None;
+            player.position = spawn;
+            player.last_position = spawn;
+            player.write_packet(&PositionLook {
+                x: spawn.x,
+                y: spawn.y,
+                z: spawn.z,
+                yaw: player.yaw,
+                pitch: player.pitch,
+                flags: 0,
+            });
+
+            server.world.send_chunks_in_view(player);
+            player.send_message(&format!("§cYou have entered the lair of §l{}§c!", self.floor.boss));
+        }
+        server.world.players = players;
+    }
+
+    /// Hitting a blood mob deals [MELEE_DAMAGE] to its floor scaled health, anything else is passed on to the puzzles.
+    pub fn attack_entity(&mut self, player: &Player, entity_id: EntityId) {
+        if !matches!(self.state, DungeonState::Started { .. }) {
+            return;
+        }
+        let world = &mut self.server_mut().world;
+        if self.blood_room.damage_mob(world, entity_id, MELEE_DAMAGE) {
+            return;
+        }
+
+        let outcome = self.puzzles.iter_mut()
+            .enumerate()
+            .filter(|(_, room_puzzle)| room_puzzle.state == PuzzleState::InProgress)
+            .find_map(|(index, room_puzzle)| {
+                let room = &self.rooms[room_puzzle.room_index];
+                room_puzzle.puzzle.attack(world, room, player, entity_id).map(|outcome| (index, outcome))
+            });
+
+        if let Some((index, outcome)) = outcome {
+            self.finish_puzzle(index, outcome);
+        }
+    }
+
+    /// Opens the trap door whose lever this is, returns false if it isn't one.
+    pub fn pull_trap_lever(&mut self, block_pos: &BlockPos) -> bool {
+        let Some(room_index) = self.get_room_at(block_pos.x, block_pos.z) else {
+            return false;
+        };
+        let world = &mut self.server_mut().world;
+        self.rooms[room_index].hazards.iter_mut().any(|hazard| hazard.pull_lever(world, block_pos))
+    }
+
+    /// Passes a block click on to the puzzle in that room,
+    /// returns false if the puzzle didn't use it (or isn't in progress) so it can be handled as usual.
+    pub fn click_puzzle_block(&mut self, player: &Player, block_pos: &BlockPos) -> bool {
+        if !matches!(self.state, DungeonState::Started { .. }) {
+            return false;
+        }
+        let Some(room_index) = self.get_room_at(block_pos.x, block_pos.z) else {
+            return false;
+        };
+        let Some(index) = self.puzzles.iter().position(|room_puzzle| {
+            room_puzzle.room_index == room_index && room_puzzle.state == PuzzleState::InProgress
+        }) else {
+            return false;
+        };
+
+        let world = &mut self.server_mut().world;
+        match self.puzzles[index].puzzle.click(world, &self.rooms[room_index], player, block_pos) {
+            PuzzleClick::Ignored => false,
+            PuzzleClick::Handled(outcome) => {
+                if let Some(outcome) = outcome {
+                    self.finish_puzzle(index, outcome);
+                }
+                true
+            }
+        }
+    }
+
+    /// Passes player movement on to the puzzle in the room they are in.
+    pub fn puzzle_player_moved(&mut self, player: &Player) {
+        if !matches!(self.state, DungeonState::Started { .. }) {
+            return;
+        }
+        let Some(room_index) = self.get_room_at(player.position.x.floor() as i32, player.position.z.floor() as i32) else {
+            return;
+        };
+        let Some(index) = self.puzzles.iter().position(|room_puzzle| {
+            room_puzzle.room_index == room_index && room_puzzle.state == PuzzleState::InProgress
+        }) else {
+            return;
+        };
+
+        let below_feet = BlockPos {
+            x: player.position.x.floor() as i32,
+            y: (player.position.y - 0.1).floor() as i32,
+            z: player.position.z.floor() as i32,
+        };
+        let world = &mut self.server_mut().world;
+        if let Some(outcome) = self.puzzles[index].puzzle.moved(world, &self.rooms[room_index], player, &below_feet) {
+            self.finish_puzzle(index, outcome);
+        }
+    }
+
+    /// Starts the puzzle timers once their rooms get entered and ticks the puzzles that are still in progress.
+    fn tick_puzzles(&mut self) {
+        let world = &mut self.server_mut().world;
+        let mut outcomes = Vec::new();
+
+        for (index, room_puzzle) in self.puzzles.iter_mut().enumerate() {
+            let room = &self.rooms[room_puzzle.room_index];
+            if room_puzzle.started_at.is_none() && room.entered {
+                room_puzzle.started_at = Some(world.tick_count);
+            }
+            if room_puzzle.state != PuzzleState::InProgress {
+                continue;
+            }
+            if let Some(outcome) = room_puzzle.puzzle.tick(world, room) {
+                outcomes.push((index, outcome));
+            }
+        }
+
+        for (index, outcome) in outcomes {
+            self.finish_puzzle(index, outcome);
+        }
+    }
+
+    /// Solving a puzzle unlocks its chest, failing it keeps the chest locked for the rest of the run.
+    fn finish_puzzle(&mut self, index: usize, outcome: PuzzleOutcome) {
+        let world = &mut self.server_mut().world;
+        let room_puzzle = &mut self.puzzles[index];
+
+        let messages = match outcome {
+            PuzzleOutcome::Solved(action) => {
+                room_puzzle.state = PuzzleState::Solved;
+                if let Some(chest) = self.locked_chests.get_mut(&room_puzzle.chest_pos) {
+                    chest.locked = false;
+                }
+                let ticks = room_puzzle.started_at.map(|started_at| world.tick_count - started_at).unwrap_or(0);
+                vec![
+                    format!("§a§lPUZZLE SOLVED! {}§e! §4G§co§6o§ed§a §2j§bo§3b§5!", action),
+                    format!("§8[Puzzle] §7{} solved in §a{}§7.", room_puzzle.puzzle.name(), format_ticks(ticks)),
+                ]
+            }
+            PuzzleOutcome::Failed(action) => {
+                room_puzzle.state = PuzzleState::Failed;
+                vec![format!("§c§lPUZZLE FAIL! {}§e! §4Y§ci§6k§ee§as§2!", action)]
+            }
+        };
+
+        for player in world.players.values_mut() {
+            for message in &messages {
+                player.send_message(message);
+            }
+        }
+    }
+
+    /// Ticks the Watcher, once blood is cleared the portal takes the party into the boss room.
+    fn tick_blood(&mut self) {
+        if self.blood_room.tick(&mut self.server_mut().world, &self.floor) {
+            self.clear_blood();
+        }
+
+        let in_portal = self.server_mut().world.players.values().any(|player| {
+            self.boss_portals.iter().any(|portal| {
+                (player.position.x.floor() as i32 - portal.x).abs() <= 1
+                    && (player.position.z.floor() as i32 - portal.z).abs() <= 1
+                    && (player.position.y - portal.y as f64).abs() <= 2.0
+            })
+        });
+
+        if in_portal {
+            self.enter_boss();
+        }
+    }
 
     pub fn start_dungeon(&mut self) {
         let world = &mut self.server_mut().world;
@@ -567,6 +1059,7 @@ impl Dungeon {
             if room.room_data.room_type == crate::dungeon::room::room_data::RoomType::Entrance {
                 if !room.entered {
                     room.entered = true;
+                    room.entered_at = Some(0);
                     entrance_room_index = Some(room_index);
                 }
                 break; // Only one entrance room, so we can break after finding it
@@ -579,11 +1072,107 @@ impl Dungeon {
         }
     }
 
+    pub fn is_room_cleared(&self, room_index: usize) -> bool {
+        let room = &self.rooms[room_index];
+        match room.room_data.room_type {
+            RoomType::Blood => self.blood_room.cleared,
+            RoomType::Puzzle => self.puzzles.iter()
+                .find(|puzzle| puzzle.room_index == room_index)
+                .map(|puzzle| puzzle.state == PuzzleState::Solved)
+                .unwrap_or(room.entered),
+            _ => room.entered,
+        }
+    }
+
+    /// Fraction of the rooms that have been cleared, between 0 and 1.
+    pub fn cleared_fraction(&self) -> f64 {
+        if self.rooms.is_empty() {
+            return 0.0;
+        }
+        let cleared = (0..self.rooms.len()).filter(|index| self.is_room_cleared(*index)).count();
+        cleared as f64 / self.rooms.len() as f64
+    }
+
+    /// Found and total secrets across every room.
+    pub fn secrets(&self) -> (u32, u32) {
+        self.rooms.iter().fold((0, 0), |(found, total), room| {
+            (found + room.found_secrets as u32, total + room.room_data.secrets as u32)
+        })
+    }
+
+    pub fn crypts_exploded(&self) -> usize {
+        self.rooms.iter().map(|room| room.crypts_exploded).sum()
+    }
+
+    pub fn score(&self, current_ticks: u64) -> DungeonScore {
+        DungeonScore::calculate(self, current_ticks)
+    }
+
+    fn send_summary(&mut self, current_ticks: u64, clear_ticks: u64) {
+        let score = self.score(clear_ticks);
+        let (found_secrets, total_secrets) = self.secrets();
+
+        let mut splits = self.rooms.iter()
+            .filter_map(|room| room.entered_at.map(|ticks| (ticks, &room.room_data.name)))
+            .collect::<Vec<(u64, &String)>>();
+        splits.sort_by_key(|(ticks, _)| *ticks);
+
+        let mut lines = vec![
+            "§a§l▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬".to_string(),
+            format!("§c               The Catacombs §8- §e{}", self.floor.name),
+            format!("§f                  Team Score: §a{} §f({}§f)", score.total(), score.rank()),
+            format!("§7  Skill §a{} §7Exploration §a{} §7Speed §a{} §7Bonus §a{}", score.skill, score.exploration, score.speed, score.bonus),
+            format!("§7  Time: §a{} §7(Clear: §a{}§7) Secrets Found: §b{}/{} §7Crypts: §6{} §7Deaths: §c{}", format_run_time(current_ticks), format_run_time(clear_ticks), found_secrets, total_secrets, self.crypts_exploded(), self.deaths),
+            "§7  Room splits:".to_string(),
+        ];
+        for (ticks, name) in splits {
+            lines.push(format!("§8    {} §a{}", name, format_run_time(ticks)));
+        }
+        lines.push(lines[0].clone());
+
+        for player in self.server_mut().world.players.values_mut() {
+            for line in &lines {
+                player.send_message(line);
+            }
+        }
+    }
+
+    /// Ends the run from the boss room and sends the summary, if auto reroll is enabled a new dungeon is rolled after the delay.
+    /// Floors without a boss room end straight from the clear.
+    pub fn finish(&mut self) {
+        let (current_ticks, clear_ticks) = match self.state {
+            DungeonState::Boss { current_ticks, clear_ticks } => (current_ticks, clear_ticks),
+            DungeonState::Started { current_ticks } if self.boss_room.is_none() => (current_ticks, current_ticks),
+            _ => return,
+        };
+        self.state = DungeonState::Finished { current_ticks, clear_ticks };
+        self.send_summary(current_ticks, clear_ticks);
+
+        let server = self.server_mut();
+        if let Some(delay) = server.auto_reroll {
+            for player in server.world.players.values_mut() {
+                player.send_message(&format!("§eA new dungeon will be rolled in {} seconds.", delay / 20));
+            }
+
+            let floor = self.floor;
+            server.schedule(delay, move |server| {
+                let seed = DungeonSeed::random(&floor, &server.room_pool, &server.room_data_storage);
+                if let Err(error) = server.rebuild_dungeon(seed) {
+                    eprintln!("Failed to reroll the dungeon: {:#}", error);
+                }
+            });
+        }
+    }
+
     pub fn tick(&mut self) -> anyhow::Result<()> {
         let server = self.server_mut();
 
         match &mut self.state {
-            DungeonState::NotReady | DungeonState::Finished => {}
+            DungeonState::NotReady | DungeonState::Finished { .. } => {}
+
+            DungeonState::Boss { current_ticks, .. } => {
+                *current_ticks += 1;
+            }
 
             DungeonState::Starting { tick_countdown: tick } => {
                 *tick -= 1;
@@ -613,12 +1202,14 @@ impl Dungeon {
                     }
                     
                     // Send Mage stats message 20 ticks after "Starting in 1 second"
-                    for (_, player) in &mut server.world.players {
-                        player.send_message("§6Your Mage stats are doubled because");
-                        player.send_message("§6you are the only player using this");
-                        player.send_message("§6class!");
-                        player.send_message("§a[Mage] §fIntelligence §c500 §f-> §a750");
-                        player.send_message("§a[Mage] §fCooldown Reduction §c50% §f-> §a75%");
+                    if let Some((intelligence, cooldown_reduction)) = self.floor.mage_stats {
+                        for player in server.world.players.values_mut() {
+                            player.send_message("§6Your Mage stats are doubled because");
+                            player.send_message("§6you are the only player using this");
+                            player.send_message("§6class!");
+                            player.send_message(&format!("§a[Mage] §fIntelligence §c{} §f-> §a{}", intelligence, intelligence * 3 / 2));
+                            player.send_message(&format!("§a[Mage] §fCooldown Reduction §c{}% §f-> §a{}%", cooldown_reduction, cooldown_reduction * 3 / 2));
+                        }
                     }
                     
                     // Send Mort message with slight delay after Mage stats
@@ -712,6 +1303,7 @@ impl Dungeon {
                 // First pass: collect room indices for each player (store positions to avoid borrow conflicts)
                 // We need to calculate room indices outside the mutable borrow of self.state
                 let room_grid = &self.room_grid; // Get immutable reference to room_grid before using it
+                let floor = &self.floor;
                 let mut player_data: Vec<(u32, f64, f64)> = Vec::new();
                 for (player_id, player) in &server.world.players  {
                     player_data.push((*player_id, player.position.x, player.position.z));
@@ -728,7 +1320,11 @@ impl Dungeon {
                     } else {
                         let grid_x = ((x_i32 - crate::dungeon::dungeon::DUNGEON_ORIGIN.0) / 32) as usize;
                         let grid_z = ((z_i32 - crate::dungeon::dungeon::DUNGEON_ORIGIN.1) / 32) as usize;
-                        room_grid.get(grid_x + (grid_z * 6)).and_then(|e| *e)
+                        if floor.is_in_grid(grid_x, grid_z) {
+                            room_grid.get(floor.grid_index(grid_x, grid_z)).and_then(|e| *e)
+                        } else {
+                            None
+                        }
                     };
                     player_room_indices.push((player_id, room_index));
                 }
@@ -752,6 +1348,7 @@ impl Dungeon {
                         let room = self.rooms.get_mut(*room_index).unwrap();
                         if !room.entered {
                             room.entered = true;
+                            room.entered_at = Some(*current_ticks);
                             rooms_just_entered.insert(*room_index);
                             
                             // Collect entry secrets (schest, sess) to spawn immediately when room is entered
@@ -809,6 +1406,13 @@ impl Dungeon {
                         }
                     }
                 }
+
+                // Trap hazards tick on their own, fall floors have to come back even once everyone has left
+                for room in self.rooms.iter_mut() {
+                    for hazard in room.hazards.iter_mut() {
+                        hazard.tick(&mut server.world);
+                    }
+                }
                 
                 // Now process secrets for each player's room
                 for (player_id, room_index, player_aabb) in player_room_data {
@@ -1116,6 +1720,12 @@ impl Dungeon {
             }
         }
 
+        if let DungeonState::Started { .. } = self.state {
+            self.tick_puzzles();
+            self.tick_blood();
+            self.fairy_room.tick(&mut server.world);
+        }
+
         // Tick all rooms to process falling blocks
         for room in self.rooms.iter_mut() {
             room.tick(&mut server.world);
@@ -1158,3 +1768,36 @@ impl Dungeon {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(layout: &str) -> DungeonParseError {
+        match Dungeon::from_str(layout, FloorProfile::default(), &DeterministicHashMap::default(), &RoomPool::default()) {
+            Ok(_) => panic!("{layout} parsed into a dungeon"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn short_string_has_wrong_length() {
+        let floor = FloorProfile::default();
+        let layout = "0".repeat(floor.layout_length() - 1);
+
+        let error = parse_error(&layout);
+        assert!(matches!(error, DungeonParseError::WrongLength { length, expected } if length == layout.len() && expected == floor.layout_length()), "{error}");
+    }
+
+    #[test]
+    fn split_normal_room_is_disconnected() {
+        let floor = FloorProfile::default();
+        let mut rooms = vec!["00"; floor.room_count()];
+        rooms[floor.grid_index(0, 0)] = "07";
+        rooms[floor.grid_index(2, 0)] = "07";
+        let layout = rooms.concat() + &"9".repeat(floor.door_count());
+
+        let error = parse_error(&layout);
+        assert!(matches!(error, DungeonParseError::DisconnectedSegments { room_id: 7, .. }), "{error}");
+    }
+}
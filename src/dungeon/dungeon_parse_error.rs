@@ -0,0 +1,42 @@
+use crate::dungeon::room::room_data::{RoomShape, RoomType};
+use std::fmt::{Display, Formatter};
+
+/// Why a dungeon string or seed could not be turned into a [crate::dungeon::dungeon::Dungeon].
+///
+/// Positions are grid coordinates (0..width, 0..height of the floor), not world coordinates.
+#[derive(Debug)]
+pub enum DungeonParseError {
+    WrongLength { length: usize, expected: usize },
+    InvalidSeed(String),
+    InvalidRoomId { x: usize, z: usize, value: String },
+    DisconnectedSegments { room_id: usize, x: usize, z: usize },
+    ImpossibleShape { room_id: usize, x: usize, z: usize, segments: usize },
+    InvalidDoor { door_index: usize, from: (usize, usize), to: (usize, usize) },
+    RoomTypeNotAllowed { x: usize, z: usize, room_type: RoomType, floor: &'static str },
+    MissingRoomData { x: usize, z: usize, room_type: RoomType, shape: RoomShape },
+    MissingPinnedRoom { x: usize, z: usize, room_id: usize },
+    UnknownPinnedRoom { x: usize, z: usize, name: String },
+    PinnedRoomMismatch { x: usize, z: usize, name: String, room_type: RoomType, shape: RoomShape },
+    InvalidLayout(String),
+}
+
+impl Display for DungeonParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongLength { length, expected } => write!(f, "Dungeon string must be {} characters long, got {}", expected, length),
+            Self::InvalidSeed(reason) => write!(f, "Invalid dungeon seed: {}", reason),
+            Self::InvalidRoomId { x, z, value } => write!(f, "Invalid room id '{}' at {},{}", value, x, z),
+            Self::DisconnectedSegments { room_id, x, z } => write!(f, "Room {} has a segment at {},{} that isn't connected to the rest of it", room_id, x, z),
+            Self::ImpossibleShape { room_id, x, z, segments } => write!(f, "Room {} at {},{} has an impossible shape ({} segments)", room_id, x, z, segments),
+            Self::InvalidDoor { door_index, from, to } => write!(f, "Door {} between {},{} and {},{} does not connect two different rooms", door_index, from.0, from.1, to.0, to.1),
+            Self::RoomTypeNotAllowed { x, z, room_type, floor } => write!(f, "{} does not have {:?} rooms, found one at {},{}", floor, room_type, x, z),
+            Self::MissingRoomData { x, z, room_type, shape } => write!(f, "No room data for a {:?} room with shape {:?} at {},{}", room_type, shape, x, z),
+            Self::MissingPinnedRoom { x, z, room_id } => write!(f, "Pinned room id {} at {},{} does not exist", room_id, x, z),
+            Self::UnknownPinnedRoom { x, z, name } => write!(f, "Pinned room '{}' at {},{} does not exist", name, x, z),
+            Self::PinnedRoomMismatch { x, z, name, room_type, shape } => write!(f, "Pinned room {} does not fit the {:?} room with shape {:?} at {},{}", name, room_type, shape, x, z),
+            Self::InvalidLayout(reason) => write!(f, "Invalid dungeon layout: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DungeonParseError {}
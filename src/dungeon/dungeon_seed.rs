@@ -0,0 +1,247 @@
+use crate::dungeon::dungeon_parse_error::DungeonParseError;
+use crate::dungeon::floor_profile::FloorProfile;
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::generator::generate_layout;
+use crate::dungeon::room::room_data::RoomData;
+use crate::dungeon::room::room_pool::RoomPool;
+use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
+use crate::utils::hasher::rapidhash::rapidhash_nano;
+use crate::utils::seeded_rng::SeededRng;
+use anyhow::{bail, Context};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+// A shareable seed for a dungeon, containing everything needed to rebuild the exact same dungeon:
+// the floor, the layout, the seed for the SeededRng (room picks, rotations, secrets) and optionally pinned room ids.
+//
+// Encoded as the prefix followed by url safe base64 of:
+// [version: u8][floor index: u8][rng seed: u64][room ids: room count x u8][door types: door count / 2 x u8 (two per byte)][pinned count: u8][pinned: (grid index: u8, room id: u16)...][checksum: u32]
+//
+// Version 1 seeds have no floor byte and are always F7.
+
+pub const SEED_PREFIX: &str = "RC";
+pub const SEED_VERSION: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DungeonSeed {
+    pub floor: FloorProfile,
+    /// The legacy dungeon string (room ids then door types, see [FloorProfile]).
+    pub layout: String,
+    pub rng_seed: u64,
+    /// Grid index (x + z * floor width) to room data id, for rooms that should not be picked randomly.
+    pub pinned_rooms: Vec<(usize, usize)>,
+}
+
+impl DungeonSeed {
+    pub fn new(floor: FloorProfile, layout: String, rng_seed: u64) -> DungeonSeed {
+        DungeonSeed {
+            floor,
+            layout,
+            rng_seed,
+            pinned_rooms: Vec::new(),
+        }
+    }
+
+    /// A new seed with a freshly generated layout for the floor, with space left for the rooms pinned in the pool.
+    pub fn random(floor: &FloorProfile, room_pool: &RoomPool, room_data_storage: &DeterministicHashMap<usize, RoomData>) -> DungeonSeed {
+        let rng_seed: u64 = rand::random();
+        // the layout is generated with the seeded rng as well, so it can be reproduced from the rng seed
+        SeededRng::set_seed(rng_seed);
+        let pins = room_pool.get_pinned_cells(floor, room_data_storage);
+        DungeonSeed::new(*floor, generate_layout(floor, &pins), rng_seed)
+    }
+
+    /// Pins every room of the dungeon built from this seed. Any room pool setting changes which rooms get picked,
+    /// so this keeps the seed building the same rooms on a server with a different (or no) pool.
+    pub fn pin_rooms(&mut self, dungeon: &Dungeon, room_data_storage: &DeterministicHashMap<usize, RoomData>) {
+        for room in dungeon.rooms.iter() {
+            let grid_indices = room.segments.iter()
+                .map(|segment| self.floor.grid_index(segment.x, segment.z))
+                .collect::<Vec<usize>>();
+            if self.pinned_rooms.iter().any(|(grid_index, _)| grid_indices.contains(grid_index)) {
+                continue;
+            }
+            let room_id = room_data_storage.iter()
+                .find(|(_, data)| data.id == room.room_data.id && data.name == room.room_data.name)
+                .map(|(room_id, _)| *room_id);
+            if let Some(room_id) = room_id {
+                self.pinned_rooms.push((grid_indices[0], room_id));
+            }
+        }
+    }
+
+    /// Reads either an encoded seed or a legacy dungeon string for the floor, which gets a random rng seed.
+    pub fn parse(value: &str, floor: FloorProfile) -> Result<DungeonSeed, DungeonParseError> {
+        if DungeonSeed::is_seed_string(value) {
+            DungeonSeed::decode(value).map_err(|e| DungeonParseError::InvalidSeed(e.to_string()))
+        } else {
+            Ok(DungeonSeed::new(floor, value.trim().to_string(), rand::random()))
+        }
+    }
+
+    pub fn is_seed_string(value: &str) -> bool {
+        value.starts_with(SEED_PREFIX)
+    }
+
+    pub fn encode(&self) -> String {
+        let layout = self.layout.as_bytes();
+        let room_count = self.floor.room_count();
+        let door_count = self.floor.door_count();
+        let mut bytes = Vec::with_capacity(2 + 8 + room_count + door_count.div_ceil(2) + 1 + self.pinned_rooms.len() * 3 + 4);
+
+        bytes.push(SEED_VERSION);
+        bytes.push(self.floor.index() as u8);
+        bytes.extend_from_slice(&self.rng_seed.to_be_bytes());
+
+        for i in 0..room_count {
+            let id = self.layout.get(i * 2..i * 2 + 2).and_then(|id| id.parse::<u8>().ok()).unwrap_or(0);
+            bytes.push(id);
+        }
+
+        // Door types are single digits, anything that isn't one of the known types just means no door.
+        // An odd door count gets padded with an extra missing door.
+        let door_digit = |index: usize| match layout.get(room_count * 2 + index).filter(|_| index < door_count) {
+            Some(char @ b'0'..=b'3') => char - b'0',
+            _ => 9,
+        };
+        for i in (0..door_count).step_by(2) {
+            bytes.push(door_digit(i) << 4 | door_digit(i + 1));
+        }
+
+        bytes.push(self.pinned_rooms.len() as u8);
+        for (grid_index, room_id) in self.pinned_rooms.iter() {
+            bytes.push(*grid_index as u8);
+            bytes.extend_from_slice(&(*room_id as u16).to_be_bytes());
+        }
+
+        let checksum = rapidhash_nano(0, &bytes) as u32;
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+
+        format!("{}{}", SEED_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub fn decode(value: &str) -> anyhow::Result<DungeonSeed> {
+        let Some(encoded) = value.trim().strip_prefix(SEED_PREFIX) else {
+            bail!("Dungeon seed must start with {}", SEED_PREFIX);
+        };
+        let bytes = URL_SAFE_NO_PAD.decode(encoded)?;
+
+        if bytes.len() < 5 {
+            bail!("Dungeon seed is too short");
+        }
+        let (payload, checksum) = bytes.split_at(bytes.len() - 4);
+        if rapidhash_nano(0, payload) as u32 != u32::from_be_bytes(checksum.try_into()?) {
+            bail!("Dungeon seed checksum does not match, it was likely copied incorrectly");
+        }
+
+        let (floor, header_length) = match payload[0] {
+            1 => (FloorProfile::default(), 1),
+            SEED_VERSION => {
+                let index = *payload.get(1).context("Dungeon seed is too short")?;
+                let floor = FloorProfile::from_index(index as usize).with_context(|| format!("Dungeon seed has an unknown floor {}", index))?;
+                (floor, 2)
+            }
+            version => bail!("Unsupported dungeon seed version {} (expected {})", version, SEED_VERSION),
+        };
+
+        let room_count = floor.room_count();
+        let door_count = floor.door_count();
+        let rooms_start = header_length + 8;
+        let pinned_start = rooms_start + room_count + door_count.div_ceil(2);
+        if payload.len() < pinned_start + 1 {
+            bail!("Dungeon seed is too short");
+        }
+
+        let rng_seed = u64::from_be_bytes(payload[header_length..rooms_start].try_into()?);
+
+        let mut layout = String::with_capacity(floor.layout_length());
+        for id in &payload[rooms_start..rooms_start + room_count] {
+            if *id > 99 {
+                bail!("Dungeon seed contains an invalid room id {}", id);
+            }
+            layout.push_str(&format!("{:02}", id));
+        }
+        for byte in &payload[rooms_start + room_count..pinned_start] {
+            for digit in [byte >> 4, byte & 0xF] {
+                layout.push(char::from(b'0' + digit.min(9)));
+            }
+        }
+        layout.truncate(floor.layout_length());
+
+        let pinned_count = payload[pinned_start] as usize;
+        let pinned_data = &payload[pinned_start + 1..];
+        if pinned_data.len() != pinned_count * 3 {
+            bail!("Dungeon seed has {} pinned rooms but {} bytes of pinned data", pinned_count, pinned_data.len());
+        }
+
+        let pinned_rooms = pinned_data.chunks_exact(3)
+            .map(|chunk| (chunk[0] as usize, u16::from_be_bytes([chunk[1], chunk[2]]) as usize))
+            .collect::<Vec<(usize, usize)>>();
+
+        Ok(DungeonSeed {
+            floor,
+            layout,
+            rng_seed,
+            pinned_rooms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::seeded_rng::SeededRng;
+
+    fn generated_seed(floor: FloorProfile) -> DungeonSeed {
+        SeededRng::set_seed(42);
+        let layout = generate_layout(&floor, &[]);
+        DungeonSeed::new(floor, layout, 0x0123_4567_89AB_CDEF)
+    }
+
+    fn payload_of(seed: &str) -> Vec<u8> {
+        let mut bytes = URL_SAFE_NO_PAD.decode(seed.strip_prefix(SEED_PREFIX).unwrap()).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        bytes
+    }
+
+    fn seal(mut payload: Vec<u8>) -> String {
+        let checksum = rapidhash_nano(0, &payload) as u32;
+        payload.extend_from_slice(&checksum.to_be_bytes());
+        format!("{}{}", SEED_PREFIX, URL_SAFE_NO_PAD.encode(payload))
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for floor in [FloorProfile::default(), FloorProfile::from_index(2).unwrap()] {
+            let mut seed = generated_seed(floor);
+            seed.pinned_rooms = vec![(3, 120), (20, 7)];
+
+            let decoded = DungeonSeed::decode(&seed.encode()).unwrap();
+            assert_eq!(decoded, seed);
+        }
+    }
+
+    #[test]
+    fn v1_seed_decodes_as_f7() {
+        let seed = generated_seed(FloorProfile::default());
+        let mut payload = payload_of(&seed.encode());
+        payload.remove(1);
+        payload[0] = 1;
+
+        let decoded = DungeonSeed::decode(&seal(payload)).unwrap();
+        assert_eq!(decoded.floor, FloorProfile::default());
+        assert_eq!(decoded.layout, seed.layout);
+        assert_eq!(decoded.rng_seed, seed.rng_seed);
+    }
+
+    #[test]
+    fn checksum_mismatch_is_rejected() {
+        let seed = generated_seed(FloorProfile::default());
+        let encoded = seed.encode();
+        let mut bytes = URL_SAFE_NO_PAD.decode(encoded.strip_prefix(SEED_PREFIX).unwrap()).unwrap();
+        bytes[12] ^= 1;
+
+        let error = DungeonSeed::decode(&format!("{}{}", SEED_PREFIX, URL_SAFE_NO_PAD.encode(bytes))).unwrap_err();
+        assert!(error.to_string().contains("checksum"), "{error}");
+    }
+}
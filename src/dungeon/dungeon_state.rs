@@ -7,5 +7,25 @@ pub enum DungeonState {
     Started {
         current_ticks: u64 
     }, 
-    Finished,
+    // the party has been teleported into the boss room, the timer keeps counting from the clear
+    Boss {
+        current_ticks: u64,
+        clear_ticks: u64,
+    },
+    // the run is over, the time it ended at is kept for the sidebar
+    Finished {
+        current_ticks: u64,
+        clear_ticks: u64,
+    },
+}
+
+impl DungeonState {
+    /// The time the speed score is taken from, the clear time once blood has been cleared and the party went into the boss room.
+    pub fn clear_ticks(&self) -> Option<u64> {
+        match self {
+            DungeonState::Started { current_ticks } => Some(*current_ticks),
+            DungeonState::Boss { clear_ticks, .. } | DungeonState::Finished { clear_ticks, .. } => Some(*clear_ticks),
+            _ => None,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,159 @@
+use crate::dungeon::boss_room::find_floor_y;
+use crate::dungeon::dungeon::DUNGEON_ORIGIN;
+use crate::dungeon::room::room::Room;
+use crate::net::packets::packet_buffer::PacketBuffer;
+use crate::net::protocol::play::clientbound::{EntityEquipment, Particles};
+use crate::net::var_int::VarInt;
+use crate::server::block::block_position::BlockPos;
+use crate::server::entity::entity::{Entity, EntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::items::item_stack::ItemStack;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::nbt::nbt::{NBTNode, NBT};
+use crate::server::utils::nbt::serialize::TAG_COMPOUND_ID;
+use crate::server::utils::particles::ParticleTypes;
+use crate::server::world::World;
+use std::collections::HashMap;
+
+// The fairy room: standing in the fountain in the middle of the room heals players over time,
+// and a fairy soul floats above it.
+//
+// Once blood is cleared a second portal to the boss room opens in here, so the party doesn't have to walk back to blood.
+
+/// How far from the middle of the room the fountain heals, horizontally.
+const FOUNTAIN_RADIUS: f64 = 4.0;
+const HEAL_INTERVAL: u64 = 20;
+/// Percent of max health healed every HEAL_INTERVAL.
+const HEAL_PERCENT: i32 = 10;
+/// Where the boss portal opens, relative to the room.
+const PORTAL_POS: BlockPos = BlockPos { x: 15, y: 0, z: 8 };
+
+const FAIRY_SOUL_TEXTURE: &str = "eyJ0ZXh0dXJlcyI6eyJTS0lOIjp7InVybCI6Imh0dHA6Ly90ZXh0dXJlcy5taW5lY3JhZnQubmV0L3RleHR1cmUvYjk2OTIzYWQyNDczMTAwMDdmNmFlNWQzMjZkODQ3YWQ1Mzg2NGNmMTZjMzU2NWExODFkYzhlNmIyMGJlMjM4NyJ9fX0=";
+
+#[derive(Default)]
+pub struct FairyRoom {
+    /// Middle of the fountain, on top of the floor. None if the dungeon has no fairy room.
+    fountain: Option<DVec3>,
+    /// The floor block in the middle of where the boss portal goes.
+    pub portal: Option<BlockPos>,
+}
+
+impl FairyRoom {
+    /// Finds the fountain and spawns the fairy soul above it.
+    pub fn load(&mut self, world: &mut World, room: &Room) {
+        let (bottom, top) = (room.room_data.bottom, room.room_data.bottom + room.room_data.height);
+
+        let segment = &room.segments[0];
+        let x = segment.x as i32 * 32 + 15 + DUNGEON_ORIGIN.0;
+        let z = segment.z as i32 * 32 + 15 + DUNGEON_ORIGIN.1;
+        let y = find_floor_y(world, x, z, bottom, top).unwrap_or(69);
+        let fountain = DVec3::new(x as f64 + 0.5, y as f64, z as f64 + 0.5);
+        self.fountain = Some(fountain);
+
+        let portal = room.get_world_block_pos(&PORTAL_POS);
+        let portal_y = find_floor_y(world, portal.x, portal.z, bottom, top).unwrap_or(69);
+        self.portal = Some(BlockPos::new(portal.x, portal_y - 1, portal.z));
+
+        let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
+        metadata.is_invisible = true;
+        metadata.ai_disabled = true;
+        if let Err(e) = world.spawn_entity(fountain + DVec3::new(0.0, 1.5, 0.0), metadata, FairySoulImpl { base_y: fountain.y + 1.5 }) {
+            eprintln!("Failed to spawn the fairy soul: {}", e);
+        }
+    }
+
+    /// Heals everyone standing in the fountain.
+    pub fn tick(&mut self, world: &mut World) {
+        let Some(fountain) = self.fountain else {
+            return;
+        };
+        if !world.tick_count.is_multiple_of(HEAL_INTERVAL) {
+            return;
+        }
+
+        for player in world.players.values_mut() {
+            let (dx, dz) = (player.position.x - fountain.x, player.position.z - fountain.z);
+            if dx * dx + dz * dz > FOUNTAIN_RADIUS * FOUNTAIN_RADIUS || (player.position.y - fountain.y).abs() > 3.0 {
+                continue;
+            }
+            let stats = &mut player.dungeon_stats;
+            if stats.hp >= stats.max_hp {
+                continue;
+            }
+            stats.hp = (stats.hp + stats.max_hp * HEAL_PERCENT / 100).min(stats.max_hp);
+
+            let position = player.position;
+            player.write_packet(&Particles {
+                particle_id: ParticleTypes::Heart.get_id(),
+                long_distance: false,
+                x: position.x as f32,
+                y: position.y as f32 + 2.0,
+                z: position.z as f32,
+                offset_x: 0.3,
+                offset_y: 0.2,
+                offset_z: 0.3,
+                speed: 0.0,
+                count: 3,
+            });
+        }
+    }
+}
+
+fn fairy_soul_skull() -> ItemStack {
+    let mut texture = HashMap::new();
+    texture.insert("Value".into(), NBTNode::String(FAIRY_SOUL_TEXTURE.to_string()));
+
+    ItemStack {
+        item: 397, // Player head
+        stack_size: 1,
+        metadata: 3,
+        tag_compound: Some(NBT::with_nodes(vec![
+            NBT::compound("SkullOwner", vec![
+                NBT::string("Name", ""),
+                NBT::string("Id", "57a4c8dc-9b8e-3d41-80da-a608901a6147"),
+                NBT::compound("Properties", vec![
+                    NBT::list("textures", TAG_COMPOUND_ID, vec![NBTNode::Compound(texture)]),
+                ]),
+            ]),
+        ])),
+    }
+}
+
+/// An invisible armor stand wearing the fairy soul head, bobs up and down and spins like the ones on the hub.
+pub struct FairySoulImpl {
+    base_y: f64,
+}
+
+impl EntityImpl for FairySoulImpl {
+    fn spawn(&mut self, entity: &mut Entity, buffer: &mut PacketBuffer) {
+        buffer.write_packet(&EntityEquipment {
+            entity_id: VarInt(entity.id),
+            item_slot: 4, // Helmet slot
+            item_stack: Some(fairy_soul_skull()),
+        });
+    }
+
+    fn tick(&mut self, entity: &mut Entity, _: &mut PacketBuffer) {
+        let ticks = entity.ticks_existed as f64;
+        entity.position.y = self.base_y + (ticks / 10.0).sin() * 0.2;
+        entity.yaw = (entity.yaw + 6.0) % 360.0;
+
+        if entity.ticks_existed.is_multiple_of(10) {
+            let position = entity.position;
+            for player in entity.world_mut().players.values_mut() {
+                player.write_packet(&Particles {
+                    particle_id: ParticleTypes::SpellWitch.get_id(),
+                    long_distance: false,
+                    x: position.x as f32,
+                    y: position.y as f32 + 1.8,
+                    z: position.z as f32,
+                    offset_x: 0.2,
+                    offset_y: 0.2,
+                    offset_z: 0.2,
+                    speed: 0.0,
+                    count: 4,
+                });
+            }
+        }
+    }
+}
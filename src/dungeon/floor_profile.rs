@@ -0,0 +1,188 @@
+use crate::dungeon::dungeon::DUNGEON_ORIGIN;
+use crate::dungeon::room::room_data::RoomType;
+
+// Everything that changes between floors: how big the grid is, which special rooms get generated,
+// how strong the mobs are and which boss is at the end.
+//
+// The grid is never bigger than 6x6, smaller floors just use the top left part of it.
+// Dungeon strings are laid out the same way for every floor, just with fewer rooms and doors:
+// width * height two digit room ids (row by row) followed by a door type for every door in door_positions order.
+
+pub const MAX_GRID_SIZE: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloorProfile {
+    /// Short name, like F7 or M3.
+    pub name: &'static str,
+    pub floor: u8,
+    pub master_mode: bool,
+
+    pub width: usize,
+    pub height: usize,
+
+    pub puzzles: usize,
+    pub trap: bool,
+    pub yellow: bool,
+
+    /// Multiplier for mob health and damage, relative to F1.
+    pub mob_strength: f64,
+    pub boss: &'static str,
+
+    /// Fraction of the total secrets needed for full secret points in the exploration score.
+    pub secrets_required: f64,
+    /// Seconds the run can take before the speed score starts dropping.
+    pub speed_limit: u64,
+    /// Intelligence and cooldown reduction (in percent) a solo Mage starts with before they're boosted,
+    /// none on floors where the start message isn't sent.
+    pub mage_stats: Option<(u32, u32)>,
+}
+
+const fn floor(name: &'static str, (width, height): (usize, usize), puzzles: usize, trap: bool, mob_strength: f64, boss: &'static str) -> FloorProfile {
+    let floor = name.as_bytes()[1] - b'0';
+    let master_mode = name.as_bytes()[0] == b'M';
+    FloorProfile {
+        name,
+        floor,
+        master_mode,
+        width,
+        height,
+        puzzles,
+        trap,
+        yellow: true,
+        mob_strength,
+        boss,
+        secrets_required: match (master_mode, floor) {
+            (true, _) | (false, 7) => 1.0,
+            (false, 6) => 0.85,
+            (false, floor) => 0.2 + floor as f64 * 0.1,
+        },
+        speed_limit: match (master_mode, floor) {
+            (_, 7) => 840,
+            (false, 6) => 720,
+            (true, 6) | (false, _) => 600,
+            (true, _) => 480,
+        },
+        mage_stats: match floor {
+            7 => Some((500, 50)),
+            _ => None,
+        },
+    }
+}
+
+pub const FLOOR_PROFILES: [FloorProfile; 14] = [
+    floor("F1", (4, 5), 1, false, 1.0, "Bonzo"),
+    floor("F2", (5, 5), 2, false, 1.5, "Scarf"),
+    floor("F3", (5, 5), 2, true, 2.0, "The Professor"),
+    floor("F4", (6, 5), 3, true, 3.0, "Thorn"),
+    floor("F5", (6, 6), 3, true, 4.0, "Livid"),
+    floor("F6", (6, 6), 3, true, 5.0, "Sadan"),
+    floor("F7", (6, 6), 3, true, 7.0, "Necron"),
+    floor("M1", (4, 5), 1, false, 12.0, "Bonzo"),
+    floor("M2", (5, 5), 2, false, 15.0, "Scarf"),
+    floor("M3", (5, 5), 2, true, 20.0, "The Professor"),
+    floor("M4", (6, 5), 3, true, 28.0, "Thorn"),
+    floor("M5", (6, 6), 3, true, 36.0, "Livid"),
+    floor("M6", (6, 6), 3, true, 45.0, "Sadan"),
+    floor("M7", (6, 6), 3, true, 60.0, "Necron"),
+];
+
+impl Default for FloorProfile {
+    fn default() -> Self {
+        FLOOR_PROFILES[6]
+    }
+}
+
+impl FloorProfile {
+    pub fn from_name(name: &str) -> Option<FloorProfile> {
+        FLOOR_PROFILES.iter().find(|profile| profile.name.eq_ignore_ascii_case(name)).copied()
+    }
+
+    /// Position in [FLOOR_PROFILES], used to store the floor in seeds.
+    pub fn index(&self) -> usize {
+        (self.floor as usize - 1) + if self.master_mode { 7 } else { 0 }
+    }
+
+    pub fn from_index(index: usize) -> Option<FloorProfile> {
+        FLOOR_PROFILES.get(index).copied()
+    }
+
+    pub fn room_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn door_count(&self) -> usize {
+        (self.width - 1) * self.height + self.width * (self.height - 1)
+    }
+
+    /// Length of a dungeon string for this floor.
+    pub fn layout_length(&self) -> usize {
+        self.room_count() * 2 + self.door_count()
+    }
+
+    pub fn grid_index(&self, x: usize, z: usize) -> usize {
+        x + z * self.width
+    }
+
+    pub fn is_in_grid(&self, x: usize, z: usize) -> bool {
+        x < self.width && z < self.height
+    }
+
+    /// Which special room types can show up in this floor.
+    pub fn allowed_room_types(&self) -> Vec<RoomType> {
+        let mut types = vec![RoomType::Entrance, RoomType::Fairy, RoomType::Blood, RoomType::Normal];
+        if self.puzzles > 0 {
+            types.push(RoomType::Puzzle);
+        }
+        if self.trap {
+            types.push(RoomType::Trap);
+        }
+        if self.yellow {
+            types.push(RoomType::Yellow);
+        }
+        types
+    }
+
+    /// The world positions of every door, in the order they appear in the dungeon string.
+    ///
+    /// Each row of the grid has width - 1 doors between horizontal neighbours followed by width doors to the row below.
+    pub fn door_positions(&self) -> Vec<(i32, i32)> {
+        let mut positions = Vec::with_capacity(self.door_count());
+
+        for z in 0..self.height as i32 {
+            for x in 0..self.width as i32 - 1 {
+                positions.push((DUNGEON_ORIGIN.0 + x * 32 + 31, DUNGEON_ORIGIN.1 + z * 32 + 15));
+            }
+            if z < self.height as i32 - 1 {
+                for x in 0..self.width as i32 {
+                    positions.push((DUNGEON_ORIGIN.0 + x * 32 + 15, DUNGEON_ORIGIN.1 + z * 32 + 31));
+                }
+            }
+        }
+        positions
+    }
+
+    /// Index into [FloorProfile::door_positions] of the door between two adjacent cells.
+    pub fn door_index_between(&self, a: (usize, usize), b: (usize, usize)) -> usize {
+        let (x, z) = (a.0.min(b.0), a.1.min(b.1));
+        let row = z * (self.width * 2 - 1);
+        if a.1 == b.1 {
+            row + x
+        } else {
+            row + self.width - 1 + x
+        }
+    }
+
+    /// The two grid cells a door sits between.
+    pub fn door_cells(&self, door_index: usize) -> ((usize, usize), (usize, usize)) {
+        let row_length = self.width * 2 - 1;
+        let z = door_index / row_length;
+        let x = door_index % row_length;
+
+        if x < self.width - 1 {
+            ((x, z), (x + 1, z))
+        } else {
+            let x = x - (self.width - 1);
+            ((x, z), (x, z + 1))
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use crate::dungeon::floor_profile::FloorProfile;
+use crate::dungeon::room::room_data::{RoomShape, RoomType};
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::Rng;
+use std::collections::VecDeque;
+
+// Generates dungeon strings in the same format Dungeon::from_str parses:
+// a two digit room id for every cell of the floors grid (row by row) followed by the door types,
+// see FloorProfile for the exact layout.
+//
+// All randomness comes from the seeded rng, so setting the seed before calling
+// generate_layout will always produce the same layout.
+//
+// Rooms pinned with the room pool need their cell to end up with the same type and shape as the pinned room,
+// normal rooms get their shape placed over the cell before the rest of the grid is filled,
+// and layouts are retried until every pin fits (a 1x1 rooms shape depends on its doors, special rooms are placed randomly).
+
+const ENTRANCE_ID: u8 = 1;
+const FAIRY_ID: u8 = 2;
+const BLOOD_ID: u8 = 3;
+const PUZZLE_ID: u8 = 4;
+const TRAP_ID: u8 = 5;
+const YELLOW_ID: u8 = 6;
+const FIRST_NORMAL_ID: u8 = 7;
+
+const DOOR_NORMAL: u8 = b'0';
+const DOOR_WITHER: u8 = b'1';
+const DOOR_BLOOD: u8 = b'2';
+const DOOR_ENTRANCE: u8 = b'3';
+const DOOR_NONE: u8 = b'9';
+
+// How many times a layout is retried before giving up, special room placement can cut off parts of the grid.
+const MAX_ATTEMPTS: usize = 256;
+// Retries for fitting pinned rooms, after this the pins are ignored and building the dungeon reports the mismatch.
+const MAX_PINNED_ATTEMPTS: usize = 4096;
+
+type Cell = (usize, usize);
+
+// Cell offsets for every shape a normal room can take, along with how likely they are to be picked.
+const SHAPES: [(&[&[Cell]], u32); 6] = [
+    (&[&[(0, 0), (1, 0), (0, 1), (1, 1)]], 12), // 2x2
+    (&[
+        &[(0, 0), (1, 0), (0, 1)],
+        &[(0, 0), (1, 0), (1, 1)],
+        &[(0, 0), (0, 1), (1, 1)],
+        &[(1, 0), (0, 1), (1, 1)],
+    ], 12), // L
+    (&[&[(0, 0), (1, 0), (2, 0), (3, 0)], &[(0, 0), (0, 1), (0, 2), (0, 3)]], 7), // 1x4
+    (&[&[(0, 0), (1, 0), (2, 0)], &[(0, 0), (0, 1), (0, 2)]], 12), // 1x3
+    (&[&[(0, 0), (1, 0)], &[(0, 0), (0, 1)]], 27), // 1x2
+    (&[&[(0, 0)]], 30), // 1x1
+];
+
+/// A cell that has to end up as part of a room with this type and shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinnedCell {
+    pub cell: Cell,
+    pub room_type: RoomType,
+    pub shape: RoomShape,
+}
+
+fn distance(a: Cell, b: Cell) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+struct Layout {
+    width: usize,
+    height: usize,
+    // Room index for every cell, None if the cell is empty
+    grid: Vec<Option<usize>>,
+    // Room id used in the dungeon string, per room index
+    room_ids: Vec<u8>,
+    room_cells: Vec<Vec<Cell>>,
+    // (room a, room b, cell in a, cell in b)
+    doors: Vec<(usize, usize, Cell, Cell)>,
+}
+
+impl Layout {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            grid: vec![None; width * height],
+            room_ids: Vec::new(),
+            room_cells: Vec::new(),
+            doors: Vec::new(),
+        }
+    }
+
+    fn index(&self, (x, z): Cell) -> usize {
+        x + z * self.width
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        (0..self.width * self.height)
+            .map(|i| (i % self.width, i / self.width))
+            .collect()
+    }
+
+    fn neighbours(&self, (x, z): Cell) -> impl Iterator<Item = Cell> + use<> {
+        let (width, height) = (self.width, self.height);
+        [
+            (x.checked_sub(1), Some(z)),
+            (Some(x + 1), Some(z)),
+            (Some(x), z.checked_sub(1)),
+            (Some(x), Some(z + 1)),
+        ]
+            .into_iter()
+            .filter_map(|(x, z)| Some((x?, z?)))
+            .filter(move |&(x, z)| x < width && z < height)
+    }
+
+    fn room_at(&self, cell: Cell) -> Option<usize> {
+        self.grid[self.index(cell)]
+    }
+
+    fn add_room(&mut self, id: u8, cells: Vec<Cell>) -> usize {
+        let index = self.room_ids.len();
+        for cell in cells.iter() {
+            let cell_index = self.index(*cell);
+            self.grid[cell_index] = Some(index);
+        }
+        self.room_ids.push(id);
+        self.room_cells.push(cells);
+        index
+    }
+
+    fn is_free(&self, cell: Cell) -> bool {
+        self.room_at(cell).is_none()
+    }
+}
+
+/// Generates a new dungeon string for the floor using the seeded rng, fitting the pinned cells if possible.
+pub fn generate_layout(floor: &FloorProfile, pins: &[PinnedCell]) -> String {
+    if !pins.is_empty() {
+        for _ in 0..MAX_PINNED_ATTEMPTS {
+            if let Some(layout) = try_generate(floor, pins) {
+                return layout
+            }
+        }
+    }
+    for _ in 0..MAX_ATTEMPTS {
+        if let Some(layout) = try_generate(floor, &[]) {
+            return layout
+        }
+    }
+    // Every attempt has been cut off somehow, the floor must be asking for more special rooms than can fit
+    let fallback = FloorProfile { puzzles: 1, trap: false, yellow: false, ..*floor };
+    try_generate(&fallback, &[]).expect("Failed to generate a dungeon layout")
+}
+
+fn try_generate(floor: &FloorProfile, pins: &[PinnedCell]) -> Option<String> {
+    let mut rng = seeded_rng();
+    let mut layout = Layout::new(floor.width, floor.height);
+
+    let all_cells = layout.cells();
+
+    let edge_cells = all_cells.iter()
+        .copied()
+        .filter(|&(x, z)| x == 0 || z == 0 || x == floor.width - 1 || z == floor.height - 1)
+        .collect::<Vec<Cell>>();
+
+    // Entrance sits on the edge of the map, blood is as far away from it as possible.
+    let entrance_cell = *edge_cells.choose(&mut rng)?;
+    let max_distance = edge_cells.iter().map(|cell| distance(*cell, entrance_cell)).max()?;
+    let blood_cell = *edge_cells.iter()
+        .copied()
+        .filter(|cell| distance(*cell, entrance_cell) == max_distance)
+        .collect::<Vec<Cell>>()
+        .choose(&mut rng)?;
+
+    let entrance = layout.add_room(ENTRANCE_ID, vec![entrance_cell]);
+    let blood = layout.add_room(BLOOD_ID, vec![blood_cell]);
+
+    // Fairy shouldn't be right next to the entrance or blood
+    let fairy_cell = *all_cells.iter()
+        .copied()
+        .filter(|cell| layout.is_free(*cell) && distance(*cell, entrance_cell) > 1 && distance(*cell, blood_cell) > 1)
+        .collect::<Vec<Cell>>()
+        .choose(&mut rng)?;
+    let fairy = layout.add_room(FAIRY_ID, vec![fairy_cell]);
+
+    // Every other special room is a dead end with a single door
+    let mut dead_ends = vec![entrance, blood];
+    let special_ids = std::iter::repeat_n(PUZZLE_ID, floor.puzzles)
+        .chain(floor.trap.then_some(TRAP_ID))
+        .chain(floor.yellow.then_some(YELLOW_ID));
+
+    for id in special_ids {
+        let cell = *all_cells.iter()
+            .copied()
+            .filter(|cell| layout.is_free(*cell) && distance(*cell, entrance_cell) > 1)
+            .collect::<Vec<Cell>>()
+            .choose(&mut rng)?;
+        dead_ends.push(layout.add_room(id, vec![cell]));
+    }
+
+    if !is_valid_placement(&layout, &dead_ends, fairy_cell) {
+        return None
+    }
+
+    let mut next_id = FIRST_NORMAL_ID;
+    for pin in pins.iter().filter(|pin| pin.room_type == RoomType::Normal) {
+        let placements = get_placements(&layout, pin.cell, shape_variants(&pin.shape)?);
+        let room_cells = placements.choose(&mut rng)?.clone();
+        layout.add_room(next_id, room_cells);
+        next_id += 1;
+    }
+
+    place_normal_rooms(&mut layout, &mut rng, next_id);
+
+    let room_count = layout.room_ids.len();
+    let is_dead_end = |room: usize| dead_ends.contains(&room);
+
+    // Random spanning tree over the normal rooms and fairy (kruskal with shuffled edges)
+    let mut edges = Vec::new();
+    for &cell in all_cells.iter() {
+        for other in layout.neighbours(cell).filter(|other| layout.index(*other) > layout.index(cell)) {
+            let (a, b) = (layout.room_at(cell)?, layout.room_at(other)?);
+            if a != b && !is_dead_end(a) && !is_dead_end(b) {
+                edges.push((a, b, cell, other));
+            }
+        }
+    }
+    edges.shuffle(&mut rng);
+
+    let mut parents = (0..room_count).collect::<Vec<usize>>();
+
+    for (a, b, cell_a, cell_b) in edges {
+        let (root_a, root_b) = (find(&mut parents, a), find(&mut parents, b));
+        if root_a != root_b {
+            parents[root_a] = root_b;
+            layout.doors.push((a, b, cell_a, cell_b));
+        }
+    }
+
+    // Hook each dead end up to a random neighbouring room that isn't a dead end itself
+    for &room in dead_ends.iter() {
+        let cell = layout.room_cells[room][0];
+        let candidates = layout.neighbours(cell)
+            .filter(|other| layout.room_at(*other).is_some_and(|other| !is_dead_end(other)))
+            .collect::<Vec<Cell>>();
+        let other = *candidates.choose(&mut rng)?;
+        layout.doors.push((room, layout.room_at(other)?, cell, other));
+    }
+
+    if !pins.iter().all(|pin| pin_fits(&layout, pin)) {
+        return None
+    }
+
+    Some(to_dungeon_string(&layout, floor, entrance, blood, fairy))
+}
+
+/// The placements of a normal room shape, None for shapes the generator never makes.
+fn shape_variants(shape: &RoomShape) -> Option<&'static [&'static [Cell]]> {
+    let index = match shape {
+        RoomShape::TwoByTwo => 0,
+        RoomShape::L => 1,
+        RoomShape::OneByFour => 2,
+        RoomShape::OneByThree => 3,
+        RoomShape::OneByTwo => 4,
+        RoomShape::OneByOneEnd | RoomShape::OneByOneCross | RoomShape::OneByOneStraight | RoomShape::OneByOneBend | RoomShape::OneByOneTriple => 5,
+        _ => return None,
+    };
+    Some(SHAPES[index].0)
+}
+
+/// Whether the room covering the pinned cell has the pinned type and shape, the same way they are worked out when the dungeon is built.
+fn pin_fits(layout: &Layout, pin: &PinnedCell) -> bool {
+    let Some(room) = layout.room_at(pin.cell) else {
+        return false
+    };
+    let cells = &layout.room_cells[room];
+
+    let (room_type, shape) = match layout.room_ids[room] {
+        ENTRANCE_ID => (RoomType::Entrance, RoomShape::OneByOneEnd),
+        FAIRY_ID => (RoomType::Fairy, RoomShape::OneByOne),
+        BLOOD_ID => (RoomType::Blood, RoomShape::OneByOneEnd),
+        PUZZLE_ID => (RoomType::Puzzle, RoomShape::OneByOneEnd),
+        TRAP_ID => (RoomType::Trap, RoomShape::OneByOneEnd),
+        YELLOW_ID => (RoomType::Yellow, RoomShape::OneByOneEnd),
+        _ => {
+            let spans_both = cells.iter().any(|cell| cell.0 != cells[0].0) && cells.iter().any(|cell| cell.1 != cells[0].1);
+            let shape = match (cells.len(), spans_both) {
+                (1, _) => one_by_one_shape(layout, room),
+                (2, _) => RoomShape::OneByTwo,
+                (3, true) => RoomShape::L,
+                (3, false) => RoomShape::OneByThree,
+                (4, true) => RoomShape::TwoByTwo,
+                (4, false) => RoomShape::OneByFour,
+                _ => RoomShape::Empty,
+            };
+            (RoomType::Normal, shape)
+        }
+    };
+    room_type == pin.room_type && shape == pin.shape
+}
+
+/// The shape of a normal 1x1 room from its doors, see Room::get_1x1_shape_and_type.
+fn one_by_one_shape(layout: &Layout, room: usize) -> RoomShape {
+    let cell = layout.room_cells[room][0];
+    let door_cells = layout.doors.iter()
+        .filter_map(|(a, b, cell_a, cell_b)| {
+            if *a == room {
+                Some(*cell_b)
+            } else if *b == room {
+                Some(*cell_a)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<Cell>>();
+
+    match door_cells.len() {
+        1 => RoomShape::OneByOneEnd,
+        // opposite doors are both in line with the room
+        2 if door_cells.iter().all(|other| other.0 == cell.0) || door_cells.iter().all(|other| other.1 == cell.1) => RoomShape::OneByOneStraight,
+        2 => RoomShape::OneByOneBend,
+        3 => RoomShape::OneByOneTriple,
+        4 => RoomShape::OneByOneCross,
+        _ => RoomShape::OneByOne,
+    }
+}
+
+fn find(parents: &mut [usize], room: usize) -> usize {
+    if parents[room] != room {
+        parents[room] = find(parents, parents[room]);
+    }
+    parents[room]
+}
+
+/// Makes sure that the special rooms don't split the grid up, and that every dead end can get a door.
+fn is_valid_placement(layout: &Layout, dead_ends: &[usize], fairy_cell: Cell) -> bool {
+    let is_open = |cell: Cell| layout.room_at(cell).map(|room| !dead_ends.contains(&room)).unwrap_or(true);
+
+    let mut visited = vec![false; layout.grid.len()];
+    let mut queue = VecDeque::from([fairy_cell]);
+    visited[layout.index(fairy_cell)] = true;
+
+    while let Some(cell) = queue.pop_front() {
+        for other in layout.neighbours(cell) {
+            if !visited[layout.index(other)] && is_open(other) {
+                visited[layout.index(other)] = true;
+                queue.push_back(other);
+            }
+        }
+    }
+
+    let open_cells_reached = layout.cells()
+        .into_iter()
+        .filter(|cell| is_open(*cell))
+        .all(|cell| visited[layout.index(cell)]);
+
+    let dead_ends_reachable = dead_ends.iter().all(|room| {
+        let cell = layout.room_cells[*room][0];
+        layout.neighbours(cell).any(is_open)
+    });
+
+    open_cells_reached && dead_ends_reachable
+}
+
+/// Fills every remaining cell with normal rooms of random shapes.
+fn place_normal_rooms(layout: &mut Layout, rng: &mut impl Rng, mut next_id: u8) {
+    let mut cells = layout.cells();
+    cells.shuffle(rng);
+
+    for cell in cells {
+        if !layout.is_free(cell) {
+            continue;
+        }
+
+        // Try shapes in a weighted random order, 1x1 always fits so this always places something.
+        let mut shapes = SHAPES.to_vec();
+        let mut room_cells = None;
+
+        while room_cells.is_none() && !shapes.is_empty() {
+            let total = shapes.iter().map(|(_, weight)| weight).sum::<u32>();
+            let mut roll = rng.random_range(0..total);
+            let index = shapes.iter().position(|(_, weight)| {
+                if roll < *weight {
+                    return true
+                }
+                roll -= weight;
+                false
+            }).unwrap();
+
+            let (variants, _) = shapes.remove(index);
+            let placements = get_placements(layout, cell, variants);
+            room_cells = placements.choose(rng).cloned();
+        }
+
+        if let Some(room_cells) = room_cells {
+            layout.add_room(next_id, room_cells);
+            next_id += 1;
+        }
+    }
+}
+
+/// Every way a shape can be placed so that it covers the given cell and only free cells.
+fn get_placements(layout: &Layout, cell: Cell, variants: &[&[Cell]]) -> Vec<Vec<Cell>> {
+    let mut placements = Vec::new();
+
+    for variant in variants {
+        for (offset_x, offset_z) in variant.iter() {
+            let (Some(anchor_x), Some(anchor_z)) = (cell.0.checked_sub(*offset_x), cell.1.checked_sub(*offset_z)) else {
+                continue;
+            };
+
+            let cells = variant.iter()
+                .map(|(x, z)| (anchor_x + x, anchor_z + z))
+                .collect::<Vec<Cell>>();
+
+            if cells.iter().all(|&(x, z)| x < layout.width && z < layout.height && layout.is_free((x, z))) {
+                placements.push(cells);
+            }
+        }
+    }
+    placements
+}
+
+fn to_dungeon_string(layout: &Layout, floor: &FloorProfile, entrance: usize, blood: usize, fairy: usize) -> String {
+    let room_count = layout.room_ids.len();
+
+    // Walk the door tree from the entrance to find the path to blood
+    let mut parent: Vec<Option<(usize, usize)>> = vec![None; room_count];
+    let mut visited = vec![false; room_count];
+    let mut queue = VecDeque::from([entrance]);
+    visited[entrance] = true;
+
+    while let Some(room) = queue.pop_front() {
+        for (door_index, (a, b, _, _)) in layout.doors.iter().enumerate() {
+            let other = if *a == room {
+                *b
+            } else if *b == room {
+                *a
+            } else {
+                continue
+            };
+            if !visited[other] {
+                visited[other] = true;
+                parent[other] = Some((room, door_index));
+                queue.push_back(other);
+            }
+        }
+    }
+
+    let mut door_types = vec![DOOR_NORMAL; layout.doors.len()];
+
+    // Wither doors along the critical path, and one in front of fairy
+    let mut current = blood;
+    while let Some((previous, door_index)) = parent[current] {
+        door_types[door_index] = DOOR_WITHER;
+        current = previous;
+    }
+    if let Some((_, door_index)) = parent[fairy] {
+        door_types[door_index] = DOOR_WITHER;
+    }
+    for (door_index, (a, b, _, _)) in layout.doors.iter().enumerate() {
+        if *a == blood || *b == blood {
+            door_types[door_index] = DOOR_BLOOD;
+        }
+        if *a == entrance || *b == entrance {
+            door_types[door_index] = DOOR_ENTRANCE;
+        }
+    }
+
+    let mut door_chars = vec![DOOR_NONE; floor.door_count()];
+    for ((_, _, cell_a, cell_b), door_type) in layout.doors.iter().zip(door_types) {
+        door_chars[floor.door_index_between(*cell_a, *cell_b)] = door_type;
+    }
+
+    let mut dungeon_str = String::with_capacity(floor.layout_length());
+    for room in layout.grid.iter() {
+        let id = room.map(|room| layout.room_ids[room]).unwrap_or(0);
+        dungeon_str.push_str(&format!("{:02}", id));
+    }
+    dungeon_str.push_str(std::str::from_utf8(&door_chars).unwrap());
+    dungeon_str
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::seeded_rng::SeededRng;
+
+    fn room_id_at(layout: &str, floor: &FloorProfile, (x, z): Cell) -> u8 {
+        let index = floor.grid_index(x, z) * 2;
+        layout[index..index + 2].parse().unwrap()
+    }
+
+    #[test]
+    fn same_seed_gives_same_layout() {
+        let floor = FloorProfile::default();
+        for seed in 0..5 {
+            SeededRng::set_seed(seed);
+            let first = generate_layout(&floor, &[]);
+            SeededRng::set_seed(seed);
+            assert_eq!(generate_layout(&floor, &[]), first, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn blood_is_reachable_from_entrance() {
+        let floor = FloorProfile::default();
+
+        for seed in 0..20 {
+            SeededRng::set_seed(seed);
+            let layout = generate_layout(&floor, &[]);
+            let doors = &layout.as_bytes()[floor.room_count() * 2..];
+
+            let cells = (0..floor.height).flat_map(|z| (0..floor.width).map(move |x| (x, z)));
+            let entrance = cells.clone().find(|cell| room_id_at(&layout, &floor, *cell) == ENTRANCE_ID).unwrap();
+            let blood = cells.clone().find(|cell| room_id_at(&layout, &floor, *cell) == BLOOD_ID).unwrap();
+
+            let mut visited = vec![entrance];
+            let mut queue = vec![entrance];
+            while let Some(cell) = queue.pop() {
+                for (door_index, door) in doors.iter().enumerate() {
+                    let (a, b) = floor.door_cells(door_index);
+                    let next = match cell {
+                        _ if cell == a => b,
+                        _ if cell == b => a,
+                        _ => continue,
+                    };
+                    let id = room_id_at(&layout, &floor, cell);
+                    let same_room = id >= FIRST_NORMAL_ID && id == room_id_at(&layout, &floor, next);
+                    if (*door != DOOR_NONE || same_room) && !visited.contains(&next) {
+                        visited.push(next);
+                        queue.push(next);
+                    }
+                }
+            }
+            assert!(visited.contains(&blood), "seed {seed}: blood at {blood:?} isn't reachable from {entrance:?}");
+        }
+    }
+
+    #[test]
+    fn pinned_normal_room_gets_its_shape() {
+        let floor = FloorProfile::default();
+        let pin = PinnedCell { cell: (2, 3), room_type: RoomType::Normal, shape: RoomShape::TwoByTwo };
+
+        for seed in 0..20 {
+            SeededRng::set_seed(seed);
+            let layout = generate_layout(&floor, std::slice::from_ref(&pin));
+
+            let id = room_id_at(&layout, &floor, pin.cell);
+            assert!(id >= FIRST_NORMAL_ID, "seed {seed}: pinned cell has room id {id}");
+            let cells = (0..floor.height)
+                .flat_map(|z| (0..floor.width).map(move |x| (x, z)))
+                .filter(|cell| room_id_at(&layout, &floor, *cell) == id)
+                .collect::<Vec<Cell>>();
+            assert_eq!(cells.len(), 4, "seed {seed}: pinned room covers {cells:?}");
+            assert!(cells.iter().all(|cell| cell.0.abs_diff(pin.cell.0) <= 1 && cell.1.abs_diff(pin.cell.1) <= 1));
+        }
+    }
+    #[test]
+    fn pinned_special_room_is_retried_into_place() {
+        let floor = FloorProfile::default();
+        let pin = PinnedCell { cell: (3, 2), room_type: RoomType::Puzzle, shape: RoomShape::OneByOneEnd };
+
+        for seed in 0..5 {
+            SeededRng::set_seed(seed);
+            let layout = generate_layout(&floor, std::slice::from_ref(&pin));
+            assert_eq!(room_id_at(&layout, &floor, pin.cell), PUZZLE_ID, "seed {seed}");
+        }
+    }
+}
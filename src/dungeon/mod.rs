@@ -1,7 +1,18 @@
 pub mod room;
 pub mod door;
+pub mod blood_room;
+pub mod fairy_room;
+pub mod boss_room;
 pub mod crushers;
+pub mod traps;
 pub mod dungeon_state;
 pub mod dungeon;
+pub mod dungeon_parse_error;
+pub mod dungeon_seed;
 pub mod map;
-pub mod p3;
\ No newline at end of file
+pub mod mort;
+pub mod floor_profile;
+pub mod generator;
+pub mod score;
+pub mod p3;
+pub mod puzzle;
\ No newline at end of file
@@ -0,0 +1,189 @@
+use crate::dungeon::room::room::Room;
+use crate::net::packets::packet_buffer::PacketBuffer;
+use crate::net::protocol::play::clientbound::{PlayerListItem, Teams};
+use crate::net::protocol::play::serverbound::EntityInteractionType;
+use crate::net::var_int::VarInt;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::entity::entity::{Entity, EntityImpl, NoEntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::entity::spawn_equipped::spawn_following_nametag;
+use crate::server::player::container_ui::UI;
+use crate::server::player::player::{GameProfile, GameProfileProperty, Player};
+use crate::server::player::scoreboard::{ADD_PLAYER, CREATE_TEAM};
+use crate::server::utils::chat_component::chat_component_text::ChatComponentTextBuilder;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::player_list::player_profile::{GameType, PlayerData};
+use crate::server::utils::sized_string::SizedString;
+use crate::server::world::World;
+use std::collections::HashMap;
+use std::ops::Add;
+use uuid::Uuid;
+
+// Mort skin constants
+const MORT_SKIN_VALUE: &str = "ewogICJ0aW1lc3RhbXAiIDogMTYyMDcyNTkwMDEzOSwKICAicHJvZmlsZUlkIiA6ICJhNzdkNmQ2YmFjOWE0NzY3YTFhNzU1NjYxOTllYmY5MiIsCiAgInByb2ZpbGVOYW1lIiA6ICIwOEJFRDUiLAogICJzaWduYXR1cmVSZXF1aXJlZCIgOiB0cnVlLAogICJ0ZXh0dXJlcyIgOiB7CiAgICAiU0tJTiIgOiB7CiAgICAgICJ1cmwiIDogImh0dHA6Ly90ZXh0dXJlcy5taW5lY3JhZnQubmV0L3RleHR1cmUvOWI1Njg5NWI5NjU5ODk2YWQ2NDdmNTg1OTkyMzhhZjUzMmQ0NmRiOWMxYjAzODliOGJiZWI3MDk5OWRhYjMzZCIKICAgIH0KICB9Cn0=";
+const MORT_SKIN_SIGNATURE: &str = "ihevlFAZ1u+xG/eeEnUzMRu1l8i+2j6pw1jIw0yxcsLn1x749GL+ToaVRyU56+13vDg9G6QjWRHQaA1DpPIkgmthhZsxQ067Q2A2SASywQiQIvIPJwmzjRmkP3eYHtKnJ7t4uZ31qjMazaONNq00Nq2t8s983u2TPfCFZJQlx8RqNWjRZjmGh7Gw+YXKbecwnlQmvpKZSiPolCcTgobPl0aZCr+benffxA0bcAohkr5Kp8U2VZW73wF0P7FGkANIhLYOtokLTemaYOMPWe4q/SU3D5yZswM6/SQ63g0mAvZJfQW/Vb+lAGzlm3zXia7T6tAJjFYuV1kg5yVcODbYOb2fgLJK3OQvUjnf9xlXXyDcESOILsPhft5SYVbBQuDkuLitG7YecJMV9cbCqldnvv4Z4XKs3jaCzZqYDRql4MVx8rYd+7hLaGXuprfrwBYL1xzzgMFSTFUCkIm942L5B7/6tZJGT5GT7g4DN1vrJpnZz4+gxdebcbcUEfP313/gHFU/U3phfN89TBbbNAfi0t5uQ5SRCGXdCz+YbO56zTKjzeUg57u49XOZaKwNZyF6hmv2IdO9CJctYw9cvljEkALOkMjMShaP95QYHsahc3mFLavJbseY7x5/vlexjRvPxdnxQCDG+Fkf9eBwUjyCqUjQozYYM6euDHFqib7uBHM=";
+
+pub struct MortImpl;
+
+impl EntityImpl for MortImpl {
+    fn spawn(&mut self, entity: &mut Entity, buffer: &mut PacketBuffer) {
+        // Add Mort to player list so the player entity can be spawned properly
+        if let Some(uuid) = entity.uuid {
+            let mort_profile = GameProfile {
+                uuid,
+                username: "Mort".to_string(),
+                properties: HashMap::from([
+                    ("textures".to_string(), GameProfileProperty {
+                        value: MORT_SKIN_VALUE.to_string(),
+                        signature: Some(MORT_SKIN_SIGNATURE.to_string()),
+                    })
+                ]),
+            };
+            
+            let player_data = PlayerData {
+                ping: 20, // More realistic ping for NPCs
+                game_mode: GameType::Survival, // Use Survival instead of Creative for better modded client compatibility
+                profile: mort_profile,
+                display_name: Some(ChatComponentTextBuilder::new("Mort").build()),
+            };
+            
+            // Send PlayerInfo Add packet
+            buffer.write_packet(&PlayerListItem {
+                action: VarInt(0), // ADD_PLAYER
+                players: vec![&player_data],
+            });
+            
+            // First create the hidden team to prevent vanilla nameplate from showing
+            buffer.write_packet(&Teams {
+                name: SizedString::truncated("npc_hide"),
+                display_name: SizedString::truncated("npc_hide"),
+                prefix: SizedString::truncated(""),
+                suffix: SizedString::truncated(""),
+                name_tag_visibility: SizedString::truncated("never"),
+                color: 0,
+                players: vec![],
+                action: CREATE_TEAM,
+                friendly_flags: 0,
+            });
+            
+            // Then add Mort to the hidden team
+            buffer.write_packet(&Teams {
+                name: SizedString::truncated("npc_hide"),
+                display_name: SizedString::truncated("npc_hide"),
+                prefix: SizedString::truncated(""),
+                suffix: SizedString::truncated(""),
+                name_tag_visibility: SizedString::truncated("never"),
+                color: 0,
+                players: vec![SizedString::truncated("Mort")],
+                action: ADD_PLAYER,
+                friendly_flags: 0,
+            });
+        }
+    }
+    
+    fn tick(&mut self, _: &mut Entity, _: &mut PacketBuffer) {
+        // rotate
+    }
+    fn interact(&mut self, _: &mut Entity, player: &mut Player, action: &EntityInteractionType) {
+        if action == &EntityInteractionType::InteractAt {
+            return;
+        }
+        player.open_ui(UI::MortReadyUpMenu);
+    }
+}
+
+/// Spawns Mort and his nametags in the entrance room.
+pub fn spawn_mort(world: &mut World, room: &Room) -> anyhow::Result<()> {
+    // Generate deterministic UUID for Mort NPC using parse_str with a fixed UUID
+    let mort_uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(); // Mort NPC UUID
+    
+    let id = world.spawn_entity_with_uuid(
+        room.get_world_block_pos(&BlockPos { x: 15, y: 69, z: 4 })
+            .as_dvec3()
+            .add(DVec3::new(0.5, 0.0, 0.5)),
+        EntityMetadata::new(EntityVariant::Player),
+        MortImpl,
+        Some(mort_uuid),
+    )?;
+    if let Some((entity, _)) = world.entities.get_mut(&id) {
+        entity.yaw = 0.0.rotate(room.rotation);
+    }
+    
+    // Create two-line armorstand nametag for Mort
+    // Get Mort's position first
+    let mort_pos = if let Some((entity, _)) = world.entities.get(&id) {
+        entity.position
+    } else {
+        // Fallback to the original position if entity not found yet
+        room.get_world_block_pos(&BlockPos { x: 15, y: 69, z: 4 })
+            .as_dvec3()
+            .add(DVec3::new(0.5, 0.0, 0.5))
+    };
+    
+    // Try following nametags first
+    match spawn_following_nametag(world, id, "§bMort", 0.4) {
+        Ok(_top_nametag_id) => {
+            // Spawn bottom nametag
+            match spawn_following_nametag(world, id, "§eCLICK", 0.1) {
+                Ok(_bottom_nametag_id) => {
+                    // Both nametags spawned successfully
+                }
+                Err(e) => {
+                    println!("Failed to spawn bottom nametag: {}", e);
+                    // Fallback to static armorstands
+                    let _bottom_nametag_id = world.spawn_entity(
+                        mort_pos + DVec3::new(0.0, 0.1, 0.0),
+                        {
+                            let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
+                            metadata.is_invisible = true;
+                            metadata.custom_name = Some("§eCLICK".to_string());
+                            metadata.custom_name_visible = true;
+                            metadata.ai_disabled = true;
+                            metadata
+                        },
+                        NoEntityImpl,
+                    )?;
+                }
+            }
+        }
+        Err(e) => {
+            println!("Failed to spawn top nametag: {}", e);
+            // Fallback to static armorstands
+            let _top_nametag_id = world.spawn_entity(
+                mort_pos + DVec3::new(0.0, 0.4, 0.0),
+                {
+                    let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
+                    metadata.is_invisible = true;
+                    metadata.custom_name = Some("§bMort".to_string());
+                    metadata.custom_name_visible = true;
+                    metadata.ai_disabled = true;
+                    metadata
+                },
+                NoEntityImpl,
+            )?;
+            
+            let _bottom_nametag_id = world.spawn_entity(
+                mort_pos + DVec3::new(0.0, 0.1, 0.0),
+                {
+                    let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
+                    metadata.is_invisible = true;
+                    metadata.custom_name = Some("§eCLICK".to_string());
+                    metadata.custom_name_visible = true;
+                    metadata.ai_disabled = true;
+                    metadata
+                },
+                NoEntityImpl,
+            )?;
+        }
+    }
+    
+    // Ensure entity is properly visible first, then add team hiding if needed
+    if let Some((_entity, _)) = world.entities.get(&id) {
+        // Player entity should be properly registered now
+        // For modded clients, we might want to ensure no immediate team modifications
+        // that could interfere with visibility
+    }
+
+    Ok(())
+}
@@ -1,274 +1,391 @@
-// use crate::server::world::World;
-// use crate::server::player::player::Player;
-// use crate::server::block::block_position::BlockPos;
-// use crate::server::utils::dvec3::DVec3;
-// use crate::dungeon::p3::terminal::{Terminal, TerminalType, TerminalManager};
-// use crate::dungeon::p3::simon_says::SimonSays;
-// use std::collections::HashMap;
+use crate::dungeon::p3::simon_says::{SimonSays, SimonSaysAction};
+use crate::dungeon::p3::terminal::{Terminal, TerminalType};
+use crate::dungeon::score::format_ticks;
+use crate::net::protocol::play::clientbound::{PositionLook, SoundEffect};
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::player::container_ui::UI;
+use crate::server::player::player::{ClientId, Player};
+use crate::server::player::terminal;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use std::collections::HashMap;
 
-// #[derive(Debug, Clone, PartialEq)]
-// pub enum Section {
-//     S1,
-//     S2,
-//     S3,
-//     S4,
-// }
+// Goldor's phase: four sections, each with its terminals, two levers and a device.
+// Once everything in a section is done its gate drops and the next section starts,
+// after the fourth the core entrance opens and the phase is over.
+//
+// All positions are the real F7 boss room ones, which line up with the boss room since it is placed at its own coordinates.
+// Simon Says is the only device that is simulated, clicking the stand of any other device completes it.
 
-// #[derive(Debug, Clone, PartialEq)]
-// pub enum Phase {
-//     P1,
-//     P2,
-//     P3,
-//     P4,
-//     P5,
-//     P6,
-//     P7,
-// }
+/// The terminal guis a P3 terminal can be.
+const TERMINAL_GUIS: [terminal::TerminalType; 5] = [
+    terminal::TerminalType::Order,
+    terminal::TerminalType::Panes,
+    terminal::TerminalType::Rubix,
+    terminal::TerminalType::Select,
+    terminal::TerminalType::StartsWith,
+];
 
-// pub struct DungeonGate {
-//     pub position: BlockPos,
-//     pub rotation: i32,
-//     pub section: Section,
-//     pub placed: bool,
-// }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    S1,
+    S2,
+    S3,
+    S4,
+}
 
-// impl DungeonGate {
-//     pub fn new(position: BlockPos, rotation: i32, section: Section) -> Self {
-//         Self {
-//             position,
-//             rotation,
-//             section,
-//             placed: false,
-//         }
-//     }
+impl Section {
+    pub fn from_name(name: &str) -> Option<Section> {
+        Some(match name.to_lowercase().as_str() {
+            "s1" | "1" => Section::S1,
+            "s2" | "2" => Section::S2,
+            "s3" | "3" => Section::S3,
+            "s4" | "4" => Section::S4,
+            _ => return None,
+        })
+    }
 
-//     pub fn place(&mut self, world: &mut World) {
-//         // TODO: Implement gate placement logic
-//         self.placed = true;
-//         println!("Placed gate at {:?} for section {:?}", self.position, self.section);
-//     }
+    pub fn number(&self) -> u8 {
+        match self {
+            Section::S1 => 1,
+            Section::S2 => 2,
+            Section::S3 => 3,
+            Section::S4 => 4,
+        }
+    }
 
-//     pub fn section_over(&mut self, world: &mut World) {
-//         // TODO: Implement gate removal logic
-//         self.placed = false;
-//         println!("Removed gate at {:?} for section {:?}", self.position, self.section);
-//     }
-// }
+    fn next(&self) -> Option<Section> {
+        match self {
+            Section::S1 => Some(Section::S2),
+            Section::S2 => Some(Section::S3),
+            Section::S3 => Some(Section::S4),
+            Section::S4 => None,
+        }
+    }
 
-// pub struct P3Manager {
-//     pub started: bool,
-//     pub started_time: u64,
-//     pub current_section: Section,
-//     pub current_phase: Phase,
-//     pub terminals: Vec<Terminal>,
-//     pub devices: Vec<Terminal>,
-//     pub has_melody: bool,
-//     pub no_melody: bool,
-//     pub s1_gate: Option<DungeonGate>,
-//     pub s2_gate: Option<DungeonGate>,
-//     pub s3_gate: Option<DungeonGate>,
-//     pub terminal_manager: TerminalManager,
-// }
+    /// Where players are put when the section is started with a command, and the yaw they face.
+    pub fn start_position(&self) -> (DVec3, f32) {
+        match self {
+            Section::S1 => (DVec3::new(110.5, 121.0, 92.5), 0.0),
+            Section::S2 => (DVec3::new(98.5, 108.0, 121.5), 90.0),
+            Section::S3 => (DVec3::new(18.5, 108.0, 121.5), 180.0),
+            Section::S4 => (DVec3::new(8.5, 108.0, 46.5), 270.0),
+        }
+    }
 
-// impl P3Manager {
-//     pub fn new() -> Self {
-//         Self {
-//             started: false,
-//             started_time: 0,
-//             current_section: Section::S1,
-//             current_phase: Phase::P3,
-//             terminals: Vec::new(),
-//             devices: Vec::new(),
-//             has_melody: false,
-//             no_melody: false,
-//             s1_gate: None,
-//             s2_gate: None,
-//             s3_gate: None,
-//             terminal_manager: TerminalManager::new(),
-//         }
-//     }
+    /// The gate at the end of the section, the last one is the core entrance.
+    fn gate(&self) -> (BlockPos, BlockPos) {
+        match self {
+            Section::S1 => (BlockPos::new(102, 108, 118), BlockPos::new(106, 114, 118)),
+            Section::S2 => (BlockPos::new(20, 108, 118), BlockPos::new(20, 114, 122)),
+            Section::S3 => (BlockPos::new(6, 108, 50), BlockPos::new(10, 114, 50)),
+            Section::S4 => (BlockPos::new(52, 115, 58), BlockPos::new(56, 121, 58)),
+        }
+    }
 
-//     pub fn start(&mut self) {
-//         self.start_section(Section::S1);
-//     }
+    fn terminals(&self) -> Vec<Terminal> {
+        match self {
+            Section::S1 => vec![
+                Terminal::new(1, TerminalType::Terminal, BlockPos::new(110, 112, 73)),
+                Terminal::new(2, TerminalType::Terminal, BlockPos::new(110, 118, 79)),
+                Terminal::new(3, TerminalType::Terminal, BlockPos::new(90, 111, 92)),
+                Terminal::new(4, TerminalType::Terminal, BlockPos::new(90, 121, 101)),
+                Terminal::new(5, TerminalType::Lever, BlockPos::new(94, 122, 113)).with_target(BlockPos::new(94, 124, 113)),
+                Terminal::new(6, TerminalType::Lever, BlockPos::new(106, 122, 113)).with_target(BlockPos::new(106, 124, 113)),
+                Terminal::new(100, TerminalType::SimonSays, BlockPos::new(110, 118, 91)),
+            ],
+            Section::S2 => vec![
+                Terminal::new(7, TerminalType::Terminal, BlockPos::new(68, 108, 122)),
+                Terminal::new(8, TerminalType::Terminal, BlockPos::new(59, 119, 123)),
+                Terminal::new(9, TerminalType::Terminal, BlockPos::new(47, 108, 122)),
+                Terminal::new(10, TerminalType::Terminal, BlockPos::new(39, 107, 142)),
+                Terminal::new(11, TerminalType::Terminal, BlockPos::new(40, 123, 123)),
+                Terminal::new(12, TerminalType::Lever, BlockPos::new(27, 122, 127)).with_target(BlockPos::new(27, 124, 127)),
+                Terminal::new(13, TerminalType::Lever, BlockPos::new(23, 130, 138)).with_target(BlockPos::new(23, 132, 138)),
+                Terminal::new(101, TerminalType::Lamps, BlockPos::new(60, 130, 142)),
+            ],
+            Section::S3 => vec![
+                Terminal::new(14, TerminalType::Terminal, BlockPos::new(-2, 108, 112)),
+                Terminal::new(15, TerminalType::Terminal, BlockPos::new(-2, 118, 93)),
+                Terminal::new(16, TerminalType::Terminal, BlockPos::new(18, 122, 93)),
+                Terminal::new(17, TerminalType::Terminal, BlockPos::new(-2, 108, 77)),
+                Terminal::new(18, TerminalType::Lever, BlockPos::new(14, 120, 55)).with_target(BlockPos::new(14, 122, 55)),
+                Terminal::new(19, TerminalType::Lever, BlockPos::new(2, 120, 55)).with_target(BlockPos::new(2, 122, 55)),
+                Terminal::new(102, TerminalType::Align, BlockPos::new(-2, 118, 74)),
+            ],
+            Section::S4 => vec![
+                Terminal::new(20, TerminalType::Terminal, BlockPos::new(41, 108, 30)),
+                Terminal::new(21, TerminalType::Terminal, BlockPos::new(44, 120, 30)),
+                Terminal::new(22, TerminalType::Terminal, BlockPos::new(67, 108, 30)),
+                Terminal::new(23, TerminalType::Terminal, BlockPos::new(72, 114, 47)),
+                Terminal::new(24, TerminalType::Lever, BlockPos::new(84, 119, 34)).with_target(BlockPos::new(84, 121, 34)),
+                Terminal::new(25, TerminalType::Lever, BlockPos::new(86, 126, 46)).with_target(BlockPos::new(86, 128, 46)),
+                Terminal::new(103, TerminalType::ShootTarget, BlockPos::new(63, 125, 34)),
+            ],
+        }
+    }
+}
 
-//     pub fn start_section(&mut self, section: Section) {
-//         self.current_phase = Phase::P3;
-//         self.current_section = section;
-        
-//         // TODO: Get client config for no_melody setting
-//         self.no_melody = false;
-        
-//         self.started = true;
-        
-//         // Get terminals and devices for this section
-//         self.terminals = self.get_terminal_locations();
-//         self.devices = self.get_device_locations();
-//     }
-    
-//     pub fn get_terminals_to_spawn(&self) -> Vec<Terminal> {
-//         self.terminals.clone()
-//     }
-    
-//     pub fn get_devices_to_spawn(&self) -> Vec<Terminal> {
-//         self.devices.clone()
-//     }
+pub struct P3Manager {
+    pub started: bool,
+    pub current_section: Section,
+    /// Terminals, levers and the device of the current section.
+    pub terminals: Vec<Terminal>,
+    phase_started_at: u64,
+    section_started_at: u64,
+    /// The terminal each player has the gui of open.
+    open_terminals: HashMap<ClientId, u32>,
+    pub simon_says: SimonSays,
+}
 
-//     pub fn get_boss_message() -> String {
-//         "[BOSS] Goldor: Who dares trespass into my domain?".to_string()
-//     }
+impl P3Manager {
+    pub fn new() -> Self {
+        Self {
+            started: false,
+            current_section: Section::S1,
+            terminals: Vec::new(),
+            phase_started_at: 0,
+            section_started_at: 0,
+            open_terminals: HashMap::new(),
+            simon_says: SimonSays::new(),
+        }
+    }
 
-//     pub fn get_terminals(&self) -> &Vec<Terminal> {
-//         &self.terminals
-//     }
+    /// Starts the phase at `section`, everything before it counts as done.
+    pub fn start(&mut self, world: &mut World, section: Section) {
+        self.reset(world);
+        self.started = true;
+        self.phase_started_at = world.tick_count;
 
-//     pub fn get_devices(&self) -> &Vec<Terminal> {
-//         &self.devices
-//     }
+        for gate_section in [Section::S1, Section::S2, Section::S3, Section::S4] {
+            let (from, to) = gate_section.gate();
+            let block = if gate_section.number() < section.number() { Blocks::Air } else { Blocks::Bedrock };
+            world.fill_blocks(block, from, to);
+        }
 
-//     fn get_terminal_locations(&self) -> Vec<Terminal> {
-//         match self.current_section {
-//             Section::S1 => {
-//                 vec![
-//                     Terminal::new(1, TerminalType::Terminal, BlockPos::new(110, 112, 73)),
-//                     Terminal::new(2, TerminalType::Terminal, BlockPos::new(110, 118, 79)),
-//                     Terminal::new(3, TerminalType::Terminal, BlockPos::new(90, 111, 92)),
-//                     Terminal::new(4, TerminalType::Terminal, BlockPos::new(90, 121, 101)),
-//                     Terminal::new(5, TerminalType::Lever, BlockPos::new(94, 122, 113)).with_target(BlockPos::new(94, 124, 113)),
-//                     Terminal::new(6, TerminalType::Lever, BlockPos::new(106, 122, 113)).with_target(BlockPos::new(106, 124, 113)),
-//                 ]
-//             }
-//             Section::S2 => {
-//                 vec![
-//                     Terminal::new(7, TerminalType::Terminal, BlockPos::new(68, 108, 122)),
-//                     Terminal::new(8, TerminalType::Terminal, BlockPos::new(59, 119, 123)),
-//                     Terminal::new(9, TerminalType::Terminal, BlockPos::new(47, 108, 122)),
-//                     Terminal::new(10, TerminalType::Terminal, BlockPos::new(39, 107, 142)),
-//                     Terminal::new(11, TerminalType::Terminal, BlockPos::new(40, 123, 123)),
-//                     Terminal::new(12, TerminalType::Lever, BlockPos::new(27, 122, 127)).with_target(BlockPos::new(27, 124, 127)),
-//                     Terminal::new(13, TerminalType::Lever, BlockPos::new(23, 130, 138)).with_target(BlockPos::new(23, 132, 138)),
-//                 ]
-//             }
-//             Section::S3 => {
-//                 vec![
-//                     Terminal::new(14, TerminalType::Terminal, BlockPos::new(-2, 108, 112)),
-//                     Terminal::new(15, TerminalType::Terminal, BlockPos::new(-2, 118, 93)),
-//                     Terminal::new(16, TerminalType::Terminal, BlockPos::new(18, 122, 93)),
-//                     Terminal::new(17, TerminalType::Terminal, BlockPos::new(-2, 108, 77)),
-//                     Terminal::new(18, TerminalType::Lever, BlockPos::new(14, 120, 55)).with_target(BlockPos::new(14, 122, 55)),
-//                     Terminal::new(19, TerminalType::Lever, BlockPos::new(2, 120, 55)).with_target(BlockPos::new(2, 122, 55)),
-//                 ]
-//             }
-//             Section::S4 => {
-//                 vec![
-//                     Terminal::new(20, TerminalType::Terminal, BlockPos::new(41, 108, 30)),
-//                     Terminal::new(21, TerminalType::Terminal, BlockPos::new(44, 120, 30)),
-//                     Terminal::new(22, TerminalType::Terminal, BlockPos::new(67, 108, 30)),
-//                     Terminal::new(23, TerminalType::Terminal, BlockPos::new(72, 114, 47)),
-//                     Terminal::new(24, TerminalType::Lever, BlockPos::new(84, 119, 34)).with_target(BlockPos::new(84, 121, 34)),
-//                     Terminal::new(25, TerminalType::Lever, BlockPos::new(86, 126, 46)).with_target(BlockPos::new(86, 128, 46)),
-//                 ]
-//             }
-//         }
-//     }
+        if section == Section::S1 {
+            for player in world.players.values_mut() {
+                player.send_message("§4[BOSS] Goldor§c: Who dares trespass into my domain?");
+            }
+        }
+        self.start_section(world, section);
+    }
 
-//     fn get_device_locations(&self) -> Vec<Terminal> {
-//         // Device locations are the same regardless of section - they're all placed at once
-//         vec![
-//             Terminal::new(100, TerminalType::SimonSays, BlockPos::new(110, 118, 91)), // S1 Simon Says
-//             Terminal::new(101, TerminalType::Lamps, BlockPos::new(60, 130, 142)),     // S2 Lamps
-//             Terminal::new(102, TerminalType::Align, BlockPos::new(-2, 118, 74)),      // S3 Align
-//             Terminal::new(103, TerminalType::ShootTarget, BlockPos::new(63, 125, 34)), // S4 Shoot Target
-//         ]
-//     }
+    fn start_section(&mut self, world: &mut World, section: Section) {
+        self.current_section = section;
+        self.section_started_at = world.tick_count;
+        self.terminals = section.terminals();
+        for terminal in self.terminals.iter_mut() {
+            terminal.spawn_armor_stands(world);
+        }
+        if section == Section::S1 {
+            self.simon_says.load(world);
+        }
+    }
 
-//     pub fn get_terminal_display_name(&self, terminal_type: &TerminalType) -> String {
-//         match terminal_type {
-//             TerminalType::Terminal => "§cInactive Terminal".to_string(),
-//             TerminalType::Lever => "§cNot Activated".to_string(),
-//             TerminalType::SimonSays => "§cSimon Says Device".to_string(),
-//             TerminalType::Lamps => "§cLamps Device".to_string(),
-//             TerminalType::Align => "§cAlign Device".to_string(),
-//             TerminalType::ShootTarget => "§cShoot Target Device".to_string(),
-//         }
-//     }
+    pub fn tick(&mut self, world: &mut World) {
+        if self.started && self.current_section == Section::S1 {
+            self.simon_says.tick(world);
+        }
+    }
 
-//     pub fn get_terminal_down_name(&self, terminal_type: &TerminalType) -> Option<String> {
-//         match terminal_type {
-//             TerminalType::Terminal => Some("§e§lCLICK HERE".to_string()),
-//             TerminalType::Lever => None,
-//             _ => None,
-//         }
-//     }
+    /// Removes all the stands and stops the phase, the gates are left as they are.
+    pub fn reset(&mut self, world: &mut World) {
+        for terminal in self.terminals.iter_mut() {
+            terminal.destroy_armor_stands(world);
+        }
+        self.terminals.clear();
+        self.open_terminals.clear();
+        self.simon_says.unload(world);
+        self.started = false;
+    }
 
+    /// Puts the player at the start of a section.
+    pub fn teleport_to_section(player: &mut Player, section: Section) {
+        let (position, yaw) = section.start_position();
+        player.position = position;
+        player.last_position = position;
+        player.yaw = yaw;
+        player.write_packet(&PositionLook {
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            yaw,
+            pitch: 0.0,
+            flags: 0,
+        });
+        player.world_mut().send_chunks_in_view(player);
+    }
 
-//     pub fn terminal_completed(&mut self, progress: (usize, usize)) {
-//         if progress.0 == progress.1 {
-//             self.next_section();
-//         }
-//     }
+    /// (completed, total) for the current section.
+    pub fn get_term_progress(&self) -> (usize, usize) {
+        let completed = self.terminals.iter().filter(|terminal| terminal.completed).count();
+        (completed, self.terminals.len())
+    }
 
-//     pub fn next_section(&mut self) {
-//         self.has_melody = false;
-        
-//         match self.current_section {
-//             Section::S1 => {
-//                 self.current_section = Section::S2;
-//             }
-//             Section::S2 => {
-//                 self.current_section = Section::S3;
-//             }
-//             Section::S3 => {
-//                 self.current_section = Section::S4;
-//             }
-//             Section::S4 => {
-//                 self.reset_terminals();
-//                 return;
-//             }
-//         }
-//     }
+    /// Right clicking a terminal or device stand.
+    pub fn interact(&mut self, world: &mut World, player: &mut Player, terminal_id: u32) {
+        let Some(terminal) = self.terminals.iter().find(|terminal| terminal.id == terminal_id) else {
+            return;
+        };
+        if terminal.completed {
+            return;
+        }
 
-//     pub fn get_term_progress(&self) -> (usize, usize) {
-//         let mut completed_count = 0;
-        
-//         for terminal in &self.terminals {
-//             if terminal.completed {
-//                 completed_count += 1;
-//             }
-//         }
-        
-//         // Add device completion
-//         let device = self.get_device_for_section();
-//         if let Some(device) = device {
-//             if device.completed {
-//                 completed_count += 1;
-//             }
-//         }
-        
-//         (completed_count, self.terminals.len() + 1)
-//     }
+        match terminal.terminal_type {
+            TerminalType::Terminal => {
+                if self.open_terminals.get(&player.client_id) == Some(&terminal_id) && matches!(player.current_ui, UI::TerminalUI { .. }) {
+                    return;
+                }
+                let mut rng = rand::rng();
+                let typ = *TERMINAL_GUIS.choose(&mut rng).unwrap();
+                let rand = rng.random_range(if typ == terminal::TerminalType::Select { 0..=15 } else { 0..=9 });
+                player.current_terminal = Some(terminal::Terminal::new(typ, rand));
+                player.open_ui(UI::TerminalUI { typ, rand });
+                self.open_terminals.insert(player.client_id, terminal_id);
+            }
+            // levers and Simon Says are clicked on their blocks
+            TerminalType::Lever | TerminalType::SimonSays => {}
+            _ => self.complete(world, terminal_id, &player.profile.username),
+        }
+    }
 
-//     fn get_device_for_section(&self) -> Option<&Terminal> {
-//         match self.current_section {
-//             Section::S1 => self.get_device(TerminalType::SimonSays),
-//             Section::S2 => self.get_device(TerminalType::Lamps),
-//             Section::S3 => self.get_device(TerminalType::Align),
-//             Section::S4 => self.get_device(TerminalType::ShootTarget),
-//         }
-//     }
+    /// Called once a player solves the terminal gui they had open.
+    pub fn terminal_gui_completed(&mut self, world: &mut World, player: &Player) {
+        if let Some(terminal_id) = self.open_terminals.remove(&player.client_id) {
+            self.complete(world, terminal_id, &player.profile.username);
+        }
+    }
 
-//     pub fn get_device(&self, device_type: TerminalType) -> Option<&Terminal> {
-//         self.devices.iter().find(|device| device.terminal_type == device_type)
-//     }
+    /// Returns false if `block_pos` isn't one of the current section's levers.
+    pub fn pull_lever(&mut self, world: &mut World, player: &Player, block_pos: &BlockPos) -> bool {
+        let Some(terminal) = self.terminals.iter().find(|terminal| terminal.target_position.as_ref() == Some(block_pos)) else {
+            return false;
+        };
+        let terminal_id = terminal.id;
+        self.complete(world, terminal_id, &player.profile.username);
+        true
+    }
 
-//     pub fn reset_terminals(&mut self) {
-//         self.terminals.clear();
-//         self.devices.clear();
-//     }
-// }
+    /// A click on the Simon Says board.
+    pub fn click_simon_says(&mut self, world: &mut World, player: &Player, block_pos: &BlockPos) {
+        if !self.started || self.current_section != Section::S1 {
+            return;
+        }
+        match self.simon_says.handle_button_click(world, player, *block_pos) {
+            Some(SimonSaysAction::Fail) => {
+                let message = format!("§c{} failed Simon Says!", player.profile.username);
+                for player in world.players.values_mut() {
+                    player.send_message(&message);
+                    player.write_packet(&SoundEffect {
+                        sound: Sounds::NotePling.id(),
+                        volume: 1.0,
+                        pitch: 0.1,
+                        pos_x: block_pos.x as f64,
+                        pos_y: block_pos.y as f64,
+                        pos_z: block_pos.z as f64,
+                    });
+                }
+            }
+            Some(SimonSaysAction::Completed) => {
+                let splits = match self.simon_says.get_splits_string() {
+                    Some(splits) if self.simon_says.is_skip => format!("§8[P3] §7Simon Says §e(skip)§7: §a{}", splits),
+                    Some(splits) => format!("§8[P3] §7Simon Says: §a{}", splits),
+                    None => "§8[P3] §7Simon Says broke!".to_string(),
+                };
+                for player in world.players.values_mut() {
+                    player.send_message(&splits);
+                }
+                if let Some(device) = self.terminals.iter().find(|terminal| terminal.terminal_type == TerminalType::SimonSays) {
+                    self.complete(world, device.id, &player.profile.username);
+                }
+            }
+            Some(SimonSaysAction::Continue) | None => {}
+        }
+    }
 
-// impl Default for P3Manager {
-//     fn default() -> Self {
-//         Self::new()
-//     }
-// }
+    fn complete(&mut self, world: &mut World, terminal_id: u32, username: &str) {
+        let Some(terminal) = self.terminals.iter_mut().find(|terminal| terminal.id == terminal_id && !terminal.completed) else {
+            return;
+        };
+        terminal.complete(world);
+
+        let action = match terminal.terminal_type {
+            TerminalType::Terminal => "activated a terminal",
+            TerminalType::Lever => "activated a lever",
+            _ => "completed a device",
+        };
+        let position = terminal.position.as_dvec3();
+        let (completed, total) = self.get_term_progress();
+        let color = if completed == total { "§a" } else { "§c" };
+        let message = format!("§b{} §a{}! ({}{}§a/{})", username, action, color, completed, total);
+        for player in world.players.values_mut() {
+            player.send_message(&message);
+            player.write_packet(&SoundEffect {
+                sound: Sounds::NotePling.id(),
+                volume: 1.0,
+                pitch: 1.5,
+                pos_x: position.x,
+                pos_y: position.y,
+                pos_z: position.z,
+            });
+        }
+
+        if completed == total {
+            self.finish_section(world);
+        }
+    }
+
+    /// Drops the section's gate and moves on to the next one, or ends the phase after the last.
+    fn finish_section(&mut self, world: &mut World) {
+        let section = self.current_section;
+        let (from, to) = section.gate();
+        world.fill_blocks(Blocks::Air, from, to);
+
+        let split = format!(
+            "§8[P3] §7Section {} took §a{}§7.",
+            section.number(),
+            format_ticks(world.tick_count - self.section_started_at),
+        );
+        let next = section.next();
+        for player in world.players.values_mut() {
+            player.send_message(if next.is_some() { "§aThe gate has been destroyed!" } else { "§aThe Core entrance is opening!" });
+            player.send_message(&split);
+            if next.is_none() {
+                player.send_message(&format!("§8[P3] §7Goldor phase took §a{}§7.", format_ticks(world.tick_count - self.phase_started_at)));
+            }
+            player.write_packet(&SoundEffect {
+                sound: Sounds::RandomExplode.id(),
+                volume: 1.0,
+                pitch: 1.0,
+                pos_x: from.x as f64,
+                pos_y: from.y as f64,
+                pos_z: from.z as f64,
+            });
+        }
+
+        for terminal in self.terminals.iter_mut() {
+            terminal.destroy_armor_stands(world);
+        }
+        self.open_terminals.clear();
+        self.simon_says.unload(world);
+        match next {
+            Some(next) => self.start_section(world, next),
+            None => {
+                self.reset(world);
+                // the core opening is the end of the boss for now
+                world.server_mut().dungeon.finish();
+            }
+        }
+    }
+}
+
+impl Default for P3Manager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
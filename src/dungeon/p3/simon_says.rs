@@ -1,484 +1,327 @@
-// use crate::server::block::block_position::BlockPos;
-// use crate::server::block::blocks::Blocks;
-// use crate::server::block::metadata::BlockMetadata;
-// use crate::server::player::player::ClientId;
-// use crate::server::world::World;
-// use rand::Rng;
-// use std::collections::HashMap;
-
-// #[derive(Debug, Clone, Copy, PartialEq)]
-// pub struct SimonSaysButton {
-//     pub x: i32,
-//     pub y: i32,
-//     pub z: i32,
-// }
-
-// #[derive(Debug, Clone, PartialEq)]
-// pub enum SimonSaysAction {
-//     BlockClick,
-//     ShowSolution,
-//     Continue,
-//     Fail,
-//     Completed,
-//     SequenceCompleted,
-// }
-
-// impl SimonSaysButton {
-//     pub fn new(x: i32, y: i32, z: i32) -> Self {
-//         Self { x, y, z }
-//     }
-
-//     pub fn to_block_pos(&self) -> BlockPos {
-//         BlockPos::new(self.x, self.y, self.z)
-//     }
-// }
-
-// pub struct SimonSays {
-//     pub completed: bool,
-//     start_clicks: u32,
-//     progress: usize,
-//     pub solution: Vec<SimonSaysButton>,
-//     start_time: Option<u64>,
-//     last_clicked: Option<u64>,
-//     pub showing_solution: bool,
-//     pub is_skip: bool,
-//     rng: rand::rngs::ThreadRng,
-    
-//     // Per-player deduplication system
-//     player_clicked_this_tick: HashMap<ClientId, (u64, BlockPos)>, // (tick, block_pos)
-    
-//     // Timing system for solution display
-//     pub solution_display_start: Option<u64>,
-//     solution_display_step: usize,
-//     pub pending_actions: Vec<(u64, SolutionAction)>,
-    
-//     // Split timing system
-//     clicking_phases: Vec<(u64, u64)>, // (first_click_time, last_click_time) for each clicking phase
-//     current_clicking_start: Option<u64>, // When the current clicking phase started
-//     puzzle_start_time: Option<u64>, // When the puzzle actually started (after first solution display)
-// }
-
-// #[derive(Debug, Clone)]
-// pub enum SolutionAction {
-//     RemoveButtons,
-//     ShowSeaLantern(BlockPos),
-//     HideSeaLantern(BlockPos),
-//     ReplaceButtons,
-//     StartPuzzle,
-// }
-
-// // Constants from Java code
-// pub const BOT_LEFT: SimonSaysButton = SimonSaysButton { x: 110, y: 120, z: 92 };
-// pub const START_BUTTON: SimonSaysButton = SimonSaysButton { x: 110, y: 121, z: 91 };
-
-// impl SimonSays {
-//     pub fn new() -> Self {
-//         Self {
-//             completed: false,
-//             start_clicks: 0,
-//             progress: 0,
-//             solution: Vec::new(),
-//             start_time: None,
-//             last_clicked: None,
-//             showing_solution: false,
-//             is_skip: false,
-//             rng: rand::rng(),
-            
-//             // Per-player deduplication system
-//             player_clicked_this_tick: HashMap::new(),
-            
-//             // Timing system
-//             solution_display_start: None,
-//             solution_display_step: 0,
-//             pending_actions: Vec::new(),
-            
-//             // Split timing system
-//             clicking_phases: Vec::new(),
-//             current_clicking_start: None,
-//             puzzle_start_time: None,
-//         }
-//     }
-
-//     pub fn should_run(&self) -> bool {
-//         // Only run if puzzle is not completed and we're in the Simon Says area
-//         // TODO: Check if we're in P3 phase
-//         !self.completed
-//     }
-    
-//     pub fn is_in_simon_says_area(&self, pos: BlockPos) -> bool {
-//         // Check if the click is in the Simon Says button area
-//         // Simon Says area is around coordinates 110, 120-123, 91-95
-//         pos.x >= 110 && pos.x <= 113 &&
-//         pos.y >= 120 && pos.y <= 123 &&
-//         pos.z >= 91 && pos.z <= 95
-//     }
-
-//     pub fn handle_button_click(&mut self, pos: BlockPos, player_id: ClientId, current_tick: u64) -> Option<SimonSaysAction> {
-        
-//         if !self.should_run() {
-//             return None;
-//         }
-        
-//         if !self.is_in_simon_says_area(pos) {
-//             return None;
-//         }
-
-//         // Per-player deduplication: only drop same-tick, same-block duplicates
-//         // But allow clicks on different blocks in the same tick
-//         if let Some((last_tick, last_pos)) = self.player_clicked_this_tick.get(&player_id) {
-//             if *last_tick == current_tick && *last_pos == pos {
-//                 return Some(SimonSaysAction::BlockClick);
-//             }
-//         }
-
-//         // Record this click for deduplication
-//         self.player_clicked_this_tick.insert(player_id, (current_tick, pos));
-
-
-//         if self.showing_solution {
-//             return Some(SimonSaysAction::BlockClick); // Block clicks during solution display
-//         }
-
-//         if self.is_start_button(pos) {
-//             self.handle_start_button_click(current_tick);
-//             return Some(SimonSaysAction::ShowSolution);
-//         } else if !self.solution.is_empty() && self.is_solution_button(pos) {
-//             let sequence_completed = self.handle_solution_button_click(player_id, current_tick);
-            
-//             // Check if puzzle is completed
-//             if self.completed {
-//                 return Some(SimonSaysAction::Completed);
-//             }
-            
-//             // Check if we completed a sequence
-//             if sequence_completed {
-//                 return Some(SimonSaysAction::SequenceCompleted);
-//             }
-            
-//             return Some(SimonSaysAction::Continue);
-//         } else if !self.solution.is_empty() {
-//             self.fail(player_id);
-//             return Some(SimonSaysAction::Fail);
-//         }
-
-//         None
-//     }
-
-//     fn is_start_button(&self, pos: BlockPos) -> bool {
-//         pos == START_BUTTON.to_block_pos()
-//     }
-
-//     fn is_solution_button(&self, pos: BlockPos) -> bool {
-//         if self.progress >= self.solution.len() {
-//             return false;
-//         }
-
-//         let expected_pos = self.solution[self.progress].to_block_pos();
-//         let actual_pos = BOT_LEFT.to_block_pos().add(expected_pos);
-        
-        
-//         // Check exact position match first
-//         if pos == actual_pos {
-//             return true;
-//         }
-        
-//         // If no exact match, check if it's the button block with proper facing
-//         // This allows for clicks on the button face rather than strict coordinates
-//         // The button should be facing the correct direction based on its metadata
-//         // For now, we'll accept the exact position match as the primary check
-//         // TODO: Add proper button facing detection if needed
-        
-//         false
-//     }
-
-//     fn handle_start_button_click(&mut self, current_tick: u64) {
-//         let current_time = current_tick * 50; // Convert ticks to milliseconds (50ms per tick)
-
-//         // Check cooldown (300ms in Java)
-//         if let Some(last_clicked) = self.last_clicked {
-//             if current_time - last_clicked < 300 {
-//                 return;
-//             }
-//         }
-
-//         if self.start_clicks == 0 {
-//             self.start_time = Some(current_time);
-//         }
-
-//         self.start_clicks += 1;
-//         self.last_clicked = Some(current_time);
-//         self.progress = 0;
-
-//         match self.start_clicks {
-//             3 => {
-//                 // Single skip
-//                 self.is_skip = true;
-//                 self.fill_solution(3);
-//             }
-//             7 => {
-//                 // Double
-//                 self.fill_solution(3);
-//             }
-//             15 => {
-//                 // Triple
-//                 self.fill_solution(4);
-//             }
-//             18 | 21 => {
-//                 // Quad
-//                 self.fill_solution(5);
-//             }
-//             24 | 58 => {
-//                 // I1 - complete puzzle
-//                 self.reset(true);
-//                 self.completed = true;
-//                 // TODO: Mark terminal as completed
-//             }
-//             _ => {
-//                 // Default
-//                 self.fill_solution(2);
-//             }
-//         }
-//     }
-
-//     fn handle_solution_button_click(&mut self, _player_id: ClientId, current_tick: u64) -> bool {
-//         self.progress += 1;
-
-//         // Start a new clicking phase when the first button is clicked in a sequence
-//         if self.progress == 1 {
-//             self.start_clicking_phase(current_tick);
-//         }
-
-
-//         // TODO: Play success sound
-//         // Utils.sendPacket(player, new S29PacketSoundEffect("note.pling", ...));
-
-//         if self.progress >= 5 {
-//             // Puzzle completed
-//             if let Some(start_time) = self.start_time {
-//                 let _total_time = (current_tick * 50 - start_time) as f64 / 1000.0;
-//                 // TODO: Send completion message
-//             }
-            
-//             // End the current clicking phase
-//             self.end_clicking_phase(current_tick);
-            
-//             // Mark as completed but don't reset yet - we need the timing data for the completion message
-//             self.completed = true;
-//             // TODO: Mark terminal as completed
-//             return false; // Not a sequence completion, but puzzle completion
-//         } else if self.progress >= self.solution.len() {
-//             // Sequence completed, add next button
-            
-//             // End the current clicking phase
-//             self.end_clicking_phase(current_tick);
-            
-//             self.progress = 0;
-//             self.add_solution();
-//             return true; // Sequence completed
-//         }
-        
-//         false // No sequence completion yet
-//     }
-
-//     fn fail(&mut self, _player_id: ClientId) {
-//         self.reset(false);
-//         // TODO: Play fail sound and send message
-//         // Utils.playSoundAll("note.pling", 1f, 0.1f);
-//         // ChatUtils.sendMessageToAllPlayers(format!("{} failed SS! ♿", player_name));
-//     }
-
-//     pub fn show_solution(&mut self, world: &mut World) {
-//         if self.showing_solution || self.solution.is_empty() {
-//             return;
-//         }
-
-//         self.showing_solution = true;
-//         // Note: remove_buttons will be handled by the world tick processor
-        
-//         // Schedule solution display actions
-//         self.pending_actions.clear();
-//         let current_tick = world.tick_count;
-        
-//         // First, remove buttons
-//         self.pending_actions.push((current_tick, SolutionAction::RemoveButtons));
-        
-//         for (i, button_pos) in self.solution.iter().enumerate() {
-//             let display_pos = BOT_LEFT.to_block_pos().add(button_pos.to_block_pos()).add(BlockPos::new(1, 0, 0));
-            
-//             // Show sea lantern at position i * 8 ticks
-//             self.pending_actions.push((current_tick + (i * 8) as u64, SolutionAction::ShowSeaLantern(display_pos)));
-            
-//             // Hide sea lantern at (i + 1) * 8 ticks
-//             self.pending_actions.push((current_tick + ((i + 1) * 8) as u64, SolutionAction::HideSeaLantern(display_pos)));
-//         }
-        
-//         // Replace buttons after showing solution
-//         let replace_tick = current_tick + (8 * (self.solution.len() + 1)) as u64;
-//         self.pending_actions.push((replace_tick, SolutionAction::ReplaceButtons));
-        
-//         // Set showing_solution to false after solution display completes
-//         let end_tick = replace_tick + 1;
-//         self.pending_actions.push((end_tick, SolutionAction::StartPuzzle));
-        
-//         self.solution_display_start = Some(current_tick);
-//     }
-
-//     /// Get actions that should be processed this tick
-//     pub fn get_pending_actions(&mut self, current_tick: u64) -> Vec<SolutionAction> {
-//         let mut actions_to_process = Vec::new();
-        
-//         // Collect actions that should be processed this tick
-//         for (tick, action) in &self.pending_actions {
-//             if *tick <= current_tick {
-//                 actions_to_process.push(action.clone());
-//             }
-//         }
-        
-//         if !actions_to_process.is_empty() {
-//         }
-        
-//         // Remove processed actions
-//         self.pending_actions.retain(|(tick, _)| *tick > current_tick);
-        
-//         actions_to_process
-//     }
-
-//     pub fn get_button_positions(&self) -> Vec<BlockPos> {
-//         let mut positions = Vec::new();
-//         for y in 0..4 {
-//             for z in 0..4 {
-//                 let pos = BOT_LEFT.to_block_pos().add(BlockPos::new(0, y, z));
-//                 positions.push(pos);
-//             }
-//         }
-//         positions
-//     }
-
-//     fn fill_solution(&mut self, count: usize) {
-//         self.solution.clear();
-//         for _ in 0..count {
-//             if self.solution.len() > 9 {
-//                 return;
-//             }
-//             if let Some(pos) = self.random_pos(4, 4) {
-//                 self.solution.push(pos);
-//             }
-//         }
-//     }
-
-//     fn add_solution(&mut self) {
-//         if self.solution.len() > 9 {
-//             return;
-//         }
-//         if let Some(pos) = self.random_pos(4, 4) {
-//             self.solution.push(pos);
-//         }
-//     }
-
-//     fn random_pos(&mut self, max_y: usize, max_z: usize) -> Option<SimonSaysButton> {
-//         if self.solution.len() > 9 {
-//             return None;
-//         }
-
-//         let random_pos = SimonSaysButton::new(
-//             0,
-//             self.rng.random_range(0..max_y as i32),
-//             self.rng.random_range(0..max_z as i32),
-//         );
-
-//         if self.contains(random_pos) {
-//             self.random_pos(max_y, max_z) // Recursive call to try again
-//         } else {
-//             Some(random_pos)
-//         }
-//     }
-
-//     fn contains(&self, pos: SimonSaysButton) -> bool {
-//         self.solution.iter().any(|p| {
-//             p.x == pos.x && p.y == pos.y && p.z == pos.z
-//         })
-//     }
-
-//     pub fn reset(&mut self, complete: bool) {
-//         self.solution.clear();
-//         self.progress = 0;
-//         self.start_clicks = 0;
-//         self.is_skip = false;
-//         self.showing_solution = false;
-//         self.completed = complete;
-        
-//         // Clear per-player deduplication
-//         self.player_clicked_this_tick.clear();
-        
-//         // Clear timing system
-//         self.solution_display_start = None;
-//         self.solution_display_step = 0;
-//         self.pending_actions.clear();
-        
-//         // Clear split timing system
-//         self.clicking_phases.clear();
-//         self.current_clicking_start = None;
-//         self.puzzle_start_time = None;
-//     }
-
-//     pub fn tick(&mut self, current_tick: u64) {
-//         // Clear per-player deduplication data for previous ticks
-//         self.player_clicked_this_tick.retain(|_, (tick, _)| *tick == current_tick);
-//     }
-    
-//     pub fn start_puzzle(&mut self, current_tick: u64) {
-//         // Record when the puzzle actually starts (after first solution display)
-//         if self.puzzle_start_time.is_none() {
-//             self.puzzle_start_time = Some(current_tick);
-//         }
-//     }
-    
-//     pub fn start_clicking_phase(&mut self, current_tick: u64) {
-//         // Start a new clicking phase when the first button is clicked in a sequence
-//         self.current_clicking_start = Some(current_tick);
-//     }
-    
-//     pub fn end_clicking_phase(&mut self, current_tick: u64) {
-//         // End the current clicking phase when the sequence is completed
-//         if let Some(clicking_start) = self.current_clicking_start {
-//             self.clicking_phases.push((clicking_start, current_tick));
-//         }
-//         self.current_clicking_start = None;
-//     }
-    
-//     pub fn get_splits_string(&self) -> Option<String> {
-//         if self.clicking_phases.is_empty() {
-//             return None;
-//         }
-        
-//         let mut splits = Vec::new();
-        
-//         // Calculate splits (time for each clicking phase - only the actual clicking time)
-//         for &(clicking_start, clicking_end) in &self.clicking_phases {
-//             let clicking_duration = (clicking_end - clicking_start) as f64 / 20.0; // Convert ticks to seconds
-//             splits.push(clicking_duration);
-//         }
-        
-//         // Calculate total time from puzzle start to completion (including all waiting)
-//         let total_time = if let (Some(puzzle_start), Some((_, last_clicking_end))) = (self.puzzle_start_time, self.clicking_phases.last()) {
-//             (last_clicking_end - puzzle_start) as f64 / 20.0
-//         } else {
-//             0.0
-//         };
-        
-//         // Format splits as "0.5 | 1.3 | 1.5 | 1.8 | Total: 4.1s"
-//         let splits_str = splits.iter()
-//             .map(|&split| format!("{:.1}", split))
-//             .collect::<Vec<_>>()
-//             .join(" | ");
-        
-//         Some(format!("{} | Total: {:.1}s", splits_str, total_time))
-//     }
-// }
-
-// impl Default for SimonSays {
-//     fn default() -> Self {
-//         Self::new()
-//     }
-// }
-
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_parameter::ButtonDirection;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::player::player::{ClientId, Player};
+use crate::server::utils::direction::Direction;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use rand::Rng;
+use std::collections::HashMap;
+
+// Simon Says, the S1 device: a 4x4 grid of buttons on a wall of obsidian with a start button next to it.
+// Clicking start flashes the sequence on sea lanterns behind the buttons (the buttons are taken away while it flashes),
+// then the buttons have to be clicked in the same order. Each finished sequence adds a button and flashes again,
+// clicking the last button of a 5 long sequence completes the device. A wrong button resets everything.
+//
+// Clicking start again restarts with a new sequence, and like on Hypixel some amounts of start clicks change it:
+// the 3rd click skips the first round (starts at 3 buttons), and the 24th or 58th click breaks the device and completes it outright.
+
+/// Bottom left button of the grid, the buttons go up in y and z from here and the lanterns are one block behind in x.
+pub const BOT_LEFT: BlockPos = BlockPos { x: 110, y: 120, z: 92 };
+pub const START_BUTTON: BlockPos = BlockPos { x: 110, y: 121, z: 91 };
+
+const GRID_SIZE: i32 = 4;
+/// Buttons in the last sequence.
+const SEQUENCE_LENGTH: usize = 5;
+/// Ticks each lantern is lit for while flashing.
+const FLASH_TICKS: u64 = 8;
+/// Ticks between start clicks that count, the 300ms from hypixel.
+const START_COOLDOWN: u64 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimonSaysAction {
+    Continue,
+    Fail,
+    Completed,
+}
+
+#[derive(Debug, Clone)]
+pub enum SolutionAction {
+    RemoveButtons,
+    ShowSeaLantern(BlockPos),
+    HideSeaLantern(BlockPos),
+    ReplaceButtons,
+    StartPuzzle,
+}
+
+pub struct SimonSays {
+    pub completed: bool,
+    start_clicks: u32,
+    progress: usize,
+    /// Offsets from BOT_LEFT of the buttons, in order.
+    pub solution: Vec<BlockPos>,
+    last_start_click: Option<u64>,
+    pub showing_solution: bool,
+    /// The first round was skipped.
+    pub is_skip: bool,
+
+    /// The client can send the same click more than once in a tick.
+    player_clicked_this_tick: HashMap<ClientId, (u64, BlockPos)>,
+    pub pending_actions: Vec<(u64, SolutionAction)>,
+
+    /// (first click, last click) of every sequence that was clicked through.
+    clicking_phases: Vec<(u64, u64)>,
+    current_clicking_start: Option<u64>,
+    /// When the first sequence finished flashing.
+    puzzle_start_time: Option<u64>,
+}
+
+impl SimonSays {
+    pub fn new() -> Self {
+        Self {
+            completed: false,
+            start_clicks: 0,
+            progress: 0,
+            solution: Vec::new(),
+            last_start_click: None,
+            showing_solution: false,
+            is_skip: false,
+            player_clicked_this_tick: HashMap::new(),
+            pending_actions: Vec::new(),
+            clicking_phases: Vec::new(),
+            current_clicking_start: None,
+            puzzle_start_time: None,
+        }
+    }
+
+    /// Builds the board and registers the buttons.
+    pub fn load(&mut self, world: &mut World) {
+        self.reset(false);
+        for pos in self.get_button_positions().into_iter().chain([START_BUTTON]) {
+            world.set_block_at(Blocks::Obsidian, pos.x + 1, pos.y, pos.z);
+            world.interactable_blocks.insert(pos, BlockInteractAction::SimonSays);
+        }
+        self.place_buttons(world);
+        world.set_block_at(Blocks::StoneButton { direction: ButtonDirection(Direction::West), powered: false }, START_BUTTON.x, START_BUTTON.y, START_BUTTON.z);
+    }
+
+    pub fn unload(&mut self, world: &mut World) {
+        for pos in self.get_button_positions().into_iter().chain([START_BUTTON]) {
+            world.interactable_blocks.remove(&pos);
+        }
+        self.reset(false);
+    }
+
+    pub fn handle_button_click(&mut self, world: &mut World, player: &Player, pos: BlockPos) -> Option<SimonSaysAction> {
+        if self.completed {
+            return None;
+        }
+        let current_tick = world.tick_count;
+
+        // only drop same tick clicks on the same block, different blocks in the same tick are fine
+        if self.player_clicked_this_tick.insert(player.client_id, (current_tick, pos)) == Some((current_tick, pos)) {
+            return None;
+        }
+
+        if pos == START_BUTTON {
+            return self.handle_start_button_click(world, current_tick);
+        }
+        if self.showing_solution || self.solution.is_empty() {
+            return None;
+        }
+
+        if pos != BOT_LEFT.add(self.solution[self.progress]) {
+            self.reset(false);
+            return Some(SimonSaysAction::Fail);
+        }
+        Some(self.handle_solution_button_click(world, player, current_tick))
+    }
+
+    fn handle_start_button_click(&mut self, world: &mut World, current_tick: u64) -> Option<SimonSaysAction> {
+        if self.last_start_click.is_some_and(|last| current_tick - last < START_COOLDOWN) {
+            return None;
+        }
+        self.start_clicks += 1;
+        self.last_start_click = Some(current_tick);
+        self.progress = 0;
+        self.clicking_phases.clear();
+        self.current_clicking_start = None;
+        self.puzzle_start_time = None;
+
+        match self.start_clicks {
+            // skip
+            3 => {
+                self.is_skip = true;
+                self.fill_solution(3);
+            }
+            7 => self.fill_solution(3),
+            15 => self.fill_solution(4),
+            18 | 21 => self.fill_solution(5),
+            // break
+            24 | 58 => {
+                self.reset(true);
+                self.place_buttons(world);
+                return Some(SimonSaysAction::Completed);
+            }
+            _ => self.fill_solution(2),
+        }
+        self.show_solution(world);
+        None
+    }
+
+    fn handle_solution_button_click(&mut self, world: &mut World, player: &Player, current_tick: u64) -> SimonSaysAction {
+        self.progress += 1;
+        if self.progress == 1 {
+            self.current_clicking_start = Some(current_tick);
+        }
+        play_sound(world, player, 1.0 + self.progress as f32 * 0.1);
+
+        if self.progress < self.solution.len() {
+            return SimonSaysAction::Continue;
+        }
+        if let Some(clicking_start) = self.current_clicking_start.take() {
+            self.clicking_phases.push((clicking_start, current_tick));
+        }
+
+        if self.progress >= SEQUENCE_LENGTH {
+            self.completed = true;
+            return SimonSaysAction::Completed;
+        }
+        self.progress = 0;
+        self.add_solution();
+        self.show_solution(world);
+        SimonSaysAction::Continue
+    }
+
+    /// Takes the buttons away and flashes the lanterns one after another, the buttons come back after the last.
+    pub fn show_solution(&mut self, world: &mut World) {
+        if self.solution.is_empty() {
+            return;
+        }
+        self.showing_solution = true;
+
+        let current_tick = world.tick_count;
+        self.pending_actions.clear();
+        self.pending_actions.push((current_tick, SolutionAction::RemoveButtons));
+        // lanterns lit from an earlier flash that was cut short go out again
+        for pos in self.get_button_positions() {
+            self.pending_actions.push((current_tick, SolutionAction::HideSeaLantern(pos.add_x(1))));
+        }
+
+        for (i, offset) in self.solution.iter().enumerate() {
+            let lantern = BOT_LEFT.add(*offset).add_x(1);
+            self.pending_actions.push((current_tick + i as u64 * FLASH_TICKS, SolutionAction::ShowSeaLantern(lantern)));
+            self.pending_actions.push((current_tick + (i as u64 + 1) * FLASH_TICKS, SolutionAction::HideSeaLantern(lantern)));
+        }
+
+        let replace_tick = current_tick + FLASH_TICKS * (self.solution.len() as u64 + 1);
+        self.pending_actions.push((replace_tick, SolutionAction::ReplaceButtons));
+        self.pending_actions.push((replace_tick + 1, SolutionAction::StartPuzzle));
+    }
+
+    /// Runs the solution actions that are due.
+    pub fn tick(&mut self, world: &mut World) {
+        let current_tick = world.tick_count;
+        self.player_clicked_this_tick.retain(|_, (tick, _)| *tick == current_tick);
+
+        let (due, pending) = std::mem::take(&mut self.pending_actions).into_iter().partition(|(tick, _)| *tick <= current_tick);
+        self.pending_actions = pending;
+
+        for (_, action) in due {
+            match action {
+                SolutionAction::RemoveButtons => {
+                    for pos in self.get_button_positions() {
+                        world.set_block_at(Blocks::Air, pos.x, pos.y, pos.z);
+                    }
+                }
+                SolutionAction::ShowSeaLantern(pos) => world.set_block_at(Blocks::SeaLantern, pos.x, pos.y, pos.z),
+                SolutionAction::HideSeaLantern(pos) => world.set_block_at(Blocks::Obsidian, pos.x, pos.y, pos.z),
+                SolutionAction::ReplaceButtons => self.place_buttons(world),
+                SolutionAction::StartPuzzle => {
+                    self.showing_solution = false;
+                    if self.puzzle_start_time.is_none() {
+                        self.puzzle_start_time = Some(current_tick);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_button_positions(&self) -> Vec<BlockPos> {
+        let mut positions = Vec::new();
+        for y in 0..GRID_SIZE {
+            for z in 0..GRID_SIZE {
+                positions.push(BOT_LEFT.add(BlockPos::new(0, y, z)));
+            }
+        }
+        positions
+    }
+
+    fn place_buttons(&self, world: &mut World) {
+        for pos in self.get_button_positions() {
+            world.set_block_at(Blocks::StoneButton { direction: ButtonDirection(Direction::West), powered: false }, pos.x, pos.y, pos.z);
+        }
+    }
+
+    fn fill_solution(&mut self, count: usize) {
+        self.solution.clear();
+        for _ in 0..count {
+            self.add_solution();
+        }
+    }
+
+    fn add_solution(&mut self) {
+        if self.solution.len() >= SEQUENCE_LENGTH {
+            return;
+        }
+        let mut rng = rand::rng();
+        loop {
+            let pos = BlockPos::new(0, rng.random_range(0..GRID_SIZE), rng.random_range(0..GRID_SIZE));
+            if !self.solution.contains(&pos) {
+                self.solution.push(pos);
+                return;
+            }
+        }
+    }
+
+    pub fn reset(&mut self, complete: bool) {
+        self.solution.clear();
+        self.progress = 0;
+        self.start_clicks = 0;
+        self.last_start_click = None;
+        self.is_skip = false;
+        self.showing_solution = false;
+        self.completed = complete;
+        self.player_clicked_this_tick.clear();
+        self.pending_actions.clear();
+        self.clicking_phases.clear();
+        self.current_clicking_start = None;
+        self.puzzle_start_time = None;
+    }
+
+    /// How long each sequence took to click through and the total since the first flash finished,
+    /// formatted like "0.5 | 1.3 | 1.5 | 1.8 | Total: 4.1s".
+    pub fn get_splits_string(&self) -> Option<String> {
+        let (puzzle_start, (_, last_clicking_end)) = (self.puzzle_start_time?, self.clicking_phases.last()?);
+
+        let splits = self.clicking_phases.iter()
+            .map(|(start, end)| format!("{:.1}", (end - start) as f64 / 20.0))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let total = (last_clicking_end - puzzle_start) as f64 / 20.0;
+        Some(format!("{} | Total: {:.1}s", splits, total))
+    }
+}
+
+impl Default for SimonSays {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn play_sound(world: &mut World, player: &Player, pitch: f32) {
+    let Some(player) = world.players.get_mut(&player.client_id) else {
+        return;
+    };
+    let position = player.position;
+    player.write_packet(&SoundEffect {
+        sound: Sounds::NotePling.id(),
+        volume: 1.0,
+        pitch,
+        pos_x: position.x,
+        pos_y: position.y,
+        pos_z: position.z,
+    });
+}
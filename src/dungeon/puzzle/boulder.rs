@@ -0,0 +1,344 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleClick, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::packets::packet_buffer::PacketBuffer;
+use crate::net::protocol::play::clientbound::{DestroyEntites, EntityAttach, SoundEffect, SpawnObject};
+use crate::net::var_int::VarInt;
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_parameter::ButtonDirection;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::entity::entity::{Entity, EntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::IndexedRandom;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Boulder: a 7x6 grid of 3x3 boulders between the entrance side of the room and the chest.
+// Every boulder has a button on each side that has room to stand in, pressing it pushes the boulder one cell away if that cell is free.
+// Once there is a way from the entrance side to the cell in front of the chest the puzzle is solved,
+// the reset button puts every boulder back if the grid gets stuck.
+//
+// Row 0 is next to the chest, the last row is the one players come in from.
+
+const CONFIGURATIONS: [[&str; ROWS]; 4] = [
+    ["#.#.#.#", ".#.#.#.", "#.###.#", ".#...#.", "##.#.##", ".#.#.#."],
+    ["##.#.##", "#.#.#.#", ".#.#.#.", "#.#.#.#", ".#.#.#.", "#.#.#.#"],
+    [".##.##.", "#..#..#", ".#.#.#.", "##...##", ".#.#.#.", "#.#.#.#"],
+    ["#.#.#.#", "##.#.##", "..#.#..", "#.#.#.#", ".#.#.#.", "##.#.##"],
+];
+
+const COLUMNS: usize = 7;
+const ROWS: usize = 6;
+/// Corner of cell (0, 0), cells are 3 blocks along x for each column and 3 along z for each row.
+const GRID_CORNER: BlockPos = BlockPos { x: 5, y: 69, z: 6 };
+const BOULDER_HEIGHT: i32 = 3;
+/// The chest sits just past the first row in front of this column.
+const CHEST_COLUMN: usize = 3;
+const RESET_BUTTON: BlockPos = BlockPos { x: 15, y: 70, z: 26 };
+const BOULDER_BLOCK: Blocks = Blocks::Stone { variant: 0 };
+/// Ticks a push takes.
+const PUSH_TICKS: u32 = 10;
+
+type Cell = (usize, usize);
+
+pub struct Boulder {
+    configuration: [&'static str; ROWS],
+    boulders: HashSet<Cell>,
+    /// World position of every button on a boulder, to the boulder and the way it gets pushed.
+    buttons: HashMap<BlockPos, (Cell, (isize, isize))>,
+    reset_button: BlockPos,
+    /// Pushes are ignored until the last one has finished moving.
+    moving_until: u64,
+}
+
+impl Boulder {
+    pub fn new() -> Boulder {
+        let configuration = *CONFIGURATIONS.choose(&mut seeded_rng()).expect("there are configurations");
+        Boulder {
+            configuration,
+            boulders: boulders_in(&configuration),
+            buttons: HashMap::new(),
+            reset_button: RESET_BUTTON,
+            moving_until: 0,
+        }
+    }
+
+    /// Sets every cell to the boulders as they are now, without animating anything.
+    fn load_cells(&mut self, world: &mut World, room: &Room) {
+        for row in 0..ROWS {
+            for column in 0..COLUMNS {
+                let block = if self.boulders.contains(&(column, row)) { BOULDER_BLOCK } else { Blocks::Air };
+                for_cell_blocks(room, (column, row), |x, y, z| world.set_block_at(block, x, y, z));
+            }
+        }
+        self.place_buttons(world, room);
+    }
+
+    /// Takes the old buttons away and puts new ones on every side of every boulder a player could stand at.
+    fn place_buttons(&mut self, world: &mut World, room: &Room) {
+        for (pos, _) in self.buttons.drain() {
+            if matches!(world.get_block_at(pos.x, pos.y, pos.z), Blocks::StoneButton { .. }) {
+                world.set_block_at(Blocks::Air, pos.x, pos.y, pos.z);
+            }
+            world.interactable_blocks.remove(&pos);
+        }
+
+        for &(column, row) in &self.boulders {
+            let corner = cell_corner((column, row));
+            // the side the button is on, and the way pressing it pushes the boulder
+            let sides = [
+                (corner.add_x(1).add_z(-1), Direction::North, (0, 1)),
+                (corner.add_x(1).add_z(3), Direction::South, (0, -1)),
+                (corner.add_x(-1).add_z(1), Direction::West, (1, 0)),
+                (corner.add_x(3).add_z(1), Direction::East, (-1, 0)),
+            ];
+            for (button, facing, push) in sides {
+                let (side_column, side_row) = (column as isize - push.0, row as isize - push.1);
+                let in_grid = (0..COLUMNS as isize).contains(&side_column) && (0..ROWS as isize).contains(&side_row);
+                // outside the grid only the entrance side can be stood on
+                let side_free = if in_grid {
+                    !self.boulders.contains(&(side_column as usize, side_row as usize))
+                } else {
+                    side_row == ROWS as isize
+                };
+                if !side_free {
+                    continue;
+                }
+
+                let pos = room.get_world_block_pos(&button.add_y(1));
+                let direction = ButtonDirection(facing).rotate(room.rotation);
+                world.set_block_at(Blocks::StoneButton { direction, powered: false }, pos.x, pos.y, pos.z);
+                world.interactable_blocks.insert(pos, BlockInteractAction::Puzzle);
+                self.buttons.insert(pos, ((column, row), push));
+            }
+        }
+    }
+
+    /// Moves a boulder to the next cell, the blocks slide over with falling block entities.
+    fn push(&mut self, world: &mut World, room: &Room, from: Cell, to: Cell) {
+        self.boulders.remove(&from);
+        self.boulders.insert(to);
+
+        let from_corner = room.get_world_block_pos(&cell_corner(from));
+        let to_corner = room.get_world_block_pos(&cell_corner(to));
+        let step = DVec3::new(
+            (to_corner.x - from_corner.x) as f64 / PUSH_TICKS as f64,
+            0.0,
+            (to_corner.z - from_corner.z) as f64 / PUSH_TICKS as f64,
+        );
+
+        let mut arriving = Vec::new();
+        for_cell_blocks(room, from, |x, y, z| {
+            world.set_block_at(Blocks::Air, x, y, z);
+            let _ = world.spawn_entity(
+                DVec3::new(x as f64 + 0.5, y as f64 - BOULDER_ENTITY_OFFSET, z as f64 + 0.5),
+                {
+                    let mut metadata = EntityMetadata::new(EntityVariant::Bat { hanging: false });
+                    metadata.is_invisible = true;
+                    metadata
+                },
+                BoulderEntityImpl::new(BOULDER_BLOCK, step, PUSH_TICKS),
+            );
+        });
+        for_cell_blocks(room, to, |x, y, z| {
+            world.set_block_at(Blocks::Barrier, x, y, z);
+            arriving.push((x, y, z));
+        });
+
+        world.server_mut().schedule(PUSH_TICKS, move |server| {
+            for (x, y, z) in arriving {
+                server.world.set_block_at(BOULDER_BLOCK, x, y, z);
+            }
+        });
+        self.moving_until = world.tick_count + PUSH_TICKS as u64;
+    }
+
+    /// If the cell in front of the chest can be walked to from the entrance side.
+    fn path_open(&self) -> bool {
+        let mut seen: HashSet<Cell> = (0..COLUMNS)
+            .map(|column| (column, ROWS - 1))
+            .filter(|cell| !self.boulders.contains(cell))
+            .collect();
+        let mut queue: VecDeque<Cell> = seen.iter().copied().collect();
+        while let Some((column, row)) = queue.pop_front() {
+            if (column, row) == (CHEST_COLUMN, 0) {
+                return true;
+            }
+            for next in [(column.wrapping_sub(1), row), (column + 1, row), (column, row.wrapping_sub(1)), (column, row + 1)] {
+                if next.0 < COLUMNS && next.1 < ROWS && !self.boulders.contains(&next) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Puzzle for Boulder {
+    fn name(&self) -> &'static str {
+        "Boulder"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (cell_corner((CHEST_COLUMN, 0)).add_x(1).add_z(-1), Direction::South)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        self.load_cells(world, room);
+
+        self.reset_button = room.get_world_block_pos(&RESET_BUTTON);
+        let direction = ButtonDirection(Direction::South).rotate(room.rotation);
+        world.set_block_at(Blocks::StoneButton { direction, powered: false }, self.reset_button.x, self.reset_button.y, self.reset_button.z);
+        world.interactable_blocks.insert(self.reset_button, BlockInteractAction::Puzzle);
+    }
+
+    fn click(&mut self, world: &mut World, room: &Room, player: &Player, block_pos: &BlockPos) -> PuzzleClick {
+        if *block_pos != self.reset_button && !self.buttons.contains_key(block_pos) {
+            return PuzzleClick::Ignored;
+        }
+        if world.tick_count < self.moving_until {
+            return PuzzleClick::Handled(None);
+        }
+
+        if *block_pos == self.reset_button {
+            self.boulders = boulders_in(&self.configuration);
+            self.load_cells(world, room);
+            return PuzzleClick::Handled(None);
+        }
+
+        let ((column, row), (push_x, push_z)) = self.buttons[block_pos];
+        let to = (column as isize + push_x, row as isize + push_z);
+        if !(0..COLUMNS as isize).contains(&to.0) || !(0..ROWS as isize).contains(&to.1) {
+            return PuzzleClick::Handled(None);
+        }
+        let to = (to.0 as usize, to.1 as usize);
+        if self.boulders.contains(&to) {
+            return PuzzleClick::Handled(None);
+        }
+
+        self.push(world, room, (column, row), to);
+        self.place_buttons(world, room);
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::RandomClick.id(),
+                volume: 0.3,
+                pitch: 0.6,
+                pos_x: block_pos.x as f64 + 0.5,
+                pos_y: block_pos.y as f64 + 0.5,
+                pos_z: block_pos.z as f64 + 0.5,
+            });
+        }
+
+        if self.path_open() {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Solved(format!("§b{} §ecleared a path to the chest", player.profile.username))));
+        }
+        PuzzleClick::Handled(None)
+    }
+}
+
+fn boulders_in(configuration: &[&str; ROWS]) -> HashSet<Cell> {
+    configuration.iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.chars().enumerate().filter(|(_, char)| *char == '#').map(move |(column, _)| (column, row))
+        })
+        .collect()
+}
+
+fn cell_corner((column, row): Cell) -> BlockPos {
+    BlockPos {
+        x: GRID_CORNER.x + column as i32 * 3,
+        y: GRID_CORNER.y,
+        z: GRID_CORNER.z + row as i32 * 3,
+    }
+}
+
+fn for_cell_blocks(room: &Room, cell: Cell, mut block: impl FnMut(i32, i32, i32)) {
+    let corner = cell_corner(cell);
+    for x in 0..3 {
+        for y in 0..BOULDER_HEIGHT {
+            for z in 0..3 {
+                let pos = room.get_world_block_pos(&BlockPos { x: corner.x + x, y: corner.y + y, z: corner.z + z });
+                block(pos.x, pos.y, pos.z);
+            }
+        }
+    }
+}
+
+/// Slides a boulder block over to the next cell, like DoorEntityImpl but sideways.
+///
+/// it spawns a falling block entity that rides the entity and removes itself once it has arrived.
+#[derive(Debug)]
+pub struct BoulderEntityImpl {
+    pub block: Blocks,
+    step: DVec3,
+    ticks_left: u32,
+}
+
+impl BoulderEntityImpl {
+    pub fn new(block: Blocks, step: DVec3, ticks: u32) -> Self {
+        Self {
+            block,
+            step,
+            ticks_left: ticks,
+        }
+    }
+}
+
+/// offset so that the falling block riding the bat lines up with the block it replaced
+pub const BOULDER_ENTITY_OFFSET: f64 = 0.65;
+
+impl EntityImpl for BoulderEntityImpl {
+    fn spawn(&mut self, entity: &mut Entity, buffer: &mut PacketBuffer) {
+        let world = entity.world_mut();
+        let entity_id = world.new_entity_id();
+
+        let object_data = {
+            let block_state_id = self.block.get_block_state_id() as i32;
+            let block_id = block_state_id >> 4;
+            let metadata = block_state_id & 0b1111;
+            block_id | (metadata << 12)
+        };
+
+        buffer.write_packet(&SpawnObject {
+            entity_id: VarInt(entity_id),
+            entity_variant: 70, // Falling block entity
+            x: entity.position.x,
+            y: entity.position.y + BOULDER_ENTITY_OFFSET,
+            z: entity.position.z,
+            yaw: 0.0,
+            pitch: 0.0,
+            data: object_data,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+        });
+
+        buffer.write_packet(&EntityAttach {
+            entity_id,
+            vehicle_id: entity.id,
+            leash: false,
+        });
+    }
+
+    fn despawn(&mut self, entity: &mut Entity, buffer: &mut PacketBuffer) {
+        buffer.write_packet(&DestroyEntites {
+            entities: vec![VarInt(entity.id + 1)],
+        });
+    }
+
+    fn tick(&mut self, entity: &mut Entity, _: &mut PacketBuffer) {
+        entity.position.x += self.step.x;
+        entity.position.z += self.step.z;
+        self.ticks_left -= 1;
+        if self.ticks_left == 0 {
+            entity.world_mut().despawn_entity(entity.id);
+        }
+    }
+}
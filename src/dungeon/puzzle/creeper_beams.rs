@@ -0,0 +1,213 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleClick, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::protocol::play::clientbound::{Particles, SoundEffect};
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::entity::entity::NoEntityImpl;
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::player::player::Player;
+use crate::server::utils::aabb::AABB;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::particles::ParticleTypes;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::Rng;
+use std::f64::consts::TAU;
+
+// Creeper Beams: a charged creeper stands in the middle of the room with sea lanterns scattered around it.
+// Clicking two lanterns whose beam goes through the creeper turns them into prismarine,
+// the puzzle is solved once four beams have been made.
+
+const CREEPER_POS: BlockPos = BlockPos { x: 15, y: 69, z: 15 };
+const PAIRS: usize = 4;
+/// How far each lantern of a pair is from the creeper.
+const LANTERN_DISTANCE: std::ops::Range<f64> = 5.0..9.0;
+/// Ticks between beam particles being sent again.
+const BEAM_INTERVAL: u64 = 10;
+
+pub struct CreeperBeams {
+    /// Relative to the room until the puzzle is loaded, then world positions.
+    lanterns: Vec<BlockPos>,
+    selected: Option<usize>,
+    beams: Vec<(usize, usize)>,
+    creeper_box: AABB,
+}
+
+impl CreeperBeams {
+    pub fn new() -> CreeperBeams {
+        let mut rng = seeded_rng();
+        let creeper_box = creeper_box(CREEPER_POS.as_dvec3() + DVec3::new(0.5, 0.0, 0.5));
+
+        // a random line through the creeper for each pair, with a lantern on either end
+        let mut lanterns: Vec<BlockPos> = Vec::with_capacity(PAIRS * 2);
+        while lanterns.len() < PAIRS * 2 {
+            let target = CREEPER_POS.as_dvec3() + DVec3::new(
+                rng.random_range(0.3..0.7),
+                rng.random_range(0.2..1.5),
+                rng.random_range(0.3..0.7),
+            );
+            let yaw = rng.random_range(0.0..TAU);
+            let pitch: f64 = rng.random_range(-0.5..0.5);
+            let direction = DVec3::new(pitch.cos() * yaw.cos(), pitch.sin(), pitch.cos() * yaw.sin());
+
+            let pair = [
+                block_at(target + scale(direction, rng.random_range(LANTERN_DISTANCE))),
+                block_at(target - scale(direction, rng.random_range(LANTERN_DISTANCE))),
+            ];
+            let fits = pair.iter().all(|pos| {
+                (2..=28).contains(&pos.x) && (2..=28).contains(&pos.z) && (68..=78).contains(&pos.y)
+                    && lanterns.iter().all(|other| chebyshev(pos, other) >= 2)
+            });
+            if fits && creeper_box.intersects_line(DVec3::from_centered(&pair[0]), DVec3::from_centered(&pair[1])) {
+                lanterns.extend(pair);
+            }
+        }
+
+        CreeperBeams {
+            lanterns,
+            selected: None,
+            beams: Vec::new(),
+            creeper_box,
+        }
+    }
+}
+
+impl Puzzle for CreeperBeams {
+    fn name(&self) -> &'static str {
+        "Creeper Beams"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (BlockPos { x: 15, y: 69, z: 27 }, Direction::North)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let position = room.get_world_block_pos(&CREEPER_POS).as_dvec3() + DVec3::new(0.5, 0.0, 0.5);
+        self.creeper_box = creeper_box(position);
+
+        let mut metadata = EntityMetadata::new(EntityVariant::Creeper { powered: true });
+        metadata.ai_disabled = true;
+        if let Err(e) = world.spawn_entity(position, metadata, NoEntityImpl) {
+            eprintln!("Failed to spawn the creeper: {}", e);
+        }
+
+        for lantern in self.lanterns.iter_mut() {
+            *lantern = room.get_world_block_pos(lantern);
+            world.set_block_at(Blocks::SeaLantern, lantern.x, lantern.y, lantern.z);
+            world.interactable_blocks.insert(*lantern, BlockInteractAction::Puzzle);
+        }
+    }
+
+    fn tick(&mut self, world: &mut World, _: &Room) -> Option<PuzzleOutcome> {
+        if !world.tick_count.is_multiple_of(BEAM_INTERVAL) {
+            return None;
+        }
+
+        let mut particles = Vec::new();
+        for (from, to) in &self.beams {
+            let from = DVec3::from_centered(&self.lanterns[*from]);
+            let to = DVec3::from_centered(&self.lanterns[*to]);
+            let steps = (from.distance_to(&to) * 2.0) as usize;
+            for step in 0..=steps {
+                let point = from + scale(to - from, step as f64 / steps as f64);
+                particles.push(particle(ParticleTypes::Redstone, point, 0.0, 1));
+            }
+        }
+        if let Some(selected) = self.selected {
+            particles.push(particle(ParticleTypes::VillagerHappy, DVec3::from_centered(&self.lanterns[selected]), 0.4, 8));
+        }
+
+        for player in world.players.values_mut() {
+            for particle in &particles {
+                player.write_packet(particle);
+            }
+        }
+        None
+    }
+
+    fn click(&mut self, world: &mut World, _: &Room, player: &Player, block_pos: &BlockPos) -> PuzzleClick {
+        let Some(index) = self.lanterns.iter().position(|pos| pos == block_pos) else {
+            return PuzzleClick::Ignored;
+        };
+        if self.beams.iter().any(|(from, to)| *from == index || *to == index) {
+            return PuzzleClick::Handled(None);
+        }
+
+        let (sound, pitch) = match self.selected.take() {
+            None => {
+                self.selected = Some(index);
+                (Sounds::NotePling, 1.0)
+            }
+            Some(selected) if selected == index => (Sounds::NotePling, 0.5),
+            Some(selected) => {
+                let from = DVec3::from_centered(&self.lanterns[selected]);
+                let to = DVec3::from_centered(&self.lanterns[index]);
+                if self.creeper_box.intersects_line(from, to) {
+                    for lantern in [self.lanterns[selected], self.lanterns[index]] {
+                        world.set_block_at(Blocks::Prismarine { variant: 0 }, lantern.x, lantern.y, lantern.z);
+                        world.interactable_blocks.remove(&lantern);
+                    }
+                    self.beams.push((selected, index));
+                    (Sounds::Orb, 1.0)
+                } else {
+                    (Sounds::NoteHat, 0.5)
+                }
+            }
+        };
+
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: sound.id(),
+                volume: 1.0,
+                pitch,
+                pos_x: block_pos.x as f64 + 0.5,
+                pos_y: block_pos.y as f64 + 0.5,
+                pos_z: block_pos.z as f64 + 0.5,
+            });
+        }
+
+        if self.beams.len() >= PAIRS {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Solved(format!("§b{} §econnected the last beam", player.profile.username))));
+        }
+        PuzzleClick::Handled(None)
+    }
+}
+
+fn creeper_box(position: DVec3) -> AABB {
+    let size = AABB::from_height_width(1.7, 0.6);
+    AABB::new(position + size.min, position + size.max)
+}
+
+fn scale(vec: DVec3, amount: f64) -> DVec3 {
+    DVec3::new(vec.x * amount, vec.y * amount, vec.z * amount)
+}
+
+fn block_at(pos: DVec3) -> BlockPos {
+    BlockPos {
+        x: pos.x.floor() as i32,
+        y: pos.y.floor() as i32,
+        z: pos.z.floor() as i32,
+    }
+}
+
+fn chebyshev(a: &BlockPos, b: &BlockPos) -> i32 {
+    (a.x - b.x).abs().max((a.y - b.y).abs()).max((a.z - b.z).abs())
+}
+
+fn particle(particle: ParticleTypes, pos: DVec3, spread: f32, count: i32) -> Particles {
+    Particles {
+        particle_id: particle.get_id(),
+        long_distance: true,
+        x: pos.x as f32,
+        y: pos.y as f32,
+        z: pos.z as f32,
+        offset_x: spread,
+        offset_y: spread,
+        offset_z: spread,
+        speed: 0.0,
+        count,
+    }
+}
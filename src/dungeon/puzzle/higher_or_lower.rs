@@ -0,0 +1,118 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::server::block::block_position::BlockPos;
+use crate::server::entity::entity::{EntityId, NoEntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::entity::spawn_equipped::spawn_following_nametag;
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::Rng;
+
+// Higher or Lower: ten blazes with random health float around the middle of the room.
+// They have to be killed from lowest to highest health, or highest to lowest in the "Higher Blaze" version.
+// Killing one out of order fails the puzzle.
+
+const BLAZE_POSITIONS: [BlockPos; 10] = [
+    BlockPos { x: 8, y: 70, z: 15 },
+    BlockPos { x: 22, y: 72, z: 15 },
+    BlockPos { x: 15, y: 74, z: 8 },
+    BlockPos { x: 15, y: 76, z: 22 },
+    BlockPos { x: 10, y: 78, z: 10 },
+    BlockPos { x: 20, y: 80, z: 20 },
+    BlockPos { x: 10, y: 82, z: 20 },
+    BlockPos { x: 20, y: 84, z: 10 },
+    BlockPos { x: 12, y: 86, z: 15 },
+    BlockPos { x: 18, y: 88, z: 15 },
+];
+
+struct Blaze {
+    entity_id: EntityId,
+    nametag_id: Option<EntityId>,
+}
+
+pub struct HigherOrLower {
+    /// Highest health first instead of lowest.
+    descending: bool,
+    /// Still alive, in the order they have to be killed.
+    blazes: Vec<Blaze>,
+}
+
+impl HigherOrLower {
+    pub fn new(room_name: &str) -> HigherOrLower {
+        let name = room_name.to_lowercase();
+        let descending = if name.contains("higher blaze") {
+            true
+        } else if name.contains("lower blaze") {
+            false
+        } else {
+            seeded_rng().random_bool(0.5)
+        };
+
+        HigherOrLower {
+            descending,
+            blazes: Vec::new(),
+        }
+    }
+}
+
+impl Puzzle for HigherOrLower {
+    fn name(&self) -> &'static str {
+        "Higher or Lower"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (BlockPos { x: 15, y: 69, z: 15 }, Direction::North)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let mut rng = seeded_rng();
+        let mut health = (300..=3000).choose_multiple(&mut rng, BLAZE_POSITIONS.len());
+        health.shuffle(&mut rng);
+
+        let mut blazes = Vec::with_capacity(BLAZE_POSITIONS.len());
+        for (room_pos, health) in BLAZE_POSITIONS.iter().zip(health) {
+            let position = room.get_world_block_pos(room_pos).as_dvec3() + DVec3::new(0.5, 0.0, 0.5);
+
+            let mut metadata = EntityMetadata::new(EntityVariant::Blaze);
+            metadata.ai_disabled = true;
+            let entity_id = match world.spawn_entity(position, metadata, NoEntityImpl) {
+                Ok(id) => id,
+                Err(e) => {
+                    eprintln!("Failed to spawn a blaze: {}", e);
+                    continue;
+                }
+            };
+
+            let text = format!("§8[§7Lv15§8] §cBlaze §a{}§f/§a{}§c❤", health, health);
+            let nametag_id = spawn_following_nametag(world, entity_id, &text, 0.0).ok();
+            blazes.push((health, Blaze { entity_id, nametag_id }));
+        }
+
+        blazes.sort_by_key(|(health, _)| *health);
+        if self.descending {
+            blazes.reverse();
+        }
+        self.blazes = blazes.into_iter().map(|(_, blaze)| blaze).collect();
+    }
+
+    fn attack(&mut self, world: &mut World, _: &Room, player: &Player, entity_id: EntityId) -> Option<PuzzleOutcome> {
+        let index = self.blazes.iter().position(|blaze| blaze.entity_id == entity_id)?;
+        let blaze = self.blazes.remove(index);
+        world.despawn_entity(blaze.entity_id);
+        if let Some(nametag_id) = blaze.nametag_id {
+            world.despawn_entity(nametag_id);
+        }
+
+        if index != 0 {
+            return Some(PuzzleOutcome::Failed(format!("§b{} §ekilled a Blaze in the wrong order", player.profile.username)));
+        }
+        if self.blazes.is_empty() {
+            return Some(PuzzleOutcome::Solved(format!("§b{} §ekilled the Blazes in the right order", player.profile.username)));
+        }
+        None
+    }
+}
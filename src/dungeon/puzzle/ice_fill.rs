@@ -0,0 +1,246 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::player::player::{ClientId, Player};
+use crate::server::utils::direction::Direction;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+// Ice Fill: three boards of ice, each one higher and bigger than the last.
+// Walking over ice turns it into packed ice, and walking onto packed ice again resets the board.
+// Once every tile of a board is packed ice the glass in front of the next board goes away, the last one leads to the chest.
+//
+// Boards go along z, centered on CENTER_X. Tiles that aren't on the generated path are walled off so every board can be finished.
+
+const CENTER_X: i32 = 15;
+
+struct BoardLayout {
+    size: usize,
+    /// z of the row the board is entered from.
+    start_z: i32,
+    /// y of the ice itself, players walk one above it.
+    ice_y: i32,
+}
+
+const BOARDS: [BoardLayout; 3] = [
+    BoardLayout { size: 3, start_z: 4, ice_y: 68 },
+    BoardLayout { size: 5, start_z: 8, ice_y: 69 },
+    BoardLayout { size: 7, start_z: 14, ice_y: 70 },
+];
+
+type Tile = (usize, usize);
+
+struct Board {
+    path: HashSet<Tile>,
+    packed: HashSet<Tile>,
+}
+
+pub struct IceFill {
+    boards: Vec<Board>,
+    /// The board being worked on, everything before it is done.
+    current: usize,
+    /// World position of every path tile, to the board and tile it belongs to.
+    tiles: HashMap<BlockPos, (usize, Tile)>,
+    /// The block each player was last standing on, so standing still on a tile doesn't count as walking onto it again.
+    last_block: HashMap<ClientId, BlockPos>,
+}
+
+impl IceFill {
+    pub fn new() -> IceFill {
+        let mut rng = seeded_rng();
+        let boards = BOARDS.iter()
+            .map(|layout| Board {
+                path: generate_path(&mut rng, layout.size).into_iter().collect(),
+                packed: HashSet::new(),
+            })
+            .collect();
+
+        IceFill {
+            boards,
+            current: 0,
+            tiles: HashMap::new(),
+            last_block: HashMap::new(),
+        }
+    }
+
+    fn set_tile(&self, world: &mut World, room: &Room, board: usize, tile: Tile, block: Blocks) {
+        let pos = room.get_world_block_pos(&tile_pos(board, tile));
+        world.set_block_at(block, pos.x, pos.y, pos.z);
+    }
+
+    /// Glass between a board and the next one until the board is finished, then a step up.
+    fn set_exit(&self, world: &mut World, room: &Room, board: usize, open: bool) {
+        let layout = &BOARDS[board];
+        let exit = BlockPos {
+            x: CENTER_X,
+            y: layout.ice_y + 1,
+            z: layout.start_z + layout.size as i32,
+        };
+        for height in 0..3 {
+            let block = match (open, height) {
+                (true, 0) => Blocks::StoneBrick { variant: 0 },
+                (true, _) => Blocks::Air,
+                (false, _) => Blocks::StainedGlass { color: 3 },
+            };
+            let pos = room.get_world_block_pos(&exit.add_y(height));
+            world.set_block_at(block, pos.x, pos.y, pos.z);
+        }
+    }
+}
+
+impl Puzzle for IceFill {
+    fn name(&self) -> &'static str {
+        "Ice Fill"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        let last = &BOARDS[BOARDS.len() - 1];
+        (BlockPos { x: CENTER_X, y: last.ice_y + 1, z: last.start_z + last.size as i32 + 1 }, Direction::North)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        for (index, (layout, board)) in BOARDS.iter().zip(&self.boards).enumerate() {
+            let size = layout.size as i32;
+            let half = size / 2;
+
+            // walls around the board, with a gap where it is entered and left
+            for x in CENTER_X - half - 1..=CENTER_X + half + 1 {
+                for z in layout.start_z - 1..=layout.start_z + size {
+                    let on_edge = x == CENTER_X - half - 1 || x == CENTER_X + half + 1 || z == layout.start_z - 1 || z == layout.start_z + size;
+                    if !on_edge || x == CENTER_X {
+                        continue;
+                    }
+                    for y in layout.ice_y + 1..=layout.ice_y + 2 {
+                        let pos = room.get_world_block_pos(&BlockPos { x, y, z });
+                        world.set_block_at(Blocks::StoneBrick { variant: 0 }, pos.x, pos.y, pos.z);
+                    }
+                }
+            }
+
+            for x in 0..layout.size {
+                for z in 0..layout.size {
+                    let tile = (x, z);
+                    let pos = room.get_world_block_pos(&tile_pos(index, tile));
+                    if board.path.contains(&tile) {
+                        world.set_block_at(Blocks::Ice, pos.x, pos.y, pos.z);
+                        self.tiles.insert(pos, (index, tile));
+                    } else {
+                        for height in 0..3 {
+                            world.set_block_at(Blocks::StoneBrick { variant: 0 }, pos.x, pos.y + height, pos.z);
+                        }
+                    }
+                }
+            }
+            self.set_exit(world, room, index, false);
+        }
+    }
+
+    fn moved(&mut self, world: &mut World, room: &Room, player: &Player, block_pos: &BlockPos) -> Option<PuzzleOutcome> {
+        if self.last_block.insert(player.client_id, *block_pos) == Some(*block_pos) {
+            return None;
+        }
+        let (index, tile) = *self.tiles.get(block_pos)?;
+        if index != self.current {
+            return None;
+        }
+
+        if self.boards[index].packed.contains(&tile) {
+            let board = &mut self.boards[index];
+            let path: Vec<Tile> = board.path.iter().copied().collect();
+            board.packed.clear();
+            for tile in path {
+                self.set_tile(world, room, index, tile, Blocks::Ice);
+            }
+            return None;
+        }
+
+        self.boards[index].packed.insert(tile);
+        self.set_tile(world, room, index, tile, Blocks::PackedIce);
+
+        let board = &self.boards[index];
+        if board.packed.len() < board.path.len() {
+            return None;
+        }
+
+        self.set_exit(world, room, index, true);
+        self.current += 1;
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::Orb.id(),
+                volume: 1.0,
+                pitch: 1.0,
+                pos_x: block_pos.x as f64 + 0.5,
+                pos_y: block_pos.y as f64 + 1.0,
+                pos_z: block_pos.z as f64 + 0.5,
+            });
+        }
+
+        if self.current == self.boards.len() {
+            return Some(PuzzleOutcome::Solved(format!("§b{} §efilled in the last board", player.profile.username)));
+        }
+        None
+    }
+}
+
+/// Tiles go from (0, 0) in the corner nearest the entrance, x across the board and z away from the entrance.
+fn tile_pos(board: usize, (x, z): Tile) -> BlockPos {
+    let layout = &BOARDS[board];
+    BlockPos {
+        x: CENTER_X - (layout.size / 2) as i32 + x as i32,
+        y: layout.ice_y,
+        z: layout.start_z + z as i32,
+    }
+}
+
+/// A random path over the board from the middle of the entrance row to the middle of the exit row,
+/// covering at least two thirds of the tiles. The tiles on it are the ones that have to be filled.
+fn generate_path(rng: &mut impl Rng, size: usize) -> Vec<Tile> {
+    let entry = (size / 2, 0);
+    let exit = (size / 2, size - 1);
+    let min_length = size * size * 2 / 3;
+
+    loop {
+        let mut path = vec![entry];
+        let mut budget = 10_000;
+        if extend_path(rng, size, &mut path, exit, min_length, &mut budget) {
+            return path;
+        }
+    }
+}
+
+fn extend_path(rng: &mut impl Rng, size: usize, path: &mut Vec<Tile>, exit: Tile, min_length: usize, budget: &mut u32) -> bool {
+    if *budget == 0 {
+        return false;
+    }
+    *budget -= 1;
+
+    let (x, z) = *path.last().unwrap();
+    if (x, z) == exit {
+        return path.len() >= min_length;
+    }
+
+    let mut neighbours = [
+        (x.wrapping_sub(1), z),
+        (x + 1, z),
+        (x, z.wrapping_sub(1)),
+        (x, z + 1),
+    ];
+    neighbours.shuffle(rng);
+    for next in neighbours {
+        if next.0 >= size || next.1 >= size || path.contains(&next) {
+            continue;
+        }
+        path.push(next);
+        if extend_path(rng, size, path, exit, min_length, budget) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
@@ -0,0 +1,257 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::entity::entity::{EntityId, NoEntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+
+// Ice Path: a silverfish sits on a grid of ice with stone in the way.
+// Hitting it makes it slide away from the player until it runs into something, the puzzle is solved once it slides onto the exit.
+// If it ends up somewhere it can't reach the exit from anymore it goes back to where it started.
+//
+// Layouts are random, the BFS in `solve` makes sure they can be done and gives the least moves needed for the message at the end.
+// Row 0 is the one with the exit, the silverfish starts in the last row.
+
+const SIZE: usize = 15;
+/// Corner of cell (0, 0), the ice is one below this.
+const GRID_CORNER: BlockPos = BlockPos { x: 8, y: 69, z: 8 };
+/// Chance for each cell to be stone.
+const OBSTACLE_CHANCE: f64 = 0.12;
+/// Layouts that can be done in fewer moves are thrown away.
+const MIN_MOVES: usize = 5;
+/// Blocks the silverfish moves per tick while sliding.
+const SLIDE_SPEED: f64 = 0.5;
+
+type Cell = (usize, usize);
+
+/// North, east, south and west in room coordinates.
+const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+struct Layout {
+    obstacles: HashSet<Cell>,
+    start: Cell,
+    exit: Cell,
+}
+
+impl Layout {
+    /// Where the silverfish stops sliding from `from`, it stops early if it goes over the exit.
+    fn slide(&self, from: Cell, (dx, dz): (isize, isize)) -> Cell {
+        let mut cell = from;
+        loop {
+            let next = (cell.0.wrapping_add_signed(dx), cell.1.wrapping_add_signed(dz));
+            if next.0 >= SIZE || next.1 >= SIZE || self.obstacles.contains(&next) {
+                return cell;
+            }
+            cell = next;
+            if cell == self.exit {
+                return cell;
+            }
+        }
+    }
+
+    /// The least moves from `from` to the exit, None if it can't be reached from there.
+    fn solve(&self, from: Cell) -> Option<usize> {
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([(from, 0)]);
+        while let Some((cell, moves)) = queue.pop_front() {
+            if cell == self.exit {
+                return Some(moves);
+            }
+            for direction in DIRECTIONS {
+                let next = self.slide(cell, direction);
+                if visited.insert(next) {
+                    queue.push_back((next, moves + 1));
+                }
+            }
+        }
+        None
+    }
+}
+
+pub struct IcePath {
+    layout: Layout,
+    optimal_moves: usize,
+    silverfish: Option<EntityId>,
+    cell: Cell,
+    moves: usize,
+    /// Where the silverfish is sliding to.
+    sliding_to: Option<Cell>,
+    /// Username of whoever hit the silverfish last, for the message once it's out.
+    last_hit_by: String,
+    /// World offset of one cell in each of DIRECTIONS, the room can be rotated.
+    world_directions: [DVec3; 4],
+    world_corner: DVec3,
+}
+
+impl IcePath {
+    pub fn new() -> IcePath {
+        let mut rng = seeded_rng();
+        let (layout, optimal_moves) = loop {
+            let start = (rng.random_range(0..SIZE), SIZE - 1);
+            let exit = (rng.random_range(1..SIZE - 1), 0);
+            let mut obstacles = HashSet::new();
+            for x in 0..SIZE {
+                for z in 0..SIZE {
+                    if (x, z) != start && (x, z) != exit && rng.random_bool(OBSTACLE_CHANCE) {
+                        obstacles.insert((x, z));
+                    }
+                }
+            }
+
+            let layout = Layout { obstacles, start, exit };
+            if let Some(moves) = layout.solve(start).filter(|moves| *moves >= MIN_MOVES) {
+                break (layout, moves);
+            }
+        };
+
+        IcePath {
+            cell: layout.start,
+            layout,
+            optimal_moves,
+            silverfish: None,
+            moves: 0,
+            sliding_to: None,
+            last_hit_by: String::new(),
+            world_directions: [DVec3::ZERO; 4],
+            world_corner: DVec3::ZERO,
+        }
+    }
+
+    /// Where the silverfish stands on a cell.
+    fn cell_position(&self, (x, z): Cell) -> DVec3 {
+        let [_, east, south, _] = self.world_directions;
+        let (x, z) = (x as f64, z as f64);
+        DVec3::new(
+            self.world_corner.x + east.x * x + south.x * z,
+            self.world_corner.y,
+            self.world_corner.z + east.z * x + south.z * z,
+        )
+    }
+}
+
+impl Puzzle for IcePath {
+    fn name(&self) -> &'static str {
+        "Ice Path"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (BlockPos { x: 15, y: 69, z: 5 }, Direction::South)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let corner = room.get_world_block_pos(&GRID_CORNER);
+        for (index, (dx, dz)) in DIRECTIONS.iter().enumerate() {
+            let pos = room.get_world_block_pos(&GRID_CORNER.add_x(*dx as i32).add_z(*dz as i32));
+            self.world_directions[index] = DVec3::new((pos.x - corner.x) as f64, 0.0, (pos.z - corner.z) as f64);
+        }
+        self.world_corner = corner.as_dvec3() + DVec3::new(0.5, 0.0, 0.5);
+
+        // a wall around the grid, the ice and the stone in it
+        for x in -1..=SIZE as i32 {
+            for z in -1..=SIZE as i32 {
+                let room_pos = GRID_CORNER.add_x(x).add_z(z);
+                let floor = room.get_world_block_pos(&room_pos.add_y(-1));
+                let pos = room.get_world_block_pos(&room_pos);
+
+                let cell = (x as usize, z as usize);
+                let (floor_block, block) = if x < 0 || z < 0 || x >= SIZE as i32 || z >= SIZE as i32 {
+                    (Blocks::StoneBrick { variant: 0 }, Blocks::StoneBrick { variant: 0 })
+                } else if cell == self.layout.exit {
+                    (Blocks::GoldBlock, Blocks::Air)
+                } else if self.layout.obstacles.contains(&cell) {
+                    (Blocks::Ice, Blocks::Stone { variant: 0 })
+                } else {
+                    (Blocks::Ice, Blocks::Air)
+                };
+                world.set_block_at(floor_block, floor.x, floor.y, floor.z);
+                world.set_block_at(block, pos.x, pos.y, pos.z);
+            }
+        }
+
+        let mut metadata = EntityMetadata::new(EntityVariant::Silverfish);
+        metadata.ai_disabled = true;
+        match world.spawn_entity(self.cell_position(self.layout.start), metadata, NoEntityImpl) {
+            Ok(id) => self.silverfish = Some(id),
+            Err(e) => eprintln!("Failed to spawn the silverfish: {}", e),
+        }
+    }
+
+    fn tick(&mut self, world: &mut World, _: &Room) -> Option<PuzzleOutcome> {
+        let target = self.sliding_to?;
+        let target_position = self.cell_position(target);
+        let (entity, _) = world.entities.get_mut(&self.silverfish?)?;
+
+        let remaining = target_position - entity.position;
+        let distance = remaining.distance_to(&DVec3::ZERO);
+        if distance > SLIDE_SPEED {
+            let step = SLIDE_SPEED / distance;
+            entity.position = entity.position + DVec3::new(remaining.x * step, 0.0, remaining.z * step);
+            return None;
+        }
+        entity.position = target_position;
+        self.sliding_to = None;
+        self.cell = target;
+
+        if target == self.layout.exit {
+            return Some(PuzzleOutcome::Solved(format!(
+                "§b{} §eslid the silverfish out in §a{} §emoves, the best was §a{}",
+                self.last_hit_by,
+                self.moves,
+                self.optimal_moves,
+            )));
+        }
+
+        if self.layout.solve(target).is_none() {
+            entity.position = self.cell_position(self.layout.start);
+            self.cell = self.layout.start;
+            for player in world.players.values_mut() {
+                player.send_message("§cThe silverfish got stuck and went back to where it started!");
+            }
+        }
+        None
+    }
+
+    fn attack(&mut self, world: &mut World, _: &Room, player: &Player, entity_id: EntityId) -> Option<PuzzleOutcome> {
+        if self.silverfish != Some(entity_id) || self.sliding_to.is_some() {
+            return None;
+        }
+        let (entity, _) = world.entities.get(&entity_id)?;
+
+        // it goes whichever way is closest to straight away from the player
+        let away = entity.position - player.position;
+        let (direction, _) = self.world_directions.iter()
+            .map(|offset| offset.x * away.x + offset.z * away.z)
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+        let target = self.layout.slide(self.cell, DIRECTIONS[direction]);
+        if target == self.cell {
+            return None;
+        }
+        self.sliding_to = Some(target);
+        self.moves += 1;
+        self.last_hit_by = player.profile.username.clone();
+
+        let position = entity.position;
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::SilverfishHit.id(),
+                volume: 1.0,
+                pitch: 1.0,
+                pos_x: position.x,
+                pos_y: position.y,
+                pos_z: position.z,
+            });
+        }
+        None
+    }
+}
@@ -0,0 +1,151 @@
+pub mod boulder;
+pub mod creeper_beams;
+pub mod higher_or_lower;
+pub mod ice_fill;
+pub mod ice_path;
+pub mod npc;
+pub mod quiz;
+pub mod teleport_maze;
+pub mod three_weirdos;
+pub mod tic_tac_toe;
+pub mod water_board;
+
+use crate::dungeon::dungeon::LockedChestState;
+use crate::dungeon::room::room::Room;
+use crate::dungeon::room::room_pool::normalize_room_name;
+use crate::dungeon::room::secrets::{DungeonSecret, SecretType};
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::entity::entity::EntityId;
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::world::World;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Puzzle rooms get their puzzle picked by room name when the dungeon is loaded into the world.
+//
+// Every puzzle has a reward chest that stays locked until it is solved, failing a puzzle keeps it locked for the rest of the run.
+// Positions inside puzzles are relative to the room corner (like the rest of the room json data) and rotated with the room,
+// y levels are absolute.
+
+pub enum PuzzleOutcome {
+    /// What the player did, like "§b{name} §ekilled the blazes in the right order".
+    Solved(String),
+    Failed(String),
+}
+
+pub trait Puzzle {
+    fn name(&self) -> &'static str;
+
+    /// Where the reward chest goes and which way it faces, relative to the room.
+    fn chest(&self) -> (BlockPos, Direction);
+
+    /// Extra chests that look like the reward chest but never open.
+    fn decoy_chests(&self) -> Vec<(BlockPos, Direction)> {
+        Vec::new()
+    }
+
+    /// Places blocks and spawns entities, runs once the room has been loaded into the world.
+    fn load(&mut self, world: &mut World, room: &Room);
+
+    fn tick(&mut self, _: &mut World, _: &Room) -> Option<PuzzleOutcome> {
+        None
+    }
+
+    /// A player hit an entity, returns None if it had nothing to do with the puzzle.
+    fn attack(&mut self, _: &mut World, _: &Room, _: &Player, _: EntityId) -> Option<PuzzleOutcome> {
+        None
+    }
+
+    /// A player clicked a block inside the puzzle room, like one of its levers.
+    fn click(&mut self, _: &mut World, _: &Room, _: &Player, _: &BlockPos) -> PuzzleClick {
+        PuzzleClick::Ignored
+    }
+
+    /// A player moved inside the puzzle room, `block_pos` is the block under their feet.
+    /// Runs for every movement packet, so most of the time it is the same block as last time.
+    fn moved(&mut self, _: &mut World, _: &Room, _: &Player, _: &BlockPos) -> Option<PuzzleOutcome> {
+        None
+    }
+}
+
+pub enum PuzzleClick {
+    /// Not one of the puzzles blocks, the click is handled like anywhere else.
+    Ignored,
+    /// The puzzle used the click, finishing it if there is an outcome.
+    Handled(Option<PuzzleOutcome>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PuzzleState {
+    InProgress,
+    Solved,
+    Failed,
+}
+
+pub struct RoomPuzzle {
+    pub room_index: usize,
+    pub state: PuzzleState,
+    /// World tick the room was first entered at, the solve time is counted from there.
+    pub started_at: Option<u64>,
+    pub chest_pos: BlockPos,
+    pub puzzle: Box<dyn Puzzle>,
+}
+
+pub fn puzzle_from_room_name(name: &str) -> Option<Box<dyn Puzzle>> {
+    let puzzle: Box<dyn Puzzle> = match normalize_room_name(name).as_str() {
+        "higher or lower" | "higher blaze" | "lower blaze" => Box::new(higher_or_lower::HigherOrLower::new(name)),
+        "water board" => Box::new(water_board::WaterBoard::new()),
+        "three weirdos" => Box::new(three_weirdos::ThreeWeirdos::new()),
+        "tic tac toe" => Box::new(tic_tac_toe::TicTacToe::new()),
+        "creeper beams" | "creeper" => Box::new(creeper_beams::CreeperBeams::new()),
+        "ice fill" => Box::new(ice_fill::IceFill::new()),
+        "ice path" | "silverfish" => Box::new(ice_path::IcePath::new()),
+        "boulder" => Box::new(boulder::Boulder::new()),
+        "quiz" => Box::new(quiz::Quiz::new()?),
+        "teleport maze" => Box::new(teleport_maze::TeleportMaze::new()),
+        _ => return None,
+    };
+    Some(puzzle)
+}
+
+impl RoomPuzzle {
+    /// Loads the puzzle and places its chests, the reward chest stays locked until the puzzle is solved.
+    pub fn new(
+        world: &mut World,
+        room_index: usize,
+        room: &Room,
+        mut puzzle: Box<dyn Puzzle>,
+        locked_chests: &mut HashMap<BlockPos, LockedChestState>,
+    ) -> RoomPuzzle {
+        puzzle.load(world, room);
+
+        let (chest_pos, _) = puzzle.chest();
+        let chest_pos = room.get_world_block_pos(&chest_pos);
+        for (pos, direction) in std::iter::once(puzzle.chest()).chain(puzzle.decoy_chests()) {
+            let pos = room.get_world_block_pos(&pos);
+            let direction = direction.rotate(room.rotation);
+
+            world.set_block_at(Blocks::Chest { direction }, pos.x, pos.y, pos.z);
+            world.interactable_blocks.insert(pos, BlockInteractAction::Chest {
+                secret: Rc::new(RefCell::new(DungeonSecret::new(SecretType::Chest { direction }, pos, 0.0))),
+            });
+            locked_chests.insert(pos, LockedChestState {
+                locked: true,
+                lever_world_pos: pos,
+            });
+        }
+
+        RoomPuzzle {
+            room_index,
+            state: PuzzleState::InProgress,
+            started_at: None,
+            chest_pos,
+            puzzle,
+        }
+    }
+}
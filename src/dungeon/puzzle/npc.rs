@@ -0,0 +1,96 @@
+use crate::dungeon::room::room::Room;
+use crate::net::packets::packet_buffer::PacketBuffer;
+use crate::net::protocol::play::clientbound::{PlayerListItem, Teams};
+use crate::net::protocol::play::serverbound::EntityInteractionType;
+use crate::net::var_int::VarInt;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::entity::entity::{Entity, EntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::entity::spawn_equipped::spawn_following_nametag;
+use crate::server::player::player::{GameProfile, Player};
+use crate::server::player::scoreboard::{ADD_PLAYER, CREATE_TEAM};
+use crate::server::utils::chat_component::chat_component_text::ChatComponentTextBuilder;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::player_list::player_profile::{GameType, PlayerData};
+use crate::server::utils::sized_string::SizedString;
+use crate::server::world::World;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Spawns a puzzle NPC standing on a block of the room with its name and a click prompt above it,
+/// `yaw` is relative to the room like the position.
+pub fn spawn_npc(world: &mut World, room: &Room, room_pos: &BlockPos, yaw: f32, name: &'static str, message: String, rng: &mut impl Rng) {
+    let position = room.get_world_block_pos(room_pos).as_dvec3() + DVec3::new(0.5, 0.0, 0.5);
+    let uuid = uuid::Builder::from_random_bytes(rng.random()).into_uuid();
+
+    let id = match world.spawn_entity_with_uuid(position, EntityMetadata::new(EntityVariant::Player), NpcImpl { name, message }, Some(uuid)) {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("Failed to spawn {}: {}", name, e);
+            return;
+        }
+    };
+    if let Some((entity, _)) = world.entities.get_mut(&id) {
+        entity.yaw = yaw.rotate(room.rotation);
+    }
+
+    if let Err(e) = spawn_following_nametag(world, id, &format!("§c{}", name), 0.4)
+        .and_then(|_| spawn_following_nametag(world, id, "§e§lCLICK", 0.1))
+    {
+        eprintln!("Failed to spawn nametags for {}: {}", name, e);
+    }
+}
+
+/// A puzzle NPC, says its message when clicked.
+pub struct NpcImpl {
+    name: &'static str,
+    message: String,
+}
+
+impl EntityImpl for NpcImpl {
+    fn spawn(&mut self, entity: &mut Entity, buffer: &mut PacketBuffer) {
+        // like Mort, the player entity needs a player list entry to spawn and a team to hide its vanilla nameplate
+        let Some(uuid) = entity.uuid else {
+            return;
+        };
+        let player_data = PlayerData {
+            ping: 20,
+            game_mode: GameType::Survival,
+            profile: GameProfile {
+                uuid,
+                username: self.name.to_string(),
+                properties: HashMap::new(),
+            },
+            display_name: Some(ChatComponentTextBuilder::new(self.name).build()),
+        };
+        buffer.write_packet(&PlayerListItem {
+            action: VarInt(0),
+            players: vec![&player_data],
+        });
+
+        let team = format!("npc_{}", self.name);
+        for (action, players) in [(CREATE_TEAM, vec![]), (ADD_PLAYER, vec![SizedString::truncated(self.name)])] {
+            buffer.write_packet(&Teams {
+                name: SizedString::truncated(&team),
+                display_name: SizedString::truncated(&team),
+                prefix: SizedString::truncated(""),
+                suffix: SizedString::truncated(""),
+                name_tag_visibility: SizedString::truncated("never"),
+                color: 0,
+                players,
+                action,
+                friendly_flags: 0,
+            });
+        }
+    }
+
+    fn tick(&mut self, _: &mut Entity, _: &mut PacketBuffer) {}
+
+    fn interact(&mut self, _: &mut Entity, player: &mut Player, action: &EntityInteractionType) {
+        if action != &EntityInteractionType::Interact {
+            return;
+        }
+        player.send_message(&self.message);
+    }
+}
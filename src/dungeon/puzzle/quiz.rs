@@ -0,0 +1,198 @@
+use crate::dungeon::puzzle::npc::spawn_npc;
+use crate::dungeon::puzzle::{Puzzle, PuzzleClick, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::dungeon::room::room_data_loader::{load_json_file, PreviousRoomData};
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_parameter::ButtonDirection;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use once_cell::sync::Lazy;
+use rand::seq::{IndexedRandom, SliceRandom};
+use serde::Deserialize;
+use std::sync::RwLock;
+
+// Quiz: Ophelia asks three questions one after another, each with three answers and a button for every answer.
+// Pressing the button of the right answer moves on to the next question, pressing a wrong one fails the puzzle.
+//
+// The questions come from Quiz/questions.json in the room data directory, so they are picked up by /reloadrooms.
+
+const ROUNDS: usize = 3;
+/// Ticks between the room being entered (or a question being answered) and the next question being asked.
+const ASK_DELAY: u64 = 40;
+
+const OPHELIA_POS: BlockPos = BlockPos { x: 15, y: 69, z: 9 };
+/// One per answer, on the side of a block facing south.
+const BUTTONS: [BlockPos; 3] = [
+    BlockPos { x: 11, y: 70, z: 16 },
+    BlockPos { x: 15, y: 70, z: 16 },
+    BlockPos { x: 19, y: 70, z: 16 },
+];
+const LETTERS: [&str; 3] = ["ⓐ", "ⓑ", "ⓒ"];
+
+#[derive(Debug, Default, Deserialize)]
+struct QuestionBank {
+    questions: Vec<QuestionEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct QuestionEntry {
+    question: String,
+    correct: String,
+    wrong: [String; 2],
+}
+
+static QUESTION_BANK: Lazy<RwLock<QuestionBank>> = Lazy::new(|| RwLock::new(QuestionBank::default()));
+
+pub fn reload_quiz_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let bank = load_json_file::<QuestionBank>("Quiz/questions.json")?;
+    previous.replace(&QUESTION_BANK, bank);
+    Ok(())
+}
+
+struct Question {
+    question: String,
+    answers: [String; 3],
+    correct: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    /// Nobody has entered the room yet.
+    Waiting,
+    AskingAt(u64),
+    Answering,
+}
+
+pub struct Quiz {
+    questions: Vec<Question>,
+    round: usize,
+    stage: Stage,
+    /// World positions of the buttons, in the same order as the answers.
+    buttons: Vec<BlockPos>,
+}
+
+impl Quiz {
+    /// None if the question bank doesn't have enough questions for a quiz.
+    pub fn new() -> Option<Quiz> {
+        let mut rng = seeded_rng();
+        let bank = QUESTION_BANK.read().unwrap();
+        if bank.questions.len() < ROUNDS {
+            eprintln!("The quiz needs at least {} questions, the question bank has {}", ROUNDS, bank.questions.len());
+            return None;
+        }
+
+        let questions = bank.questions.choose_multiple(&mut rng, ROUNDS)
+            .map(|entry| {
+                let mut answers = [entry.correct.clone(), entry.wrong[0].clone(), entry.wrong[1].clone()];
+                answers.shuffle(&mut rng);
+                let correct = answers.iter().position(|answer| *answer == entry.correct).unwrap();
+                Question {
+                    question: entry.question.clone(),
+                    answers,
+                    correct,
+                }
+            })
+            .collect();
+
+        Some(Quiz {
+            questions,
+            round: 0,
+            stage: Stage::Waiting,
+            buttons: Vec::new(),
+        })
+    }
+
+    fn ask(&mut self, world: &mut World) {
+        let question = &self.questions[self.round];
+        let mut lines = vec![
+            format!("§e[NPC] §cOphelia§f: Question #{}", self.round + 1),
+            format!("§f{}", question.question),
+        ];
+        for (letter, answer) in LETTERS.iter().zip(&question.answers) {
+            lines.push(format!("§6 {} §a{}", letter, answer));
+        }
+
+        for player in world.players.values_mut() {
+            for line in &lines {
+                player.send_message(line);
+            }
+        }
+        self.stage = Stage::Answering;
+    }
+}
+
+impl Puzzle for Quiz {
+    fn name(&self) -> &'static str {
+        "Quiz"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (BlockPos { x: 15, y: 69, z: 6 }, Direction::South)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let message = "§e[NPC] §cOphelia§f: Answer my questions and the treasure is yours!".to_string();
+        spawn_npc(world, room, &OPHELIA_POS, 0.0, "Ophelia", message, &mut seeded_rng());
+
+        let direction = ButtonDirection(Direction::South).rotate(room.rotation);
+        for room_pos in BUTTONS.iter() {
+            let wall = room.get_world_block_pos(&room_pos.add_z(-1));
+            world.set_block_at(Blocks::QuartzBlock { variant: 0 }, wall.x, wall.y, wall.z);
+
+            let button = room.get_world_block_pos(room_pos);
+            world.set_block_at(Blocks::StoneButton { direction, powered: false }, button.x, button.y, button.z);
+            world.interactable_blocks.insert(button, BlockInteractAction::Puzzle);
+            self.buttons.push(button);
+        }
+    }
+
+    fn tick(&mut self, world: &mut World, room: &Room) -> Option<PuzzleOutcome> {
+        match self.stage {
+            Stage::Waiting if room.entered => self.stage = Stage::AskingAt(world.tick_count + ASK_DELAY),
+            Stage::AskingAt(tick) if world.tick_count >= tick => self.ask(world),
+            _ => {}
+        }
+        None
+    }
+
+    fn click(&mut self, world: &mut World, _: &Room, player: &Player, block_pos: &BlockPos) -> PuzzleClick {
+        let Some(answer) = self.buttons.iter().position(|pos| pos == block_pos) else {
+            return PuzzleClick::Ignored;
+        };
+        if self.stage != Stage::Answering {
+            return PuzzleClick::Handled(None);
+        }
+
+        let number = self.round + 1;
+        if answer != self.questions[self.round].correct {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Failed(format!("§b{} §eanswered Question #{} incorrectly", player.profile.username, number))));
+        }
+
+        self.round += 1;
+        if self.round == ROUNDS {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Solved(format!("§b{} §eanswered the final question correctly", player.profile.username))));
+        }
+
+        self.stage = Stage::AskingAt(world.tick_count + ASK_DELAY);
+        let message = format!("§b{} §aanswered Question #{} correctly!", player.profile.username, number);
+        for player in world.players.values_mut() {
+            player.send_message(&message);
+            player.write_packet(&SoundEffect {
+                sound: Sounds::Orb.id(),
+                volume: 1.0,
+                pitch: 1.0,
+                pos_x: block_pos.x as f64 + 0.5,
+                pos_y: block_pos.y as f64 + 0.5,
+                pos_z: block_pos.z as f64 + 0.5,
+            });
+        }
+        PuzzleClick::Handled(None)
+    }
+}
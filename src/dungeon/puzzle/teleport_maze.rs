@@ -0,0 +1,274 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::protocol::play::clientbound::{PositionLook, SoundEffect};
+use crate::server::block::block_parameter::HorizontalDirection;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::player::player::{ClientId, Player};
+use crate::server::utils::direction::Direction;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Teleport Maze: a 3x3 grid of walled cells with end portal frames on the floor.
+// Stepping on a pad teleports the player next to the pad it is paired with in another cell,
+// one pad somewhere in the maze leads into the middle cell where the chest is.
+//
+// Players come in through the south wall into the cell below the middle one.
+
+/// Center of the middle cell, the other cells are CELL_SIZE blocks apart from it.
+const CENTER: BlockPos = BlockPos { x: 15, y: 68, z: 15 };
+/// Distance between cell centers, cells are 7x7 inside with a 1 block wall between them.
+const CELL_SIZE: i32 = 8;
+const WALL_HEIGHT: i32 = 3;
+
+const GOAL_CELL: Cell = (1, 1);
+const START_CELL: Cell = (1, 2);
+
+/// Ticks a teleported player's movement is ignored for while the client catches up, unless they already show up where they were sent.
+const ARRIVAL_TICKS: u64 = 20;
+
+/// Column and row, (0, 0) is the north west cell.
+type Cell = (i32, i32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    /// Index of the pad this one is paired with.
+    Pad(usize),
+    Goal,
+}
+
+struct Pad {
+    cell: Cell,
+    /// Which wall of the cell the pad is next to.
+    side: Direction,
+    target: Target,
+}
+
+pub struct TeleportMaze {
+    pads: Vec<Pad>,
+    /// World position of every pad, in the same order as `pads`.
+    pad_positions: Vec<BlockPos>,
+    /// The block each player was last standing on, so standing still on a pad doesn't teleport them again.
+    last_block: HashMap<ClientId, BlockPos>,
+    /// Players that were just teleported, with the block they were sent to and the tick to stop waiting for them at.
+    arriving: HashMap<ClientId, (BlockPos, u64)>,
+}
+
+impl TeleportMaze {
+    pub fn new() -> TeleportMaze {
+        let mut rng = seeded_rng();
+
+        let mut pads = Vec::new();
+        for column in 0..3 {
+            for row in 0..3 {
+                let cell = (column, row);
+                if cell == GOAL_CELL {
+                    continue;
+                }
+                for side in [Direction::North, Direction::East, Direction::South, Direction::West] {
+                    // the gap players come in through
+                    if cell == START_CELL && side == Direction::South {
+                        continue;
+                    }
+                    pads.push(Pad { cell, side, target: Target::Goal });
+                }
+            }
+        }
+
+        // one pad outside of the start cell goes to the goal, the rest are paired up with pads in other cells.
+        // shuffled again until the goal pad can be reached from the start cell
+        loop {
+            let mut order: Vec<usize> = (0..pads.len()).collect();
+            order.shuffle(&mut rng);
+
+            let goal = order.iter().position(|pad| pads[*pad].cell != START_CELL).expect("there are pads outside the start cell");
+            let goal = order.remove(goal);
+            if let Some(pairs) = pair_up(&pads, order) {
+                pads[goal].target = Target::Goal;
+                for (first, second) in pairs {
+                    pads[first].target = Target::Pad(second);
+                    pads[second].target = Target::Pad(first);
+                }
+                if goal_reachable(&pads) {
+                    break;
+                }
+            }
+        }
+
+        TeleportMaze {
+            pads,
+            pad_positions: Vec::new(),
+            last_block: HashMap::new(),
+            arriving: HashMap::new(),
+        }
+    }
+
+    /// Sends a player to a block inside the maze, the position packet goes out once the current movement has been handled.
+    fn teleport(&mut self, world: &mut World, room: &Room, player: &Player, destination: BlockPos, yaw: f32) {
+        let pos = room.get_world_block_pos(&destination);
+        self.arriving.insert(player.client_id, (pos, world.tick_count + ARRIVAL_TICKS));
+
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::EndermenPortal.id(),
+                volume: 1.0,
+                pitch: 1.0,
+                pos_x: pos.x as f64 + 0.5,
+                pos_y: pos.y as f64 + 1.0,
+                pos_z: pos.z as f64 + 0.5,
+            });
+        }
+
+        let client_id = player.client_id;
+        let (x, y, z) = (pos.x as f64 + 0.5, pos.y as f64 + 1.0, pos.z as f64 + 0.5);
+        let yaw = yaw.rotate(room.rotation);
+        world.server_mut().schedule(0, move |server| {
+            let Some(player) = server.world.players.get_mut(&client_id) else {
+                return;
+            };
+            player.set_position(x, y, z);
+            player.last_position = player.position;
+            player.write_packet(&PositionLook {
+                x,
+                y,
+                z,
+                yaw,
+                pitch: player.pitch,
+                flags: 0,
+            });
+        });
+    }
+}
+
+impl Puzzle for TeleportMaze {
+    fn name(&self) -> &'static str {
+        "Teleport Maze"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (CENTER.add_y(1), Direction::South)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let half = CELL_SIZE / 2;
+        let min = CELL_SIZE + half;
+
+        // the floor and walls of the whole grid, with a gap in the south wall of the start cell
+        for x in CENTER.x - min..=CENTER.x + min {
+            for z in CENTER.z - min..=CENTER.z + min {
+                let pos = room.get_world_block_pos(&BlockPos { x, y: CENTER.y, z });
+                world.set_block_at(Blocks::StoneBrick { variant: 0 }, pos.x, pos.y, pos.z);
+
+                let on_wall = (x - CENTER.x + min) % CELL_SIZE == 0 || (z - CENTER.z + min) % CELL_SIZE == 0;
+                let entrance = x == CENTER.x && z == CENTER.z + min;
+                for height in 1..=WALL_HEIGHT {
+                    let block = if on_wall && !entrance { Blocks::StoneBrick { variant: 0 } } else { Blocks::Air };
+                    world.set_block_at(block, pos.x, pos.y + height, pos.z);
+                }
+            }
+        }
+
+        for pad in &self.pads {
+            let pos = room.get_world_block_pos(&pad_pos(pad));
+            let direction = facing(pad.side).rotate(room.rotation);
+            world.set_block_at(Blocks::EndPortalFrame { direction, has_eye: false }, pos.x, pos.y, pos.z);
+            self.pad_positions.push(pos);
+        }
+    }
+
+    fn moved(&mut self, world: &mut World, room: &Room, player: &Player, block_pos: &BlockPos) -> Option<PuzzleOutcome> {
+        if let Some((landing, until)) = self.arriving.get(&player.client_id) {
+            // movement the client sent before getting the position packet
+            if block_pos != &landing.add_y(-1) && world.tick_count < *until {
+                return None;
+            }
+            self.arriving.remove(&player.client_id);
+        }
+        if self.last_block.insert(player.client_id, *block_pos) == Some(*block_pos) {
+            return None;
+        }
+        let index = self.pad_positions.iter().position(|pos| pos == block_pos)?;
+
+        match self.pads[index].target {
+            Target::Pad(other) => {
+                let other = &self.pads[other];
+                // one block in from the pad, facing into the cell
+                let (x, _, z) = other.side.get_offset();
+                let destination = pad_pos(other).add_y(1).add(BlockPos { x: -x, y: 0, z: -z });
+                self.teleport(world, room, player, destination, into_cell_yaw(other.side));
+                None
+            }
+            Target::Goal => {
+                self.teleport(world, room, player, CENTER.add_y(1).add_z(2), into_cell_yaw(Direction::South));
+
+                // let the players walk out of the middle cell into the start cell
+                for height in 1..=2 {
+                    let pos = room.get_world_block_pos(&CENTER.add_y(height).add_z(CELL_SIZE / 2));
+                    world.set_block_at(Blocks::Air, pos.x, pos.y, pos.z);
+                }
+                Some(PuzzleOutcome::Solved(format!("§b{} §efound the way through the Teleport Maze", player.profile.username)))
+            }
+        }
+    }
+}
+
+/// Pairs the pads up in order, None if two pads in the same cell would end up together.
+fn pair_up(pads: &[Pad], order: Vec<usize>) -> Option<Vec<(usize, usize)>> {
+    order.chunks(2)
+        .map(|pair| match pair {
+            [first, second] if pads[*first].cell != pads[*second].cell => Some((*first, *second)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the cell with the goal pad can be reached from the start cell by going through pads.
+fn goal_reachable(pads: &[Pad]) -> bool {
+    let mut seen = HashSet::from([START_CELL]);
+    let mut queue = VecDeque::from([START_CELL]);
+
+    while let Some(cell) = queue.pop_front() {
+        for pad in pads.iter().filter(|pad| pad.cell == cell) {
+            match pad.target {
+                Target::Goal => return true,
+                Target::Pad(other) => {
+                    if seen.insert(pads[other].cell) {
+                        queue.push_back(pads[other].cell);
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+fn pad_pos(pad: &Pad) -> BlockPos {
+    let (column, row) = pad.cell;
+    let center = CENTER.add_x((column - 1) * CELL_SIZE).add_z((row - 1) * CELL_SIZE);
+    let (x, _, z) = pad.side.get_offset();
+    center.add(BlockPos { x: x * 3, y: 0, z: z * 3 })
+}
+
+/// Frames face away from the wall they are next to.
+fn facing(side: Direction) -> HorizontalDirection {
+    match side {
+        Direction::North => HorizontalDirection::South,
+        Direction::East => HorizontalDirection::West,
+        Direction::West => HorizontalDirection::East,
+        _ => HorizontalDirection::North,
+    }
+}
+
+/// Yaw looking away from a wall of the cell, into it.
+fn into_cell_yaw(side: Direction) -> f32 {
+    match side {
+        Direction::North => 0.0,
+        Direction::East => 90.0,
+        Direction::West => 270.0,
+        _ => 180.0,
+    }
+}
@@ -0,0 +1,160 @@
+use crate::dungeon::puzzle::npc::spawn_npc;
+use crate::dungeon::puzzle::{Puzzle, PuzzleClick, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::server::block::block_position::BlockPos;
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::{IndexedRandom, IteratorRandom, SliceRandom};
+
+// Three Weirdos: three NPCs each stand behind a chest and say something about where the reward is.
+// Exactly one of them is telling the truth, and the statements are picked so only one chest works with that.
+// Opening the right chest solves the puzzle, opening either of the others fails it.
+
+const NAMES: [&str; 24] = [
+    "Ardis", "Ashton", "Baxter", "Benson", "Carver", "Elmo", "Eveleth", "Frankie",
+    "Ginny", "Hope", "Jerry", "Jimmy", "Kelly", "Lino", "Luverne", "Marshall",
+    "Melody", "Montgomery", "Randal", "Rose", "Tommy", "Victoria", "Willie", "Wilson",
+];
+
+const NPC_POSITIONS: [BlockPos; 3] = [
+    BlockPos { x: 11, y: 69, z: 18 },
+    BlockPos { x: 15, y: 69, z: 18 },
+    BlockPos { x: 19, y: 69, z: 18 },
+];
+
+/// One in front of each NPC.
+const CHEST_POSITIONS: [BlockPos; 3] = [
+    BlockPos { x: 11, y: 69, z: 19 },
+    BlockPos { x: 15, y: 69, z: 19 },
+    BlockPos { x: 19, y: 69, z: 19 },
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Statement {
+    InMyChest,
+    NotInMyChest,
+    InChestOf(usize),
+    NotInChestOf(usize),
+}
+
+impl Statement {
+    /// Everything the weirdo at `speaker` can say.
+    fn all(speaker: usize) -> Vec<Statement> {
+        let mut statements = vec![Statement::InMyChest, Statement::NotInMyChest];
+        for other in (0..3).filter(|other| *other != speaker) {
+            statements.push(Statement::InChestOf(other));
+            statements.push(Statement::NotInChestOf(other));
+        }
+        statements
+    }
+
+    fn is_true(&self, speaker: usize, reward: usize) -> bool {
+        match *self {
+            Statement::InMyChest => reward == speaker,
+            Statement::NotInMyChest => reward != speaker,
+            Statement::InChestOf(other) => reward == other,
+            Statement::NotInChestOf(other) => reward != other,
+        }
+    }
+
+    fn text(&self, names: &[&str; 3]) -> String {
+        match *self {
+            Statement::InMyChest => "The reward is in my chest!".to_string(),
+            Statement::NotInMyChest => "The reward is not in my chest!".to_string(),
+            Statement::InChestOf(other) => format!("The reward is in §c{}§f's chest!", names[other]),
+            Statement::NotInChestOf(other) => format!("The reward isn't in §c{}§f's chest!", names[other]),
+        }
+    }
+}
+
+/// Finds where the reward has to be for exactly one of the statements to be true,
+/// None if that fits more than one chest (or none) since the puzzle couldn't be worked out.
+fn solve(statements: &[Statement; 3]) -> Option<usize> {
+    let mut rewards = (0..3).filter(|reward| {
+        statements.iter()
+            .enumerate()
+            .filter(|(speaker, statement)| statement.is_true(*speaker, *reward))
+            .count() == 1
+    });
+    let reward = rewards.next()?;
+    rewards.next().is_none().then_some(reward)
+}
+
+pub struct ThreeWeirdos {
+    names: [&'static str; 3],
+    statements: [Statement; 3],
+    reward: usize,
+    /// World positions, in the same order as the weirdos.
+    chests: Vec<BlockPos>,
+}
+
+impl ThreeWeirdos {
+    pub fn new() -> ThreeWeirdos {
+        let mut rng = seeded_rng();
+
+        let mut names: Vec<&'static str> = NAMES.choose_multiple(&mut rng, 3).copied().collect();
+        names.shuffle(&mut rng);
+
+        let mut puzzles = Vec::new();
+        for first in Statement::all(0) {
+            for second in Statement::all(1) {
+                for third in Statement::all(2) {
+                    let statements = [first, second, third];
+                    if let Some(reward) = solve(&statements) {
+                        puzzles.push((statements, reward));
+                    }
+                }
+            }
+        }
+        let (statements, reward) = puzzles.into_iter()
+            .choose(&mut rng)
+            .expect("some statements always have a single answer");
+
+        ThreeWeirdos {
+            names: [names[0], names[1], names[2]],
+            statements,
+            reward,
+            chests: Vec::new(),
+        }
+    }
+}
+
+impl Puzzle for ThreeWeirdos {
+    fn name(&self) -> &'static str {
+        "Three Weirdos"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (CHEST_POSITIONS[self.reward], Direction::South)
+    }
+
+    fn decoy_chests(&self) -> Vec<(BlockPos, Direction)> {
+        (0..3).filter(|index| *index != self.reward)
+            .map(|index| (CHEST_POSITIONS[index], Direction::South))
+            .collect()
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let mut rng = seeded_rng();
+        self.chests = CHEST_POSITIONS.iter().map(|pos| room.get_world_block_pos(pos)).collect();
+
+        for (index, room_pos) in NPC_POSITIONS.iter().enumerate() {
+            let name = self.names[index];
+            let message = format!("§e[NPC] §c{}§f: {}", name, self.statements[index].text(&self.names));
+            spawn_npc(world, room, room_pos, 0.0, name, message, &mut rng);
+        }
+    }
+
+    fn click(&mut self, _: &mut World, _: &Room, player: &Player, block_pos: &BlockPos) -> PuzzleClick {
+        let Some(index) = self.chests.iter().position(|pos| pos == block_pos) else {
+            return PuzzleClick::Ignored;
+        };
+        if index == self.reward {
+            PuzzleClick::Handled(Some(PuzzleOutcome::Solved(format!("§b{} §ewasn't fooled by §c{}", player.profile.username, self.names[index]))))
+        } else {
+            PuzzleClick::Handled(Some(PuzzleOutcome::Failed(format!("§b{} §epicked the wrong chest", player.profile.username))))
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleClick, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_parameter::ButtonDirection;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::seq::IndexedRandom;
+
+// Tic Tac Toe: the board is 3x3 blocks on the wall with a button in front of every empty square.
+// The opponent has already made its first moves from one of the openings, the players are X and move by pressing buttons.
+// It plays perfectly, so the puzzle is solved by getting a draw (or a win if it ever makes a mistake) and failed by losing.
+
+/// Top left square of the board, the board goes along x and down from there.
+const BOARD_CORNER: BlockPos = BlockPos { x: 14, y: 72, z: 24 };
+
+const EMPTY_BLOCK: Blocks = Blocks::QuartzBlock { variant: 0 };
+const PLAYER_BLOCK: Blocks = Blocks::StainedHardenedClay { color: 14 };
+const OPPONENT_BLOCK: Blocks = Blocks::StainedHardenedClay { color: 11 };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Square {
+    Empty,
+    Player,
+    Opponent,
+}
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2], [3, 4, 5], [6, 7, 8],
+    [0, 3, 6], [1, 4, 7], [2, 5, 8],
+    [0, 4, 8], [2, 4, 6],
+];
+
+/// Squares the opponent starts with and squares the players start with, the players always move next.
+const OPENINGS: [(&[usize], &[usize]); 6] = [
+    (&[4], &[]),
+    (&[0], &[]),
+    (&[1], &[]),
+    (&[0, 8], &[4]),
+    (&[4, 8], &[0]),
+    (&[2, 3], &[0]),
+];
+
+pub struct TicTacToe {
+    board: [Square; 9],
+    /// World positions of the buttons, one per square.
+    buttons: Vec<BlockPos>,
+}
+
+impl TicTacToe {
+    pub fn new() -> TicTacToe {
+        let (opponent, players) = OPENINGS.choose(&mut seeded_rng()).expect("there are openings");
+
+        let mut board = [Square::Empty; 9];
+        for square in opponent.iter() {
+            board[*square] = Square::Opponent;
+        }
+        for square in players.iter() {
+            board[*square] = Square::Player;
+        }
+
+        TicTacToe {
+            board,
+            buttons: Vec::new(),
+        }
+    }
+
+    /// Marks a square and takes its button away.
+    fn play(&mut self, world: &mut World, room: &Room, square: usize, mark: Square) {
+        self.board[square] = mark;
+
+        let button = self.buttons[square];
+        world.set_block_at(Blocks::Air, button.x, button.y, button.z);
+        world.interactable_blocks.remove(&button);
+
+        let pos = room.get_world_block_pos(&square_pos(square));
+        let block = if mark == Square::Player { PLAYER_BLOCK } else { OPPONENT_BLOCK };
+        world.set_block_at(block, pos.x, pos.y, pos.z);
+    }
+}
+
+impl Puzzle for TicTacToe {
+    fn name(&self) -> &'static str {
+        "Tic Tac Toe"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (BlockPos { x: 15, y: 69, z: 18 }, Direction::North)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        let direction = ButtonDirection(Direction::North).rotate(room.rotation);
+
+        for (square, mark) in self.board.iter().enumerate() {
+            let pos = room.get_world_block_pos(&square_pos(square));
+            self.buttons.push(room.get_world_block_pos(&square_pos(square).add_z(-1)));
+
+            let block = match mark {
+                Square::Empty => EMPTY_BLOCK,
+                Square::Player => PLAYER_BLOCK,
+                Square::Opponent => OPPONENT_BLOCK,
+            };
+            world.set_block_at(block, pos.x, pos.y, pos.z);
+
+            if *mark == Square::Empty {
+                let button = self.buttons[square];
+                world.set_block_at(Blocks::StoneButton { direction, powered: false }, button.x, button.y, button.z);
+                world.interactable_blocks.insert(button, BlockInteractAction::Puzzle);
+            }
+        }
+    }
+
+    fn click(&mut self, world: &mut World, room: &Room, player: &Player, block_pos: &BlockPos) -> PuzzleClick {
+        let Some(square) = self.buttons.iter().position(|pos| pos == block_pos) else {
+            return PuzzleClick::Ignored;
+        };
+        if self.board[square] != Square::Empty {
+            return PuzzleClick::Handled(None);
+        }
+
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::RandomClick.id(),
+                volume: 0.3,
+                pitch: 0.6,
+                pos_x: block_pos.x as f64 + 0.5,
+                pos_y: block_pos.y as f64 + 0.5,
+                pos_z: block_pos.z as f64 + 0.5,
+            });
+        }
+
+        let username = &player.profile.username;
+        self.play(world, room, square, Square::Player);
+        if winner(&self.board) == Some(Square::Player) {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Solved(format!("§b{} §ebeat Tic Tac Toe", username))));
+        }
+
+        if let Some(square) = best_move(&mut self.board) {
+            self.play(world, room, square, Square::Opponent);
+        }
+        if winner(&self.board) == Some(Square::Opponent) {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Failed(format!("§b{} §elost Tic Tac Toe", username))));
+        }
+        if !self.board.contains(&Square::Empty) {
+            return PuzzleClick::Handled(Some(PuzzleOutcome::Solved(format!("§b{} §etied Tic Tac Toe", username))));
+        }
+        PuzzleClick::Handled(None)
+    }
+}
+
+fn square_pos(square: usize) -> BlockPos {
+    BlockPos {
+        x: BOARD_CORNER.x + (square % 3) as i32,
+        y: BOARD_CORNER.y - (square / 3) as i32,
+        z: BOARD_CORNER.z,
+    }
+}
+
+fn winner(board: &[Square; 9]) -> Option<Square> {
+    LINES.iter().find_map(|[a, b, c]| {
+        (board[*a] != Square::Empty && board[*a] == board[*b] && board[*b] == board[*c]).then_some(board[*a])
+    })
+}
+
+/// The opponent's best square, preferring the quickest win and the slowest loss.
+fn best_move(board: &mut [Square; 9]) -> Option<usize> {
+    let mut best = None;
+    let mut best_score = i32::MIN;
+    for square in 0..9 {
+        if board[square] != Square::Empty {
+            continue;
+        }
+        board[square] = Square::Opponent;
+        let score = minimax(board, false, 1);
+        board[square] = Square::Empty;
+        if score > best_score {
+            best_score = score;
+            best = Some(square);
+        }
+    }
+    best
+}
+
+fn minimax(board: &mut [Square; 9], opponent_turn: bool, depth: i32) -> i32 {
+    match winner(board) {
+        Some(Square::Opponent) => return 10 - depth,
+        Some(_) => return depth - 10,
+        None if !board.contains(&Square::Empty) => return 0,
+        None => {}
+    }
+
+    let mut best = if opponent_turn { i32::MIN } else { i32::MAX };
+    for square in 0..9 {
+        if board[square] != Square::Empty {
+            continue;
+        }
+        board[square] = if opponent_turn { Square::Opponent } else { Square::Player };
+        let score = minimax(board, !opponent_turn, depth + 1);
+        board[square] = Square::Empty;
+        best = if opponent_turn { best.max(score) } else { best.min(score) };
+    }
+    best
+}
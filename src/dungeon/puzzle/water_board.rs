@@ -0,0 +1,308 @@
+use crate::dungeon::puzzle::{Puzzle, PuzzleClick, PuzzleOutcome};
+use crate::dungeon::room::room::Room;
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_parameter::LeverOrientation;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::player::player::Player;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use crate::utils::seeded_rng::seeded_rng;
+use rand::Rng;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Water Board: water pours in from the top of a board and five coloured gates decide which pipes it ends up in.
+// Each gate has a lever in front of the board, standing on the gate's block so you can tell which is which,
+// and a sixth lever turns the water on and off.
+// The wool above the board shows which pipes have to be filled, the chest opens once the water has settled in exactly those.
+//
+// The board is a vertical slice of the room: columns go along x from BOARD_X, rows go down from BOARD_TOP, all at BOARD_Z.
+
+const BOARD: [&str; 7] = [
+    "#######S#######",
+    "#.............#",
+    "#0##1##2##3##4#",
+    "#.##.##.##.##.#",
+    "#.##.##.##.##.#",
+    "#a##b##c##d##e#",
+    "###############",
+];
+
+const BOARD_X: i32 = 8;
+const BOARD_TOP: i32 = 82;
+const BOARD_Z: i32 = 24;
+
+const GATE_BLOCKS: [Blocks; 5] = [
+    Blocks::GoldBlock,
+    Blocks::HardenedClay,
+    Blocks::EmeraldBlock,
+    Blocks::QuartzBlock { variant: 0 },
+    Blocks::DiamondBlock,
+];
+
+/// Wool colour under each pipe and above it when the pipe has to be filled.
+const PIPE_COLORS: [u8; 5] = [5, 4, 14, 11, 10];
+
+const GATE_LEVERS: [BlockPos; 5] = [
+    BlockPos { x: 9, y: 70, z: 19 },
+    BlockPos { x: 12, y: 70, z: 19 },
+    BlockPos { x: 15, y: 70, z: 19 },
+    BlockPos { x: 18, y: 70, z: 19 },
+    BlockPos { x: 21, y: 70, z: 19 },
+];
+const WATER_LEVER: BlockPos = BlockPos { x: 15, y: 70, z: 16 };
+
+/// How far water runs sideways before it stops, same as vanilla.
+const SPREAD: u8 = 7;
+/// Ticks between each block of water flowing.
+const FLOW_INTERVAL: u64 = 5;
+
+type Cell = (usize, usize);
+
+pub struct WaterBoard {
+    gates_open: [bool; 5],
+    water_on: bool,
+    /// Pipes that have to be filled, and nothing else.
+    targets: HashSet<usize>,
+    /// Where the water will end up with the current levers, with how many steps it takes to get there and its block.
+    flow: HashMap<Cell, (u32, Blocks)>,
+    /// Cells that have water in them right now.
+    wet: HashSet<Cell>,
+    step: u32,
+    /// World positions of the gate levers, then the water lever.
+    levers: Vec<BlockPos>,
+    last_toggled_by: String,
+}
+
+impl WaterBoard {
+    pub fn new() -> WaterBoard {
+        let mut rng = seeded_rng();
+
+        // pick a gate configuration that fills at least one pipe, the pipes it fills are the solution
+        let targets = loop {
+            let gates: [bool; 5] = std::array::from_fn(|_| rng.random_bool(0.5));
+            let targets = filled_pipes(&flow(&gates, true).keys().copied().collect());
+            if !targets.is_empty() {
+                break targets;
+            }
+        };
+        // different gates can fill the same pipes, so the starting gates must not already fill the targets
+        let gates_open = loop {
+            let gates: [bool; 5] = std::array::from_fn(|_| rng.random_bool(0.5));
+            if filled_pipes(&flow(&gates, true).keys().copied().collect()) != targets {
+                break gates;
+            }
+        };
+
+        WaterBoard {
+            gates_open,
+            water_on: false,
+            targets,
+            flow: HashMap::new(),
+            wet: HashSet::new(),
+            step: 0,
+            levers: Vec::new(),
+            last_toggled_by: String::new(),
+        }
+    }
+
+    fn set_cell(&self, world: &mut World, room: &Room, cell: Cell, block: Blocks) {
+        let pos = world_pos(room, cell);
+        world.set_block_at(block, pos.x, pos.y, pos.z);
+    }
+
+    fn gate_block(&self, gate: usize) -> Blocks {
+        if self.gates_open[gate] { Blocks::Air } else { GATE_BLOCKS[gate] }
+    }
+
+    /// Works out where the water goes now and drains whatever it doesn't reach anymore,
+    /// the rest flows back in over the next ticks.
+    fn update_flow(&mut self, world: &mut World, room: &Room) {
+        self.flow = flow(&self.gates_open, self.water_on);
+        self.step = 0;
+
+        let drained: Vec<Cell> = self.wet.iter().filter(|cell| !self.flow.contains_key(cell)).copied().collect();
+        for cell in drained {
+            self.wet.remove(&cell);
+            let block = match board_char(cell) {
+                Some(gate @ '0'..='4') => self.gate_block(gate as usize - '0' as usize),
+                _ => Blocks::Air,
+            };
+            self.set_cell(world, room, cell, block);
+        }
+    }
+}
+
+impl Puzzle for WaterBoard {
+    fn name(&self) -> &'static str {
+        "Water Board"
+    }
+
+    fn chest(&self) -> (BlockPos, Direction) {
+        (BlockPos { x: 15, y: 70, z: 13 }, Direction::North)
+    }
+
+    fn load(&mut self, world: &mut World, room: &Room) {
+        for (row, line) in BOARD.iter().enumerate() {
+            for (col, char) in line.chars().enumerate() {
+                let block = match char {
+                    '#' => match board_char((row.wrapping_sub(1), col)) {
+                        Some(pipe @ 'a'..='e') => Blocks::Wool { color: PIPE_COLORS[pipe as usize - 'a' as usize] },
+                        _ => Blocks::StoneBrick { variant: 0 },
+                    },
+                    '0'..='4' => self.gate_block(char as usize - '0' as usize),
+                    _ => Blocks::Air,
+                };
+                self.set_cell(world, room, (row, col), block);
+            }
+        }
+
+        // the wool above the board shows which pipes have to be filled
+        for (col, char) in BOARD[BOARD.len() - 2].chars().enumerate() {
+            if let pipe @ 'a'..='e' = char {
+                let pipe = pipe as usize - 'a' as usize;
+                let mut pos = world_pos(room, (0, col));
+                pos.y += 1;
+                let block = if self.targets.contains(&pipe) { Blocks::Wool { color: PIPE_COLORS[pipe] } } else { Blocks::Air };
+                world.set_block_at(block, pos.x, pos.y, pos.z);
+            }
+        }
+
+        let orientation = LeverOrientation::UpX.rotate(room.rotation);
+        for (index, lever_pos) in GATE_LEVERS.iter().chain(std::iter::once(&WATER_LEVER)).enumerate() {
+            let pos = room.get_world_block_pos(lever_pos);
+            let (stand, powered) = match GATE_BLOCKS.get(index) {
+                Some(block) => (*block, self.gates_open[index]),
+                None => (Blocks::StoneBrick { variant: 0 }, self.water_on),
+            };
+            world.set_block_at(stand, pos.x, pos.y - 1, pos.z);
+            world.set_block_at(Blocks::Lever { orientation, powered }, pos.x, pos.y, pos.z);
+            world.interactable_blocks.insert(pos, BlockInteractAction::Lever);
+            self.levers.push(pos);
+        }
+    }
+
+    fn tick(&mut self, world: &mut World, room: &Room) -> Option<PuzzleOutcome> {
+        if self.wet.len() < self.flow.len() && world.tick_count.is_multiple_of(FLOW_INTERVAL) {
+            self.step += 1;
+            let flowing: Vec<(Cell, Blocks)> = self.flow.iter()
+                .filter(|(cell, (distance, _))| *distance < self.step && !self.wet.contains(cell))
+                .map(|(cell, (_, block))| (*cell, *block))
+                .collect();
+            for (cell, block) in flowing {
+                self.wet.insert(cell);
+                self.set_cell(world, room, cell, block);
+            }
+        }
+
+        // only once the water has settled, so it can't pass through the right pipes on the way somewhere else
+        if self.wet.len() == self.flow.len() && filled_pipes(&self.wet) == self.targets {
+            return Some(PuzzleOutcome::Solved(format!("§b{} §efilled the right pipes", self.last_toggled_by)));
+        }
+        None
+    }
+
+    fn click(&mut self, world: &mut World, room: &Room, player: &Player, block_pos: &BlockPos) -> PuzzleClick {
+        let Some(index) = self.levers.iter().position(|pos| pos == block_pos) else {
+            return PuzzleClick::Ignored;
+        };
+
+        let powered = if index < self.gates_open.len() {
+            self.gates_open[index] = !self.gates_open[index];
+            // a gate with water in it gets put back once the water drains
+            let gate = find_cell((b'0' + index as u8) as char);
+            if !self.wet.contains(&gate) {
+                self.set_cell(world, room, gate, self.gate_block(index));
+            }
+            self.gates_open[index]
+        } else {
+            self.water_on = !self.water_on;
+            self.water_on
+        };
+
+        if let Blocks::Lever { orientation, .. } = world.get_block_at(block_pos.x, block_pos.y, block_pos.z) {
+            world.set_block_at(Blocks::Lever { orientation, powered }, block_pos.x, block_pos.y, block_pos.z);
+        }
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::RandomClick.id(),
+                volume: 0.3,
+                pitch: if powered { 0.6 } else { 0.5 },
+                pos_x: block_pos.x as f64 + 0.5,
+                pos_y: block_pos.y as f64 + 0.5,
+                pos_z: block_pos.z as f64 + 0.5,
+            });
+        }
+
+        self.last_toggled_by = player.profile.username.clone();
+        self.update_flow(world, room);
+        PuzzleClick::Handled(None)
+    }
+}
+
+fn world_pos(room: &Room, (row, col): Cell) -> BlockPos {
+    room.get_world_block_pos(&BlockPos {
+        x: BOARD_X + col as i32,
+        y: BOARD_TOP - row as i32,
+        z: BOARD_Z,
+    })
+}
+
+fn find_cell(char: char) -> Cell {
+    BOARD.iter()
+        .enumerate()
+        .find_map(|(row, line)| line.find(char).map(|col| (row, col)))
+        .expect("every gate and the source are on the board")
+}
+
+fn board_char((row, col): Cell) -> Option<char> {
+    BOARD.get(row)?.chars().nth(col)
+}
+
+fn is_open(gates_open: &[bool; 5], cell: Cell) -> bool {
+    match board_char(cell) {
+        Some('#') | None => false,
+        Some(gate @ '0'..='4') => gates_open[gate as usize - '0' as usize],
+        Some(_) => true,
+    }
+}
+
+/// Runs the water from the source: it falls whenever it can, otherwise it spreads sideways up to SPREAD blocks.
+/// Returns every cell it reaches with how many steps it took and what water block goes there.
+fn flow(gates_open: &[bool; 5], water_on: bool) -> HashMap<Cell, (u32, Blocks)> {
+    let mut flow = HashMap::new();
+    if !water_on {
+        return flow;
+    }
+    let mut queue = VecDeque::from([(find_cell('S'), 0, SPREAD, Blocks::StillWater { level: 0 })]);
+    while let Some((cell @ (row, col), distance, spread, block)) = queue.pop_front() {
+        if flow.contains_key(&cell) {
+            continue;
+        }
+        flow.insert(cell, (distance, block));
+
+        if is_open(gates_open, (row + 1, col)) {
+            queue.push_back(((row + 1, col), distance + 1, SPREAD, Blocks::FlowingWater { level: 8 }));
+        } else if spread > 0 {
+            let level = SPREAD - spread + 1;
+            for side in [col.wrapping_sub(1), col + 1] {
+                if is_open(gates_open, (row, side)) {
+                    queue.push_back(((row, side), distance + 1, spread - 1, Blocks::FlowingWater { level }));
+                }
+            }
+        }
+    }
+    flow
+}
+
+fn filled_pipes(cells: &HashSet<Cell>) -> HashSet<usize> {
+    cells.iter()
+        .filter_map(|cell| match board_char(*cell) {
+            Some(pipe @ 'a'..='e') => Some(pipe as usize - 'a' as usize),
+            _ => None,
+        })
+        .collect()
+}
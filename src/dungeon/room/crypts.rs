@@ -1,7 +1,9 @@
 use once_cell::sync::Lazy;
-use include_dir::include_dir;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::dungeon::room::room_data_loader::{load_json_file, room_data_dir, PreviousRoomData};
 
 use crate::server::block::block_position::BlockPos;
 use crate::server::block::blocks::Blocks;
@@ -38,50 +40,59 @@ pub struct RoomCrypts {
     pub patterns: Vec<CryptPattern>,
 }
 
-static RELATIVE_1X1: Lazy<Option<RelativeCoordsFile>> = Lazy::new(|| {
-    let data = include_str!("../../room_data/relativecoords/1x1crypts.json");
-    serde_json::from_str::<RelativeCoordsFile>(data).ok()
-});
-
-static RELATIVE_1X2: Lazy<Option<RelativeCoordsFile>> = Lazy::new(|| {
-    let data = include_str!("../../room_data/relativecoords/1x2crypts.json");
-    serde_json::from_str::<RelativeCoordsFile>(data).ok()
-});
-
-static RELATIVE_REST: Lazy<Option<RelativeCoordsFile>> = Lazy::new(|| {
-    let data = include_str!("../../room_data/relativecoords/rest.json");
-    serde_json::from_str::<RelativeCoordsFile>(data).ok()
-});
-
-// Additional sources contributed later
-static RELATIVE_CHAMBERS: Lazy<Option<RelativeCoordsFile>> = Lazy::new(|| {
-    let data = include_str!("../../room_data/relativecoords/Chambers.json");
-    serde_json::from_str::<RelativeCoordsFile>(data).ok()
-});
-
-static RELATIVE_L1X3: Lazy<Option<RelativeCoordsFile>> = Lazy::new(|| {
-    let data = include_str!("../../room_data/relativecoords/Lroomsandonebythrees.json");
-    serde_json::from_str::<RelativeCoordsFile>(data).ok()
-});
-
-// Optional extra file: crypts(1).json. Loaded if present in the directory bundle.
-static RELATIVE_EXTRA: Lazy<Option<RelativeCoordsFile>> = Lazy::new(|| {
-    // Embed the directory and try to fetch the file at runtime (path relative to crate root)
-    static DIR: include_dir::Dir<'_> = include_dir!("src/room_data/relativecoords");
-    if let Some(file) = DIR.get_file("crypts(1).json") {
-        let contents = file.contents_utf8().unwrap_or("");
-        serde_json::from_str::<RelativeCoordsFile>(contents).ok()
-    } else {
-        None
+/// All of the crypt coordinate files, populated by reload_crypt_data
+#[derive(Default)]
+struct CryptFiles {
+    one_by_one: Option<RelativeCoordsFile>,
+    one_by_two: Option<RelativeCoordsFile>,
+    rest: Option<RelativeCoordsFile>,
+    // Additional sources contributed later
+    chambers: Option<RelativeCoordsFile>,
+    l_and_one_by_three: Option<RelativeCoordsFile>,
+    // Optional extra file: crypts(1).json. Loaded if present in the directory.
+    extra: Option<RelativeCoordsFile>,
+}
+
+static CRYPT_FILES: Lazy<RwLock<CryptFiles>> = Lazy::new(|| RwLock::new(CryptFiles::default()));
+
+/// Reloads every crypt file, files that fail to load are left empty and reported together.
+pub fn reload_crypt_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let mut errors = Vec::new();
+    let mut load = |path: &str| match load_json_file::<RelativeCoordsFile>(path) {
+        Ok(file) => Some(file),
+        Err(error) => {
+            errors.push(format!("{:#}", error));
+            None
+        }
+    };
+
+    let files = CryptFiles {
+        one_by_one: load("relativecoords/1x1crypts.json"),
+        one_by_two: load("relativecoords/1x2crypts.json"),
+        rest: load("relativecoords/rest.json"),
+        chambers: load("relativecoords/Chambers.json"),
+        l_and_one_by_three: load("relativecoords/Lroomsandonebythrees.json"),
+        extra: match room_data_dir().join("relativecoords/crypts(1).json").exists() {
+            true => load("relativecoords/crypts(1).json"),
+            false => None,
+        },
+    };
+
+    previous.replace(&CRYPT_FILES, files);
+
+    if !errors.is_empty() {
+        anyhow::bail!(errors.join(", "));
     }
-});
+    Ok(())
+}
 
 pub fn get_room_crypts(shape: &str, room_name: &str) -> Option<RoomCrypts> {
     // Select primary source based on shape
+    let files = CRYPT_FILES.read().unwrap();
     let primary = match shape {
-        "1x1" | "1x1_E" | "1x1_X" | "1x1_I" | "1x1_L" | "1x1_3" => RELATIVE_1X1.as_ref(),
-        "1x2" => RELATIVE_1X2.as_ref(),
-        _ => RELATIVE_REST.as_ref(),
+        "1x1" | "1x1_E" | "1x1_X" | "1x1_I" | "1x1_L" | "1x1_3" => files.one_by_one.as_ref(),
+        "1x2" => files.one_by_two.as_ref(),
+        _ => files.rest.as_ref(),
     };
 
     // Normalize room names to make matching more forgiving
@@ -107,17 +118,17 @@ pub fn get_room_crypts(shape: &str, room_name: &str) -> Option<RoomCrypts> {
             all_blocks.extend(entry.crypts.iter().cloned());
         }
     }
-    if let Some(chambers) = RELATIVE_CHAMBERS.as_ref() {
+    if let Some(chambers) = files.chambers.as_ref() {
         if let Some(entry) = find_entry(chambers, room_name, &want) {
             all_blocks.extend(entry.crypts.iter().cloned());
         }
     }
-    if let Some(l1x3) = RELATIVE_L1X3.as_ref() {
+    if let Some(l1x3) = files.l_and_one_by_three.as_ref() {
         if let Some(entry) = find_entry(l1x3, room_name, &want) {
             all_blocks.extend(entry.crypts.iter().cloned());
         }
     }
-    if let Some(extra) = RELATIVE_EXTRA.as_ref() {
+    if let Some(extra) = files.extra.as_ref() {
         if let Some(entry) = find_entry(extra, room_name, &want) {
             all_blocks.extend(entry.crypts.iter().cloned());
         }
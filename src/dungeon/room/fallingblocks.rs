@@ -6,10 +6,10 @@ use crate::net::protocol::play::clientbound::{DestroyEntites, EntityAttach, Spaw
 use crate::net::var_int::VarInt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use include_dir::{include_dir, Dir};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::dungeon::room::room_data_loader::{load_json_file, PreviousRoomData};
 
-// Include the fallingblocks data
-const FALLINGBLOCKS_DIR: Dir<'_> = include_dir!("src/room_data/relativecoords");
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FallingBlock {
@@ -29,26 +29,24 @@ pub struct RoomFallingBlocksEntry {
     pub crypts: Vec<Vec<FallingBlock>>, // array of falling block patterns, each a list of blocks
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FallingBlocksFile {
     pub schema: u32,
     pub rooms: HashMap<String, RoomFallingBlocksEntry>,
 }
 
-/// Load fallingblocks data from the fallingblocks.json file
-fn load_fallingblocks_data() -> FallingBlocksFile {
-    let data = FALLINGBLOCKS_DIR.get_file("fallingblocks.json")
-        .expect("fallingblocks.json not found")
-        .contents_utf8()
-        .expect("Invalid UTF-8 in fallingblocks.json");
-    
-    serde_json::from_str(data)
-        .expect("Failed to parse fallingblocks.json")
+static FALLINGBLOCKS_DATA: Lazy<RwLock<FallingBlocksFile>> = Lazy::new(|| RwLock::new(FallingBlocksFile::default()));
+
+/// Reload fallingblocks data from the fallingblocks.json file
+pub fn reload_fallingblocks_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let data = load_json_file::<FallingBlocksFile>("relativecoords/fallingblocks.json")?;
+    previous.replace(&FALLINGBLOCKS_DATA, data);
+    Ok(())
 }
 
 /// Get falling block patterns for a specific room
 pub fn get_room_fallingblocks(shape: &str, room_name: &str) -> Option<Vec<FallingBlockPattern>> {
-    let file = load_fallingblocks_data();
+    let file = FALLINGBLOCKS_DATA.read().unwrap();
     
     // Helper function to find entry by shape and room name
     fn find_entry<'a>(file: &'a FallingBlocksFile, key: &str, _want_norm: &str) -> Option<&'a RoomFallingBlocksEntry> {
@@ -10,6 +10,8 @@ use crate::net::var_int::VarInt;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::dungeon::room::room_data_loader::{load_json_file, PreviousRoomData};
 
 /// Room lever data structure for JSON parsing
 #[derive(Debug, Deserialize)]
@@ -18,21 +20,24 @@ struct RoomLeverData {
 }
 
 /// Lever file structure for JSON parsing
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct LeverFile {
     schema: u32,
     rooms: HashMap<String, RoomLeverData>,
 }
 
-/// Load lever data from the JSON file
-static LEVER_DATA: Lazy<LeverFile> = Lazy::new(|| {
-    let lever_json = include_str!("../../room_data/lever shi/lever.json");
-    serde_json::from_str(lever_json).expect("Failed to parse lever.json")
-});
+/// Lever data from the JSON file, populated by reload_lever_data
+static LEVER_DATA: Lazy<RwLock<LeverFile>> = Lazy::new(|| RwLock::new(LeverFile::default()));
+
+pub fn reload_lever_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let data = load_json_file::<LeverFile>("lever shi/lever.json")?;
+    previous.replace(&LEVER_DATA, data);
+    Ok(())
+}
 
 /// Get lever data for a specific room by shape and name (similar to crypts)
-pub fn get_room_levers<'a>(_shape_key: &str, room_name: &str) -> Option<&'a Vec<LeverData>> {
-    LEVER_DATA.rooms.get(room_name).map(|room_data| &room_data.levers)
+pub fn get_room_levers(_shape_key: &str, room_name: &str) -> Option<Vec<LeverData>> {
+    LEVER_DATA.read().unwrap().rooms.get(room_name).map(|room_data| room_data.levers.clone())
 }
 
 /// Lever data structure for storing lever coordinates and associated falling blocks
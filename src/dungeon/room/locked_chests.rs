@@ -1,11 +1,14 @@
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::dungeon::room::room_data_loader::{load_json_file, PreviousRoomData};
 
 use crate::server::block::block_position::BlockPos;
 use crate::server::utils::direction::Direction;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct LockedChestsFile {
     schema: u32,
     rooms: HashMap<String, Vec<LockedChestEntry>>,
@@ -20,14 +23,17 @@ pub struct LockedChestEntry {
     pub lever: [i32; 3],
 }
 
-static LOCKED_CHESTS_DATA: Lazy<LockedChestsFile> = Lazy::new(|| {
-    let locked_json = include_str!("../../room_data/Chests/locked.json");
-    serde_json::from_str(locked_json).expect("Failed to parse locked.json")
-});
+static LOCKED_CHESTS_DATA: Lazy<RwLock<LockedChestsFile>> = Lazy::new(|| RwLock::new(LockedChestsFile::default()));
+
+pub fn reload_locked_chests_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let data = load_json_file::<LockedChestsFile>("Chests/locked.json")?;
+    previous.replace(&LOCKED_CHESTS_DATA, data);
+    Ok(())
+}
 
 /// Get locked chest entries for a specific room by name
-pub fn get_room_locked_chests(room_name: &str) -> Option<&Vec<LockedChestEntry>> {
-    LOCKED_CHESTS_DATA.rooms.get(room_name)
+pub fn get_room_locked_chests(room_name: &str) -> Option<Vec<LockedChestEntry>> {
+    LOCKED_CHESTS_DATA.read().unwrap().rooms.get(room_name).cloned()
 }
 
 /// Convert facing string to Direction
@@ -1,5 +1,7 @@
 pub mod room;
 pub mod room_data;
+pub mod room_data_loader;
+pub mod room_pool;
 pub mod secrets;
 pub mod secrets_loader;
 pub mod levers;
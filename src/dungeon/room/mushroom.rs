@@ -1,12 +1,14 @@
 use once_cell::sync::Lazy;
 use serde::Deserialize;
+use std::sync::RwLock;
 
 use crate::server::block::block_position::BlockPos;
 use crate::server::utils::direction::Direction;
 
 use super::crypts::{CryptBlock, rotate_block_pos};
+use super::room_data_loader::{load_json_file, PreviousRoomData};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct MushroomFile {
     schema: Option<u32>,
     rooms: std::collections::HashMap<String, MushroomRoomEntry>,
@@ -18,10 +20,13 @@ struct MushroomRoomEntry {
     crypts: Vec<Vec<CryptBlock>>, // reuse same block format
 }
 
-static MUSHROOM_DATA: Lazy<Option<MushroomFile>> = Lazy::new(|| {
-    let data = include_str!("../../room_data/Mushroom secret/Mushroomdata.json");
-    serde_json::from_str::<MushroomFile>(data).ok()
-});
+static MUSHROOM_DATA: Lazy<RwLock<MushroomFile>> = Lazy::new(|| RwLock::new(MushroomFile::default()));
+
+pub fn reload_mushroom_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let data = load_json_file::<MushroomFile>("Mushroom secret/Mushroomdata.json")?;
+    previous.replace(&MUSHROOM_DATA, data);
+    Ok(())
+}
 
 fn normalize(s: &str) -> String {
     s.to_lowercase()
@@ -64,7 +69,7 @@ pub struct MushroomSets {
 
 /// Load mushroom secret sets for a given room name; returns world-relative positions once rotated.
 pub fn get_room_mushrooms(room_name: &str, rotation: Direction, corner_pos: &BlockPos) -> Vec<MushroomSets> {
-    let Some(file) = MUSHROOM_DATA.as_ref() else { return Vec::new(); };
+    let file = MUSHROOM_DATA.read().unwrap();
 
     let want = normalize(room_name);
     // Find entry either by exact key or normalized
@@ -1,4 +1,5 @@
 use crate::dungeon::crushers::Crusher;
+use crate::dungeon::traps::TrapHazard;
 use crate::dungeon::door::Door;
 use crate::dungeon::dungeon::DUNGEON_ORIGIN;
 use crate::dungeon::room::room_data::{RoomData, RoomShape, RoomType};
@@ -38,9 +39,11 @@ pub struct Room {
 
     pub tick_amount: u32,
     pub crushers: Vec<Crusher>,
+    pub hazards: Vec<TrapHazard>,
     pub crypt_patterns: Vec<Vec<(BlockPos, Option<u16>)>>, // world positions with expected block ids
     pub crypts_checked: bool,
     pub crypts_detected_count: usize,
+    pub crypts_exploded: usize,
     pub superboomwall_patterns: Vec<SuperboomWallPattern>, // superboomwall patterns for this room
     pub superboomwalls_checked: bool,
     pub superboomwalls_detected_count: usize,
@@ -52,6 +55,7 @@ pub struct Room {
     pub lever_data: Vec<LeverData>, // Store lever data for this room
     
     pub entered: bool,
+    pub entered_at: Option<u64>, // run ticks when the room was first entered, used for the splits
     pub found_secrets: u8, // Number of secrets found in this room (runtime tracking)
     pub json_secrets: Vec<std::rc::Rc<std::cell::RefCell<crate::dungeon::room::secrets::DungeonSecret>>>, // Secrets from secrets.json
     pub room_entry_secrets_spawned: bool, // Track if schest/sess have been spawned on room entry
@@ -232,15 +236,17 @@ impl Room {
             }
         }
 
-        Room {
+        let mut room = Room {
             segments,
             room_data,
             rotation,
             tick_amount: 0,
             crushers,
+            hazards: Vec::new(),
             crypt_patterns,
             crypts_checked: false,
             crypts_detected_count: 0,
+            crypts_exploded: 0,
             superboomwall_patterns,
             superboomwalls_checked: false,
             superboomwalls_detected_count: 0,
@@ -251,10 +257,17 @@ impl Room {
             mushroom_sets,
             lever_data,
             entered: false,
+            entered_at: None,
             found_secrets: 0,
             json_secrets: Vec::new(),
             room_entry_secrets_spawned: false,
-        }
+        };
+
+        // hazards are placed relative to the room, so they need the finished room to work out their positions
+        room.hazards = room.room_data.hazard_data.iter()
+            .map(|data| TrapHazard::new(data, &room))
+            .collect();
+        room
     }
 
     pub fn get_corner_pos(&self) -> BlockPos {
@@ -365,6 +378,7 @@ impl Room {
             }
         }
 
+        self.crypts_exploded += exploded;
         exploded
     }
 
@@ -751,6 +765,10 @@ impl Room {
         // Register levers for this room
         self.register_levers(world);
 
+        for hazard in self.hazards.iter_mut() {
+            hazard.load(world);
+        }
+
         // Special placement for Gold room
         if self.room_data.name == "Gold" {
             let pos = BlockPos { x: 15, y: 100, z: 15 }.rotate(self.rotation);
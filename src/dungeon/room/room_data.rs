@@ -1,9 +1,12 @@
 use crate::dungeon::door::Door;
+use crate::dungeon::traps::TrapHazardData;
 use crate::dungeon::room::room::{Room, RoomSegment};
+use crate::dungeon::room::room_pool::RoomPool;
 use crate::server::block::blocks::Blocks;
 use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
 use crate::utils::seeded_rng::seeded_rng;
-use rand::seq::IteratorRandom;
+use anyhow::Context;
+use rand::seq::{IndexedRandom, IteratorRandom};
 use serde_json::Value;
 use std::collections::HashSet;
 
@@ -26,8 +29,8 @@ pub enum RoomShape {
 }
 
 impl RoomShape {
-    pub fn from_str(value: &str) -> RoomShape {
-        match value {
+    pub fn from_str(value: &str) -> Option<RoomShape> {
+        Some(match value {
             "1x1" => Self::OneByOne,
             "1x1_E" => Self::OneByOneEnd,
             "1x1_X" => Self::OneByOneCross,
@@ -40,8 +43,8 @@ impl RoomShape {
             "2x2" => Self::TwoByTwo,
             "4x4" => Self::FourByFour,
             "L" => Self::L,
-            _ => unimplemented!(),
-        }
+            _ => return None,
+        })
     }
 
     pub fn from_segments(segments: &[RoomSegment], dungeon_doors: &[Door]) -> RoomShape {
@@ -119,21 +122,25 @@ pub struct RoomData {
     pub height: i32,
     pub block_data: Vec<Blocks>,
     pub crusher_data: Vec<Value>, // Needs to be parsed when rooms are generated
+    pub hazard_data: Vec<TrapHazardData>, // Trap room hazards other than crushers
     pub secrets: u8, // Total number of secrets in this room
 }
 
 impl RoomData {
-    pub fn from_raw_json(raw_data: &str) -> RoomData {
-        let json_data: Value = serde_json::from_str(raw_data).unwrap(); // surely we just parse into a struct instead of doing this indexing?
-
-        let name = json_data["name"].as_str().unwrap().to_string();
-        let id = json_data["id"].as_str().unwrap().to_string();
-        let shape = RoomShape::from_str(json_data["shape"].as_str().unwrap());
-        let room_type = RoomType::from_str(json_data["type"].as_str().unwrap());
-        let bottom = json_data["bottom"].as_number().unwrap().as_u64().unwrap() as i32;
-        let width = json_data["width"].as_number().unwrap().as_u64().unwrap() as i32;
-        let length = json_data["length"].as_number().unwrap().as_u64().unwrap() as i32;
-        let height = json_data["height"].as_number().unwrap().as_u64().unwrap() as i32;
+    pub fn from_raw_json(raw_data: &str) -> anyhow::Result<RoomData> {
+        let json_data: Value = serde_json::from_str(raw_data)?; // surely we just parse into a struct instead of doing this indexing?
+
+        let get_str = |key: &str| json_data[key].as_str().with_context(|| format!("Missing string field '{}'", key));
+        let get_int = |key: &str| json_data[key].as_u64().map(|n| n as i32).with_context(|| format!("Missing number field '{}'", key));
+
+        let name = get_str("name")?.to_string();
+        let id = get_str("id")?.to_string();
+        let shape = RoomShape::from_str(get_str("shape")?).with_context(|| format!("Unknown room shape '{}'", json_data["shape"]))?;
+        let room_type = RoomType::from_str(get_str("type")?);
+        let bottom = get_int("bottom")?;
+        let width = get_int("width")?;
+        let length = get_int("length")?;
+        let height = get_int("height")?;
 
         let secrets = json_data["secrets"]
             .as_number()
@@ -142,21 +149,25 @@ impl RoomData {
             .unwrap_or(0);
 
         let crusher_data: Vec<Value> = json_data["crushers"].as_array().unwrap_or(&Vec::new()).to_vec();
+        let hazard_data: Vec<TrapHazardData> = match json_data.get("hazards") {
+            Some(hazards) => serde_json::from_value(hazards.clone()).context("Invalid hazards")?,
+            None => Vec::new(),
+        };
 
-        let hex_data = json_data["block_data"].as_str().unwrap();
+        let hex_data = get_str("block_data")?;
 
         let mut block_data: Vec<Blocks> = Vec::new();
 
         for i in (0..hex_data.len()).step_by(4) {
-            let hex_str = hex_data.get(i..i+4).unwrap();
+            let hex_str = hex_data.get(i..i+4).with_context(|| format!("Truncated block data at {}", i))?;
     
-            let num = u16::from_str_radix(hex_str, 16).unwrap();
+            let num = u16::from_str_radix(hex_str, 16).with_context(|| format!("Invalid block data '{}' at {}", hex_str, i))?;
             let block = Blocks::from(num);
             
             block_data.push(block)
         }
 
-        RoomData {
+        Ok(RoomData {
             name,
             id,
             shape,
@@ -167,24 +178,9 @@ impl RoomData {
             height,
             block_data,
             crusher_data,
+            hazard_data,
             secrets,
-        }
-    }
-
-    pub fn dummy() -> RoomData {
-        RoomData {
-            name: String::from("Dummy"),
-            id: String::from(""),
-            shape: RoomShape::OneByOne,
-            room_type: RoomType::Normal,
-            bottom: 68,
-            width: 31,
-            length: 31,
-            height: 30,
-            block_data: vec![],
-            crusher_data: vec![],
-            secrets: 0,
-        }
+        })
     }
 }
 
@@ -193,16 +189,33 @@ pub fn get_random_data_with_type(
     room_shape: RoomShape,
     data_storage: &DeterministicHashMap<usize, RoomData>,
     current_rooms: &[Room],
-) -> RoomData {
-    // Random selection from available rooms matching the criteria
-    data_storage.iter()
+    room_pool: &RoomPool,
+) -> Option<RoomData> {
+    let candidates = data_storage.values()
         .filter(|data| {
-            data.1.room_type == room_type &&
-                data.1.shape == room_shape &&
-                !current_rooms.iter().any(|room| room.room_data == *data.1) // No duplicate rooms
-        })
-        .map(|x| x.1)
-        .choose(&mut seeded_rng())
-        .unwrap_or(&RoomData::dummy())
-        .clone()
-}
\ No newline at end of file
+            data.room_type == room_type &&
+                data.shape == room_shape &&
+                !room_pool.is_excluded(data) &&
+                !current_rooms.iter().any(|room| room.room_data == **data) // No duplicate rooms
+        });
+
+    // Included rooms get the first spot that fits them
+    if !room_pool.included.is_empty() {
+        if let Some(included) = candidates.clone().filter(|data| room_pool.is_included(data)).choose(&mut seeded_rng()) {
+            return Some(included.clone());
+        }
+    }
+
+    if room_pool.weights.is_empty() {
+        // Random selection from available rooms matching the criteria
+        return candidates.choose(&mut seeded_rng()).cloned();
+    }
+
+    let candidates = candidates.collect::<Vec<&RoomData>>();
+    // every candidate having a weight of 0 can't be picked from by weight, so all of them are equally likely instead
+    candidates
+        .choose_weighted(&mut seeded_rng(), |data| room_pool.weight(data))
+        .ok()
+        .or_else(|| candidates.choose(&mut seeded_rng()))
+        .map(|data| (*data).clone())
+}
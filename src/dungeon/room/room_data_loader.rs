@@ -0,0 +1,179 @@
+use crate::dungeon::puzzle::quiz;
+use crate::dungeon::room::room_data::RoomData;
+use crate::dungeon::room::{crypts, fallingblocks, levers, locked_chests, mushroom, secrets_loader, superboomwalls};
+use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
+use anyhow::{bail, Context};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+// Room data is read from a directory at runtime instead of being baked into the binary,
+// so data fixes only need a /reloadrooms instead of a recompile.
+//
+// The directory contains the room jsons ("<id>,<name>,...json") at the top level,
+// along with bettermapRooms.json and the sub folders for secrets, crypts, walls, levers, etc.
+
+pub const DEFAULT_ROOM_DATA_DIR: &str = "src/room_data";
+
+static ROOM_DATA_DIR: Lazy<RwLock<PathBuf>> = Lazy::new(|| RwLock::new(PathBuf::from(DEFAULT_ROOM_DATA_DIR)));
+
+pub fn set_room_data_dir(path: impl Into<PathBuf>) {
+    *ROOM_DATA_DIR.write().unwrap() = path.into();
+}
+
+pub fn room_data_dir() -> PathBuf {
+    ROOM_DATA_DIR.read().unwrap().clone()
+}
+
+pub fn read_room_data_file(relative_path: &str) -> anyhow::Result<String> {
+    let path = room_data_dir().join(relative_path);
+    std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+pub fn load_json_file<T: DeserializeOwned>(relative_path: &str) -> anyhow::Result<T> {
+    let contents = read_room_data_file(relative_path)?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", relative_path))
+}
+
+/// Everything read by [load_room_data], errors are per file so one broken room doesn't stop the rest from loading.
+pub struct RoomDataLoad {
+    pub storage: DeterministicHashMap<usize, RoomData>,
+    pub errors: Vec<anyhow::Error>,
+    /// The secret, crypt, etc. data the reload replaced, in case the new rooms don't work out.
+    pub previous: PreviousRoomData,
+}
+
+/// Keeps whatever the reloads replaced so it can be put back with [PreviousRoomData::restore].
+#[derive(Default)]
+pub struct PreviousRoomData {
+    restores: Vec<Box<dyn FnOnce()>>,
+}
+
+impl PreviousRoomData {
+    pub fn replace<T: Send + Sync + 'static>(&mut self, data: &'static RwLock<T>, value: T) {
+        let old = std::mem::replace(&mut *data.write().unwrap(), value);
+        self.restores.push(Box::new(move || *data.write().unwrap() = old));
+    }
+
+    pub fn restore(self) {
+        for restore in self.restores.into_iter().rev() {
+            restore();
+        }
+    }
+}
+
+/// (Re)loads the room jsons as well as the secret, crypt, superboom wall, falling block, lever, locked chest and mushroom data, along with the quiz questions.
+pub fn load_room_data() -> RoomDataLoad {
+    let mut errors = Vec::new();
+    let mut previous = PreviousRoomData::default();
+
+    let reloads: [fn(&mut PreviousRoomData) -> anyhow::Result<()>; 8] = [
+        secrets_loader::reload_secrets_data,
+        crypts::reload_crypt_data,
+        superboomwalls::reload_superboomwalls_data,
+        fallingblocks::reload_fallingblocks_data,
+        levers::reload_lever_data,
+        locked_chests::reload_locked_chests_data,
+        mushroom::reload_mushroom_data,
+        quiz::reload_quiz_data,
+    ];
+
+    for reload in reloads {
+        if let Err(error) = reload(&mut previous) {
+            errors.push(error);
+        }
+    }
+
+    let storage = match load_room_data_storage(&mut errors) {
+        Ok(storage) => storage,
+        Err(error) => {
+            errors.push(error);
+            DeterministicHashMap::default()
+        }
+    };
+
+    RoomDataLoad {
+        storage,
+        errors,
+        previous,
+    }
+}
+
+fn load_room_data_storage(errors: &mut Vec<anyhow::Error>) -> anyhow::Result<DeterministicHashMap<usize, RoomData>> {
+    let dir = room_data_dir();
+
+    // Load secrets from bettermapRooms.json
+    let mut secrets_map: HashMap<String, u8> = HashMap::new();
+    match load_json_file::<serde_json::Value>("bettermapRooms.json") {
+        Ok(bettermap_rooms) => {
+            for room in bettermap_rooms.as_array().into_iter().flatten() {
+                let name = room.get("name").and_then(|n| n.as_str());
+                let secrets = room.get("secrets").and_then(|s| s.as_u64());
+
+                if let (Some(name), Some(secrets)) = (name, secrets) {
+                    secrets_map.insert(name.to_string(), secrets as u8);
+                }
+            }
+        }
+        Err(error) => errors.push(error),
+    }
+
+    // sorted so that the storage (and thus seeded room picks) doesn't depend on the order the os lists files in
+    let mut paths = std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read room data directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    let mut storage = DeterministicHashMap::default();
+
+    for path in paths {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        // Skip bettermapRooms.json - it's not a room file
+        if file_name == "bettermapRooms.json" {
+            continue;
+        }
+
+        let result = load_room_file(&path, file_name).with_context(|| format!("Failed to load room {}", path.display()));
+
+        match result {
+            Ok((room_id, mut room_data)) => {
+                // Override secrets from bettermapRooms.json if available
+                if let Some(secrets) = secrets_map.get(&room_data.name) {
+                    room_data.secrets = *secrets;
+                }
+
+                // Override specific room secrets
+                if room_data.name == "Golden Oasis" {
+                    room_data.secrets = 3;
+                }
+
+                if storage.insert(room_id, room_data).is_some() {
+                    errors.push(anyhow::anyhow!("Room id {} in {} is used by more than one file", room_id, file_name));
+                }
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+
+    Ok(storage)
+}
+
+// roomdata first digit (the key) is just a list of numbers 0..etc. this could just be a vec with roomid lookups.
+fn load_room_file(path: &PathBuf, file_name: &str) -> anyhow::Result<(usize, RoomData)> {
+    let room_id = match file_name.split(",").next().map(|id| id.parse::<usize>()) {
+        Some(Ok(room_id)) => room_id,
+        _ => bail!("File name should start with the room id followed by a comma"),
+    };
+
+    let contents = std::fs::read_to_string(path)?;
+    let room_data = RoomData::from_raw_json(&contents)?;
+
+    Ok((room_id, room_data))
+}
@@ -0,0 +1,162 @@
+use crate::dungeon::floor_profile::{FloorProfile, MAX_GRID_SIZE};
+use crate::dungeon::generator::PinnedCell;
+use crate::dungeon::room::room_data::RoomData;
+use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+
+// Controls which rooms get picked when a dungeon is built, for practicing specific rooms.
+//
+// Configured with the same syntax from the cli (--pool "include Mossy") and the /pool command:
+// pin <room> <x,z>     always place the room at that grid position
+// include <room>       pick the room before any other room of the same type and shape
+// exclude <room>       never pick the room
+// weight <room> <n>    make the room n times as likely to be picked (default is 1)
+// remove <room>        undo any of the above for the room
+// clear                reset the pool
+//
+// Room names are case insensitive and underscores can be used in place of spaces.
+
+#[derive(Debug, Clone, Default)]
+pub struct RoomPool {
+    /// Grid position (x, z) to room name, positions outside of the current floor are ignored.
+    pub pinned: Vec<((usize, usize), String)>,
+    pub included: Vec<String>,
+    pub excluded: Vec<String>,
+    pub weights: HashMap<String, u32>,
+}
+
+pub fn normalize_room_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+impl RoomPool {
+    pub fn is_excluded(&self, room_data: &RoomData) -> bool {
+        self.excluded.contains(&normalize_room_name(&room_data.name))
+    }
+
+    pub fn is_included(&self, room_data: &RoomData) -> bool {
+        self.included.contains(&normalize_room_name(&room_data.name))
+    }
+
+    pub fn weight(&self, room_data: &RoomData) -> u32 {
+        self.weights.get(&normalize_room_name(&room_data.name)).copied().unwrap_or(1)
+    }
+
+    pub fn get_pinned_name(&self, x: usize, z: usize) -> Option<&String> {
+        self.pinned.iter()
+            .find(|(position, _)| *position == (x, z))
+            .map(|(_, name)| name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pinned.is_empty() && self.included.is_empty() && self.excluded.is_empty() && self.weights.is_empty()
+    }
+
+    /// The type and shape every pinned room needs at its position, so the generator can leave space for them.
+    pub fn get_pinned_cells(&self, floor: &FloorProfile, room_data_storage: &DeterministicHashMap<usize, RoomData>) -> Vec<PinnedCell> {
+        self.pinned.iter()
+            .filter(|((x, z), _)| floor.is_in_grid(*x, *z))
+            .filter_map(|(cell, name)| {
+                let room_data = room_data_storage.values().find(|data| normalize_room_name(&data.name) == *name)?;
+                Some(PinnedCell { cell: *cell, room_type: room_data.room_type, shape: room_data.shape.clone() })
+            })
+            .collect()
+    }
+
+    /// Applies a single pool instruction, returning a message describing what changed.
+    /// Room names are checked against the storage so typos don't silently do nothing.
+    pub fn apply(&mut self, args: &[&str], room_data_storage: &DeterministicHashMap<usize, RoomData>) -> anyhow::Result<String> {
+        let Some(action) = args.first() else {
+            bail!("Missing pool action, expected one of pin, include, exclude, weight, remove, clear");
+        };
+
+        let room_name = || -> anyhow::Result<String> {
+            let name = normalize_room_name(args.get(1).context("Missing room name")?);
+            if !room_data_storage.values().any(|data| normalize_room_name(&data.name) == name) {
+                bail!("There is no room called '{}'", name);
+            }
+            Ok(name)
+        };
+
+        let message = match *action {
+            "pin" => {
+                let name = room_name()?;
+                let position = args.get(2).context("Missing grid position, expected x,z")?;
+                let (x, z) = position.split_once(',')
+                    .and_then(|(x, z)| Some((x.trim().parse::<usize>().ok()?, z.trim().parse::<usize>().ok()?)))
+                    .filter(|(x, z)| *x < MAX_GRID_SIZE && *z < MAX_GRID_SIZE)
+                    .with_context(|| format!("Invalid grid position '{}', expected x,z between 0 and {}", position, MAX_GRID_SIZE - 1))?;
+
+                self.pinned.retain(|(pinned_position, pinned)| *pinned_position != (x, z) && *pinned != name);
+                self.pinned.push(((x, z), name.clone()));
+                format!("Pinned {} to {},{}", name, x, z)
+            }
+            "include" => {
+                let name = room_name()?;
+                self.excluded.retain(|excluded| *excluded != name);
+                if !self.included.contains(&name) {
+                    self.included.push(name.clone());
+                }
+                format!("Included {}", name)
+            }
+            "exclude" => {
+                let name = room_name()?;
+                self.included.retain(|included| *included != name);
+                if !self.excluded.contains(&name) {
+                    self.excluded.push(name.clone());
+                }
+                format!("Excluded {}", name)
+            }
+            "weight" => {
+                let name = room_name()?;
+                let weight = args.get(2)
+                    .and_then(|weight| weight.parse::<u32>().ok())
+                    .context("Missing or invalid weight, expected a whole number")?;
+                self.weights.insert(name.clone(), weight);
+                format!("Set the weight of {} to {}", name, weight)
+            }
+            "remove" => {
+                let name = room_name()?;
+                self.pinned.retain(|(_, pinned)| *pinned != name);
+                self.included.retain(|included| *included != name);
+                self.excluded.retain(|excluded| *excluded != name);
+                self.weights.remove(&name);
+                format!("Removed {} from the pool", name)
+            }
+            "clear" => {
+                *self = RoomPool::default();
+                "Cleared the pool".to_string()
+            }
+            _ => bail!("Unknown pool action '{}', expected one of pin, include, exclude, weight, remove, clear", action),
+        };
+
+        Ok(message)
+    }
+
+    /// A line per setting, for showing the current pool to players.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for ((x, z), name) in self.pinned.iter() {
+            lines.push(format!("Pinned: {} at {},{}", name, x, z));
+        }
+        for name in self.included.iter() {
+            lines.push(format!("Included: {}", name));
+        }
+        for name in self.excluded.iter() {
+            lines.push(format!("Excluded: {}", name));
+        }
+        let mut weights = self.weights.iter().collect::<Vec<(&String, &u32)>>();
+        weights.sort();
+        for (name, weight) in weights {
+            lines.push(format!("Weight: {} x{}", name, weight));
+        }
+
+        lines
+    }
+}
@@ -1,3 +1,4 @@
+use crate::dungeon::room::room_data_loader::{load_json_file, PreviousRoomData};
 use crate::dungeon::room::secrets::{DungeonSecret, SecretType};
 use crate::server::block::block_position::BlockPos;
 use crate::server::block::rotatable::Rotatable;
@@ -9,10 +10,11 @@ use serde::Deserialize;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::RwLock;
 use once_cell::sync::Lazy;
 use rand::Rng;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct SecretJsonData {
     schema: u32,
     rooms: HashMap<String, RoomSecrets>,
@@ -59,25 +61,20 @@ struct ItemJson {
     damage: u16,
 }
 
-static SECRETS_DATA: Lazy<SecretJsonData> = Lazy::new(|| {
-    // Load secrets.json from room_data/Chests/secrets.json
-    let json_str = include_str!("../../room_data/Chests/secrets.json");
-    
-    match serde_json::from_str::<SecretJsonData>(json_str) {
-        Ok(data) => data,
-        Err(_) => {
-            SecretJsonData {
-                schema: 1,
-                rooms: HashMap::new(),
-            }
-        }
-    }
-});
+// Populated by reload_secrets_data, through room_data_loader::load_room_data
+static SECRETS_DATA: Lazy<RwLock<SecretJsonData>> = Lazy::new(|| RwLock::new(SecretJsonData::default()));
+
+pub fn reload_secrets_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let data = load_json_file::<SecretJsonData>("Chests/secrets.json")?;
+    previous.replace(&SECRETS_DATA, data);
+    Ok(())
+}
 
 pub fn load_secrets_for_room(room_name: &str, corner: BlockPos, rotation: crate::server::utils::direction::Direction) -> Vec<Rc<RefCell<DungeonSecret>>> {
     let mut secrets = Vec::new();
     
-    if let Some(room_secrets) = SECRETS_DATA.rooms.get(room_name) {
+    let secrets_data = SECRETS_DATA.read().unwrap();
+    if let Some(room_secrets) = secrets_data.rooms.get(room_name) {
         let total = room_secrets.rchest.len() + room_secrets.ress.len() + room_secrets.batsp.len() 
             + room_secrets.batdie.len() + room_secrets.itemsp.len() 
             + room_secrets.schest.len() + room_secrets.sess.len();
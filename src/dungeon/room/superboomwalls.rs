@@ -1,10 +1,10 @@
 use crate::server::block::block_position::BlockPos;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use include_dir::{include_dir, Dir};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use crate::dungeon::room::room_data_loader::{load_json_file, PreviousRoomData};
 
-// Include the superboomwalls data
-const SUPERBOOMWALLS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/room_data/Superboomwalls");
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuperboomWallBlock {
@@ -24,26 +24,24 @@ pub struct RoomSuperboomWallsEntry {
     pub crypts: Vec<Vec<SuperboomWallBlock>>, // array of walls, each a list of blocks
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SuperboomWallsFile {
     pub schema: u32,
     pub rooms: HashMap<String, RoomSuperboomWallsEntry>,
 }
 
-/// Load superboomwalls data from the wallsboom.json file
-fn load_superboomwalls_data() -> SuperboomWallsFile {
-    let data = SUPERBOOMWALLS_DIR.get_file("wallsboom.json")
-        .expect("wallsboom.json not found")
-        .contents_utf8()
-        .expect("Invalid UTF-8 in wallsboom.json");
-    
-    serde_json::from_str(data)
-        .expect("Failed to parse wallsboom.json")
+static SUPERBOOMWALLS_DATA: Lazy<RwLock<SuperboomWallsFile>> = Lazy::new(|| RwLock::new(SuperboomWallsFile::default()));
+
+/// Reload superboomwalls data from the wallsboom.json file
+pub fn reload_superboomwalls_data(previous: &mut PreviousRoomData) -> anyhow::Result<()> {
+    let data = load_json_file::<SuperboomWallsFile>("Superboomwalls/wallsboom.json")?;
+    previous.replace(&SUPERBOOMWALLS_DATA, data);
+    Ok(())
 }
 
 /// Get superboomwalls patterns for a specific room
 pub fn get_room_superboomwalls(shape: &str, room_name: &str) -> Option<Vec<SuperboomWallPattern>> {
-    let file = load_superboomwalls_data();
+    let file = SUPERBOOMWALLS_DATA.read().unwrap();
     
     // Helper function to find entry by shape and room name
     fn find_entry<'a>(file: &'a SuperboomWallsFile, key: &str, _want_norm: &str) -> Option<&'a RoomSuperboomWallsEntry> {
@@ -0,0 +1,94 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::room::room_data::RoomType;
+
+// Hypixel style score: skill, exploration and speed are out of 100 each, bonus comes from crypts and the mimic.
+//
+// Rooms don't have mobs to kill, so a room counts as cleared once it has been entered,
+// except for blood which is cleared once every mob the Watcher spawned is dead and puzzles which have to be solved.
+
+const MAX_CRYPT_BONUS: usize = 5;
+const MIMIC_BONUS: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DungeonScore {
+    pub skill: u32,
+    pub exploration: u32,
+    pub speed: u32,
+    pub bonus: u32,
+}
+
+impl DungeonScore {
+    pub fn calculate(dungeon: &Dungeon, current_ticks: u64) -> DungeonScore {
+        let cleared = dungeon.cleared_fraction();
+
+        let incomplete_puzzles = dungeon.rooms.iter()
+            .enumerate()
+            .filter(|(index, room)| room.room_data.room_type == RoomType::Puzzle && !dungeon.is_room_cleared(*index))
+            .count() as i64;
+        let skill = (20.0 + 80.0 * cleared).floor() as i64 - incomplete_puzzles * 10 - dungeon.deaths as i64 * 2;
+
+        let (found_secrets, total_secrets) = dungeon.secrets();
+        let needed_secrets = total_secrets as f64 * dungeon.floor.secrets_required;
+        let secrets = if needed_secrets > 0.0 { (found_secrets as f64 / needed_secrets).min(1.0) } else { 1.0 };
+        let exploration = (60.0 * cleared).floor() + (40.0 * secrets).floor();
+
+        let bonus = dungeon.crypts_exploded().min(MAX_CRYPT_BONUS) as u32 + if dungeon.mimic_killed { MIMIC_BONUS } else { 0 };
+
+        DungeonScore {
+            skill: skill.clamp(20, 100) as u32,
+            exploration: exploration as u32,
+            speed: speed_score(current_ticks / 20, dungeon.floor.speed_limit),
+            bonus,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.skill + self.exploration + self.speed + self.bonus
+    }
+
+    pub fn rank(&self) -> &'static str {
+        match self.total() {
+            300.. => "§6S+",
+            270.. => "§eS",
+            230.. => "§5A",
+            160.. => "§aB",
+            100.. => "§9C",
+            _ => "§cD",
+        }
+    }
+}
+
+/// Full points up to the time limit, then drops off slower the further over the limit the run is.
+fn speed_score(seconds: u64, limit: u64) -> u32 {
+    if seconds <= limit {
+        return 100;
+    }
+    let over = (seconds - limit) as f64 / limit as f64 * 100.0;
+    let score = if over < 20.0 {
+        100.0 - over / 2.0
+    } else if over < 40.0 {
+        90.0 - (over - 20.0) / 4.0
+    } else if over < 50.0 {
+        85.0 - (over - 40.0) / 5.0
+    } else if over < 60.0 {
+        83.0 - (over - 50.0) / 6.0
+    } else {
+        81.33 - (over - 60.0) / 7.0
+    };
+    score.max(0.0) as u32
+}
+
+/// Run time like 05m12s, the same way the sidebar shows it.
+pub fn format_run_time(ticks: u64) -> String {
+    let seconds = ticks / 20;
+    if seconds >= 60 {
+        format!("{:02}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{:02}s", seconds)
+    }
+}
+
+/// Split time like 12.35s.
+pub fn format_ticks(ticks: u64) -> String {
+    format!("{:.2}s", ticks as f64 / 20.0)
+}
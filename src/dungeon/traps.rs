@@ -0,0 +1,334 @@
+use crate::dungeon::room::locked_chests::facing_string_to_direction;
+use crate::dungeon::room::room::Room;
+use crate::net::packets::packet_buffer::PacketBuffer;
+use crate::net::protocol::play::clientbound::SoundEffect;
+use crate::server::block::block_interact_action::BlockInteractAction;
+use crate::server::block::block_position::BlockPos;
+use crate::server::block::blocks::Blocks;
+use crate::server::block::rotatable::Rotatable;
+use crate::server::entity::entity::{Entity, EntityImpl};
+use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
+use crate::server::player::player::Player;
+use crate::server::utils::aabb::AABB;
+use crate::server::utils::direction::Direction;
+use crate::server::utils::dvec3::DVec3;
+use crate::server::utils::sounds::Sounds;
+use crate::server::world::World;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Trap room hazards, other than the crushers.
+//
+// They are listed under "hazards" in the room json (next to "crushers"), positions are relative to the room corner with absolute y:
+// { "type": "arrow_dispenser", "position": [x, y, z], "facing": "north", "interval": 20, "damage": 10 }
+// { "type": "fall_floor", "from": [x, y, z], "to": [x, y, z], "delay": 10, "respawn": 100 }
+// { "type": "lava", "from": [x, y, z], "to": [x, y, z], "damage": 5 }
+// { "type": "door", "lever": [x, y, z], "from": [x, y, z], "to": [x, y, z] }
+
+/// Players further than this from a dispenser don't make it shoot.
+const DISPENSER_RANGE: f64 = 16.0;
+/// Blocks an arrow moves per tick.
+const ARROW_SPEED: f64 = 1.5;
+/// Hits are checked at this many points along each tick's movement (0.5 blocks apart),
+/// so an arrow can't skip over a player or a wall.
+const ARROW_HIT_STEPS: u32 = 3;
+const ARROW_LIFETIME: u32 = 40;
+/// Ticks between lava hurting the players in it.
+const LAVA_INTERVAL: u64 = 10;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TrapHazardData {
+    ArrowDispenser {
+        position: [i32; 3],
+        facing: String,
+        interval: u64,
+        damage: i32,
+    },
+    /// Floor blocks that drop `delay` ticks after being stepped on and come back `respawn` ticks later.
+    FallFloor {
+        from: [i32; 3],
+        to: [i32; 3],
+        delay: u64,
+        respawn: u64,
+    },
+    Lava {
+        from: [i32; 3],
+        to: [i32; 3],
+        damage: i32,
+    },
+    /// Blocks that are removed for good once the lever is pulled.
+    Door {
+        lever: [i32; 3],
+        from: [i32; 3],
+        to: [i32; 3],
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FloorState {
+    Standing,
+    FallingAt(u64),
+    Fallen { block: Blocks, restore_at: u64 },
+}
+
+#[derive(Debug)]
+pub enum TrapHazard {
+    ArrowDispenser {
+        position: BlockPos,
+        direction: Direction,
+        interval: u64,
+        damage: i32,
+    },
+    FallFloor {
+        blocks: HashMap<BlockPos, FloorState>,
+        delay: u64,
+        respawn: u64,
+    },
+    Lava {
+        blocks: Vec<BlockPos>,
+        area: AABB,
+        damage: i32,
+    },
+    Door {
+        lever: BlockPos,
+        blocks: Vec<BlockPos>,
+        open: bool,
+    },
+}
+
+impl TrapHazard {
+    /// Turns the json data into world positions for the room.
+    pub fn new(data: &TrapHazardData, room: &Room) -> TrapHazard {
+        let world_pos = |[x, y, z]: [i32; 3]| room.get_world_block_pos(&BlockPos { x, y, z });
+        let region = |from: [i32; 3], to: [i32; 3]| {
+            let (from, to) = (world_pos(from), world_pos(to));
+            let mut blocks = Vec::new();
+            for x in from.x.min(to.x)..=from.x.max(to.x) {
+                for y in from.y.min(to.y)..=from.y.max(to.y) {
+                    for z in from.z.min(to.z)..=from.z.max(to.z) {
+                        blocks.push(BlockPos { x, y, z });
+                    }
+                }
+            }
+            blocks
+        };
+
+        match data {
+            TrapHazardData::ArrowDispenser { position, facing, interval, damage } => TrapHazard::ArrowDispenser {
+                position: world_pos(*position),
+                direction: facing_string_to_direction(facing).rotate(room.rotation),
+                interval: (*interval).max(1),
+                damage: *damage,
+            },
+            TrapHazardData::FallFloor { from, to, delay, respawn } => TrapHazard::FallFloor {
+                blocks: region(*from, *to).into_iter().map(|pos| (pos, FloorState::Standing)).collect(),
+                delay: *delay,
+                respawn: *respawn,
+            },
+            TrapHazardData::Lava { from, to, damage } => {
+                let blocks = region(*from, *to);
+                let (from, to) = (world_pos(*from), world_pos(*to));
+                let area = AABB::new(
+                    DVec3::new(from.x.min(to.x) as f64, from.y.min(to.y) as f64, from.z.min(to.z) as f64),
+                    DVec3::new(from.x.max(to.x) as f64 + 1.0, from.y.max(to.y) as f64 + 1.0, from.z.max(to.z) as f64 + 1.0),
+                );
+                TrapHazard::Lava { blocks, area, damage: *damage }
+            }
+            TrapHazardData::Door { lever, from, to } => TrapHazard::Door {
+                lever: world_pos(*lever),
+                blocks: region(*from, *to),
+                open: false,
+            },
+        }
+    }
+
+    /// Places the blocks the hazard needs, runs after the room's own blocks have been placed.
+    pub fn load(&mut self, world: &mut World) {
+        match self {
+            TrapHazard::ArrowDispenser { position, direction, .. } => {
+                world.set_block_at(Blocks::Dispenser { direction: *direction, triggered: false }, position.x, position.y, position.z);
+            }
+            TrapHazard::Lava { blocks, .. } => {
+                for pos in blocks.iter() {
+                    world.set_block_at(Blocks::Lava { level: 0 }, pos.x, pos.y, pos.z);
+                }
+            }
+            TrapHazard::Door { lever, .. } => {
+                world.interactable_blocks.insert(*lever, BlockInteractAction::Lever);
+            }
+            TrapHazard::FallFloor { .. } => {}
+        }
+    }
+
+    pub fn tick(&mut self, world: &mut World) {
+        let tick = world.tick_count;
+        match self {
+            TrapHazard::ArrowDispenser { position, direction, interval, damage } => {
+                if !tick.is_multiple_of(*interval) {
+                    return;
+                }
+                let center = DVec3::from_centered(position);
+                if !world.players.values().any(|player| player.position.distance_to(&center) <= DISPENSER_RANGE) {
+                    return;
+                }
+
+                let (dx, _, dz) = direction.get_offset();
+                let velocity = DVec3::new(dx as f64 * ARROW_SPEED, 0.0, dz as f64 * ARROW_SPEED);
+                let spawn_at = center + DVec3::new(dx as f64 * 0.7, -0.2, dz as f64 * 0.7);
+                if let Err(e) = world.spawn_entity(spawn_at, EntityMetadata::new(EntityVariant::Arrow), TrapArrowImpl { velocity, damage: *damage }) {
+                    eprintln!("Failed to shoot a trap arrow: {}", e);
+                    return;
+                }
+                for player in world.players.values_mut() {
+                    player.write_packet(&SoundEffect {
+                        sound: Sounds::Bow.id(),
+                        volume: 1.0,
+                        pitch: 1.2,
+                        pos_x: center.x,
+                        pos_y: center.y,
+                        pos_z: center.z,
+                    });
+                }
+            }
+
+            TrapHazard::FallFloor { blocks, delay, respawn } => {
+                for player in world.players.values() {
+                    let under = BlockPos {
+                        x: player.position.x.floor() as i32,
+                        y: (player.position.y - 0.1).floor() as i32,
+                        z: player.position.z.floor() as i32,
+                    };
+                    if let Some(state @ FloorState::Standing) = blocks.get_mut(&under) {
+                        *state = FloorState::FallingAt(tick + *delay);
+                    }
+                }
+
+                for (pos, state) in blocks.iter_mut() {
+                    match *state {
+                        FloorState::FallingAt(at) if tick >= at => {
+                            let block = world.get_block_at(pos.x, pos.y, pos.z);
+                            world.set_block_at(Blocks::Air, pos.x, pos.y, pos.z);
+                            *state = FloorState::Fallen { block, restore_at: tick + *respawn };
+                        }
+                        FloorState::Fallen { block, restore_at } if tick >= restore_at => {
+                            world.set_block_at(block, pos.x, pos.y, pos.z);
+                            *state = FloorState::Standing;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            TrapHazard::Lava { area, damage, .. } => {
+                if !tick.is_multiple_of(LAVA_INTERVAL) {
+                    return;
+                }
+                for player in world.players.values_mut() {
+                    if player.collision_aabb().intersects(area) {
+                        damage_player(player, *damage, "lava");
+                    }
+                }
+            }
+
+            TrapHazard::Door { .. } => {}
+        }
+    }
+
+    /// Opens the door if `block_pos` is its lever, returns false if it isn't.
+    pub fn pull_lever(&mut self, world: &mut World, block_pos: &BlockPos) -> bool {
+        let TrapHazard::Door { lever, blocks, open } = self else {
+            return false;
+        };
+        if lever != block_pos {
+            return false;
+        }
+        if *open {
+            return true;
+        }
+        *open = true;
+
+        if let Blocks::Lever { orientation, .. } = world.get_block_at(lever.x, lever.y, lever.z) {
+            world.set_block_at(Blocks::Lever { orientation, powered: true }, lever.x, lever.y, lever.z);
+        }
+        for pos in blocks.iter() {
+            world.set_block_at(Blocks::Air, pos.x, pos.y, pos.z);
+        }
+
+        for player in world.players.values_mut() {
+            player.write_packet(&SoundEffect {
+                sound: Sounds::PistonIn.id(),
+                volume: 1.0,
+                pitch: 0.8,
+                pos_x: lever.x as f64 + 0.5,
+                pos_y: lever.y as f64 + 0.5,
+                pos_z: lever.z as f64 + 0.5,
+            });
+        }
+        true
+    }
+}
+
+/// Takes health off a player, reduced by their defense like the rest of skyblock.
+/// Running out of health counts as a death for the run and puts them back at full health.
+pub fn damage_player(player: &mut Player, damage: i32, cause: &'static str) {
+    let stats = &mut player.dungeon_stats;
+    let taken = damage * 100 / (100 + stats.defense.max(0));
+    stats.hp -= taken.max(1);
+    if stats.hp > 0 {
+        return;
+    }
+    stats.hp = stats.max_hp;
+
+    let server = player.server_mut();
+    server.dungeon.deaths += 1;
+    // the players are usually being iterated over when this happens, so the message goes out afterwards
+    let message = format!("§c ☠ §7{} was killed by {} and became a ghost§7.", player.profile.username, cause);
+    server.schedule(0, move |server| {
+        for player in server.world.players.values_mut() {
+            player.send_message(&message);
+        }
+    });
+}
+
+/// An arrow shot by a trap dispenser, flies straight until it hits a block or a player.
+pub struct TrapArrowImpl {
+    velocity: DVec3,
+    damage: i32,
+}
+
+impl EntityImpl for TrapArrowImpl {
+    fn spawn(&mut self, entity: &mut Entity, _: &mut PacketBuffer) {
+        entity.yaw = (-self.velocity.x.atan2(self.velocity.z).to_degrees()) as f32;
+    }
+
+    fn tick(&mut self, entity: &mut Entity, _: &mut PacketBuffer) {
+        let world = entity.world_mut();
+        let step = DVec3::new(
+            self.velocity.x / ARROW_HIT_STEPS as f64,
+            self.velocity.y / ARROW_HIT_STEPS as f64,
+            self.velocity.z / ARROW_HIT_STEPS as f64,
+        );
+
+        for _ in 0..ARROW_HIT_STEPS {
+            entity.position = entity.position + step;
+
+            let pos = entity.position;
+            let hit_box = AABB::new(pos - DVec3::new(0.25, 0.25, 0.25), pos + DVec3::new(0.25, 0.25, 0.25));
+            if let Some(player) = world.players.values_mut().find(|player| player.collision_aabb().intersects(&hit_box)) {
+                damage_player(player, self.damage, "an arrow");
+                world.despawn_entity(entity.id);
+                return;
+            }
+
+            if world.get_block_at(pos.x.floor() as i32, pos.y.floor() as i32, pos.z.floor() as i32) != Blocks::Air {
+                world.despawn_entity(entity.id);
+                return;
+            }
+        }
+
+        if entity.ticks_existed > ARROW_LIFETIME {
+            world.despawn_entity(entity.id);
+        }
+    }
+}
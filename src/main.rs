@@ -3,54 +3,33 @@ mod net;
 mod server;
 mod utils;
 
-use crate::dungeon::door::DoorType;
 use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_seed::DungeonSeed;
 use crate::dungeon::dungeon_state::DungeonState;
-use crate::dungeon::room::room_data::{RoomData, RoomType};
+use crate::dungeon::floor_profile::FloorProfile;
+use crate::dungeon::room::room_data_loader::{load_room_data, room_data_dir, set_room_data_dir};
+use crate::dungeon::room::room_pool::RoomPool;
 // use crate::dungeon::room::room::Room;
 use crate::net::internal_packets::{MainThreadMessage, NetworkThreadMessage};
 use crate::net::packets::packet_buffer::PacketBuffer;
 use crate::net::protocol::play::clientbound;
-use crate::net::protocol::play::clientbound::{AddEffect, PlayerListItem, Teams};
-use crate::net::protocol::play::serverbound::EntityInteractionType;
+use crate::net::protocol::play::clientbound::AddEffect;
 use crate::net::run_network::run_network_thread;
 use crate::net::var_int::VarInt;
-use crate::server::block::block_position::BlockPos;
-use crate::server::block::blocks::Blocks;
-use crate::server::block::rotatable::Rotatable;
 use crate::server::chunk::chunk::Chunk;
 use crate::server::chunk::chunk_grid::ChunkDiff;
-use crate::server::entity::entity::{Entity, EntityImpl, NoEntityImpl};
-use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
-use crate::server::entity::spawn_equipped::spawn_following_nametag;
-// use crate::server::lava_boost::apply_lava_boost;
-use crate::server::player::container_ui::UI;
-use crate::server::player::player::{Player, GameProfile, GameProfileProperty};
-use crate::server::utils::player_list::player_profile::{PlayerData, GameType};
-use crate::server::player::scoreboard::{ScoreboardLines, CREATE_TEAM, ADD_PLAYER};
+use crate::server::lava_boost::apply_lava_boost;
+use crate::server::player::scoreboard::ScoreboardLines;
 use crate::server::server::Server;
 use crate::server::utils::chat_component::chat_component_text::ChatComponentTextBuilder;
 use crate::server::utils::color::MCColors;
-use crate::server::utils::dvec3::DVec3;
-use crate::server::utils::sized_string::SizedString;
 use crate::server::world::VIEW_DISTANCE;
-use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
-use crate::utils::seeded_rng::SeededRng;
 use anyhow::Result;
 use chrono::Local;
-use include_dir::include_dir;
 use indoc::formatdoc;
-use rand::seq::IndexedRandom;
-use std::collections::HashMap;
 use std::env;
-use std::ops::Add;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc::unbounded_channel;
-use uuid::Uuid;
-
-// Mort skin constants
-const MORT_SKIN_VALUE: &str = "ewogICJ0aW1lc3RhbXAiIDogMTYyMDcyNTkwMDEzOSwKICAicHJvZmlsZUlkIiA6ICJhNzdkNmQ2YmFjOWE0NzY3YTFhNzU1NjYxOTllYmY5MiIsCiAgInByb2ZpbGVOYW1lIiA6ICIwOEJFRDUiLAogICJzaWduYXR1cmVSZXF1aXJlZCIgOiB0cnVlLAogICJ0ZXh0dXJlcyIgOiB7CiAgICAiU0tJTiIgOiB7CiAgICAgICJ1cmwiIDogImh0dHA6Ly90ZXh0dXJlcy5taW5lY3JhZnQubmV0L3RleHR1cmUvOWI1Njg5NWI5NjU5ODk2YWQ2NDdmNTg1OTkyMzhhZjUzMmQ0NmRiOWMxYjAzODliOGJiZWI3MDk5OWRhYjMzZCIKICAgIH0KICB9Cn0=";
-const MORT_SKIN_SIGNATURE: &str = "ihevlFAZ1u+xG/eeEnUzMRu1l8i+2j6pw1jIw0yxcsLn1x749GL+ToaVRyU56+13vDg9G6QjWRHQaA1DpPIkgmthhZsxQ067Q2A2SASywQiQIvIPJwmzjRmkP3eYHtKnJ7t4uZ31qjMazaONNq00Nq2t8s983u2TPfCFZJQlx8RqNWjRZjmGh7Gw+YXKbecwnlQmvpKZSiPolCcTgobPl0aZCr+benffxA0bcAohkr5Kp8U2VZW73wF0P7FGkANIhLYOtokLTemaYOMPWe4q/SU3D5yZswM6/SQ63g0mAvZJfQW/Vb+lAGzlm3zXia7T6tAJjFYuV1kg5yVcODbYOb2fgLJK3OQvUjnf9xlXXyDcESOILsPhft5SYVbBQuDkuLitG7YecJMV9cbCqldnvv4Z4XKs3jaCzZqYDRql4MVx8rYd+7hLaGXuprfrwBYL1xzzgMFSTFUCkIm942L5B7/6tZJGT5GT7g4DN1vrJpnZz4+gxdebcbcUEfP313/gHFU/U3phfN89TBbbNAfi0t5uQ5SRCGXdCz+YbO56zTKjzeUg57u49XOZaKwNZyF6hmv2IdO9CJctYw9cvljEkALOkMjMShaP95QYHsahc3mFLavJbseY7x5/vlexjRvPxdnxQCDG+Fkf9eBwUjyCqUjQozYYM6euDHFqib7uBHM=";
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -59,134 +38,90 @@ async fn main() -> Result<()> {
 
     let args: Vec<String> = env::args().collect();
 
-    let rooms_dir = include_dir!("src/room_data/");
-
-    // Load secrets from bettermapRooms.json
-    let bettermap_rooms_json = include_str!("room_data/bettermapRooms.json");
-    let bettermap_rooms: serde_json::Value = serde_json::from_str(bettermap_rooms_json).unwrap();
-    let mut secrets_map: std::collections::HashMap<String, u8> = std::collections::HashMap::new();
-    
-    if let Some(rooms_array) = bettermap_rooms.as_array() {
-        for room in rooms_array {
-            if let Some(name) = room.get("name").and_then(|n| n.as_str()) {
-                if let Some(secrets) = room.get("secrets")
-                    .and_then(|s| s.as_number())
-                    .and_then(|n| n.as_u64())
-                    .map(|n| n as u8) {
-                    secrets_map.insert(name.to_string(), secrets);
-                }
+    // --room-data <dir> overrides where the room data is read from,
+    // --auto-reroll <seconds> starts a new dungeon that long after a run is finished.
+    // --pool "<instruction>" configures which rooms get picked, see room_pool.rs (can be given multiple times).
+    // --floor <F1-F7 or M1-M7> picks the floor to generate, seeds already contain their floor.
+    // the first other argument is the dungeon seed
+    let mut positional_args = Vec::new();
+    let mut auto_reroll = None;
+    let mut floor = FloorProfile::default();
+    let mut pool_instructions = Vec::new();
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        if arg == "--room-data" {
+            match args_iter.next() {
+                Some(dir) => set_room_data_dir(dir),
+                None => eprintln!("--room-data needs a directory"),
             }
-        }
-    }
-
-    // roomdata first digit (the key) is just a list of numbers 0..etc. this could just be a vec with roomid lookups.
-    let room_data_storage: DeterministicHashMap<usize, RoomData> = rooms_dir
-        .entries()
-        .iter()
-        .filter_map(|file| {
-            let file = file.as_file()?;
-            let file_name = file.path().file_name()?.to_str()?;
-            
-            // Skip bettermapRooms.json - it's not a room file
-            if file_name == "bettermapRooms.json" {
-                return None;
+        } else if arg == "--pool" {
+            match args_iter.next() {
+                Some(instruction) => pool_instructions.push(instruction),
+                None => eprintln!("--pool needs an instruction, like \"include Mossy\""),
             }
-            
-            let contents = file.contents_utf8()?;
-            let mut room_data = RoomData::from_raw_json(contents);
-
-            // Override secrets from bettermapRooms.json if available
-            if let Some(secrets) = secrets_map.get(&room_data.name) {
-                room_data.secrets = *secrets;
+        } else if arg == "--floor" {
+            match args_iter.next().and_then(|name| FloorProfile::from_name(name)) {
+                Some(profile) => floor = profile,
+                None => eprintln!("--floor needs a floor, like F7 or M3"),
             }
-
-            // Override specific room secrets
-            if room_data.name == "Golden Oasis" {
-                room_data.secrets = 3;
+        } else if arg == "--auto-reroll" {
+            match args_iter.next().map(|seconds| seconds.parse::<u32>()) {
+                Some(Ok(seconds)) => auto_reroll = Some(seconds * 20),
+                _ => eprintln!("--auto-reroll needs the amount of seconds to wait after a run is finished"),
             }
+        } else {
+            positional_args.push(arg);
+        }
+    }
 
-            let name_parts: Vec<&str> = file_name.split(",").collect();
-            let room_id = name_parts.first()?.parse::<usize>().ok()?;
+    let room_data = load_room_data();
+    for error in room_data.errors.iter() {
+        eprintln!("Room data error: {:#}", error);
+    }
+    println!("Loaded {} rooms from {}", room_data.storage.len(), room_data_dir().display());
+    let room_data_storage = room_data.storage;
+
+    let mut room_pool = RoomPool::default();
+    for instruction in pool_instructions {
+        let args = instruction.split_whitespace().collect::<Vec<&str>>();
+        match room_pool.apply(&args, &room_data_storage) {
+            Ok(message) => println!("{}", message),
+            Err(error) => eprintln!("Invalid --pool \"{}\": {}", instruction, error),
+        }
+    }
 
-            Some((room_id, room_data))
-        })
-        .collect();
+    // Check if a custom dungeon seed or legacy dungeon str has been given via cli args, otherwise generate one
 
-    // Load lever data - using include_str for now since the directory name has spaces
-    let _lever_json_data = include_str!("room_data/lever shi/lever.json");
+    // let dungeon_str = "080809010400100211121300101415161304171418161300191403161304191905160600919999113099910991099909090099999919990929999999099999999009";
 
-    // Might be a good idea to make a new format for storing doors so that indexes etc don't need to be hard coded.
-    // But this works for now...
-    let door_data: Vec<Vec<Blocks>> = include_str!("door_data/doors.txt")
-        .split("\n")
-        .map(|line| {
-            let mut blocks: Vec<Blocks> = Vec::new();
+    let custom_dungeon = positional_args.first().map(|arg| {
+        DungeonSeed::parse(arg, floor).and_then(|seed| Dungeon::from_seed(&seed, &room_data_storage, &room_pool).map(|dungeon| (seed, dungeon)))
+    });
 
-            for i in (0..line.len() - 1).step_by(4) {
-                if let Some(substr) = line.get(i..i + 4) {
-                    if let Ok(state) = u16::from_str_radix(substr, 16) {
-                        blocks.push(Blocks::from(state));
-                    }
-                }
+    // A bad seed shouldn't stop the server from starting, just fall back to a generated dungeon
+    let (mut dungeon_seed, dungeon) = match custom_dungeon {
+        Some(Ok(custom_dungeon)) => custom_dungeon,
+        result => {
+            if let Some(Err(error)) = result {
+                eprintln!("Failed to load dungeon from args, generating one instead: {}", error);
             }
-
-            blocks
-        })
-        .collect();
-
-    let door_type_blocks: HashMap<DoorType, Vec<Vec<Blocks>>> = HashMap::from_iter(
-        vec![
-            (DoorType::BLOOD, vec![door_data[0].clone()]),
-            (DoorType::ENTRANCE, vec![door_data[1].clone()]),
-            (DoorType::WITHER, vec![
-                door_data[2].clone(),
-                door_data[3].clone(),
-                door_data[4].clone(),
-            ]),
-            (
-                DoorType::NORMAL,
-                vec![
-                    door_data[1].clone(),
-                    door_data[2].clone(),
-                    door_data[3].clone(),
-                    door_data[4].clone(),
-                    door_data[5].clone(),
-                    door_data[6].clone(),
-                    door_data[7].clone(),
-                ],
-            ),
-        ]
-        .into_iter(),
-    );
-
-    let dungeon_strings = include_str!("dungeon_storage/dungeons.txt")
-        .split("\n")
-        .collect::<Vec<&str>>();
-
-    // Check if a custom dungeon str has been given via cli args
-
-    // let dungeon_str = "080809010400100211121300101415161304171418161300191403161304191905160600919999113099910991099909090099999919990929999999099999999009";
-
-    let dungeon_str = match args.len() {
-        0..=1 => {
-            let mut rng = rand::rng();
-            dungeon_strings.choose(&mut rng).unwrap_or(&"080809010400100211121300101415161304171418161300191403161304191905160600919999113099910991099909090099999919990929999999099999999009")
+            let seed = DungeonSeed::random(&floor, &room_pool, &room_data_storage);
+            let dungeon = Dungeon::from_seed(&seed, &room_data_storage, &room_pool)?;
+            (seed, dungeon)
         }
-        _ => args.get(1).map(|s| s.as_str()).unwrap_or("080809010400100211121300101415161304171418161300191403161304191905160600919999113099910991099909090099999919990929999999099999999009"),
     };
-    println!("Dungeon String: {}", dungeon_str);
-
-    let rng_seed: u64 = rand::random(); // using a second seed for rng enables the same layout to have randomized rooms. Maybe should be included in the dungeon seed string?
-    // let rng_seed: u64 = 12946977352813673410;
-
-    println!("Rng Seed: {}", rng_seed);
-    SeededRng::set_seed(rng_seed);
-
-    let dungeon = Dungeon::from_str(dungeon_str, &room_data_storage)?;
+    if !room_pool.is_empty() {
+        dungeon_seed.pin_rooms(&dungeon, &room_data_storage);
+    }
+    println!("Floor: {}", dungeon_seed.floor.name);
+    println!("Dungeon String: {}", dungeon_seed.layout);
+    println!("Rng Seed: {}", dungeon_seed.rng_seed);
+    println!("Dungeon Seed: {}", dungeon_seed.encode());
     
-    let mut server = Server::initialize_with_dungeon(network_tx, dungeon);
+    let mut server = Server::initialize_with_dungeon(network_tx, dungeon, dungeon_seed, room_data_storage, room_pool);
     server.world.server = &mut server;
     server.dungeon.server = &mut server;
+    server.auto_reroll = auto_reroll;
 
     let mut tick_interval = tokio::time::interval(Duration::from_millis(50));
     tokio::spawn(run_network_thread(
@@ -195,349 +130,7 @@ async fn main() -> Result<()> {
         main_tx,
     ));
 
-    // Load the bossroom at fixed coordinates first
-    // {
-    //     // Load the bossroom JSON data
-    //     let bossroom_json = include_str!("room_data/146,bossroom,-8,-8.json");
-    //     let bossroom_data = RoomData::from_raw_json(bossroom_json);
-    //     
-    //     // Extract dimensions before using bossroom_data
-    //     let bossroom_width = bossroom_data.width;
-    //     let bossroom_length = bossroom_data.length;
-    //     let bossroom_height = bossroom_data.height;
-    //     
-    //     // Store boss room dimensions in dungeon for detection
-    //     server.dungeon.boss_room_width = bossroom_width;
-    //     server.dungeon.boss_room_length = bossroom_length;
-    //     server.dungeon.boss_room_height = bossroom_height;
-    //     
-    //     // Create a single segment for the bossroom at the specified coordinates
-    //     let bossroom_segments = vec![crate::dungeon::room::room::RoomSegment {
-    //         x: 0, // This will be overridden by our custom positioning
-    //         z: 0, // This will be overridden by our custom positioning
-    //         neighbours: [None; 4],
-    //     }];
-    //     
-    //     // Create the bossroom with North rotation (no rotation)
-    //     let bossroom = Room::new(
-    //         bossroom_segments,
-    //         &[], // empty doors array
-    //         bossroom_data,
-    //     );
-    //     
-    //     // Override the corner position to spawn at -8, 254, -8
-    //     // We need to manually load the bossroom since it's not part of the regular dungeon grid
-    //     let corner = BlockPos { x: -8, y: 254, z: -8 };
-    //     
-    //     // Update the dungeon's boss room corner to match the actual loaded position
-    //     server.dungeon.boss_room_corner = corner;
-    //     
-    //     // Manually load the bossroom blocks at the specified position
-    //     for (i, block) in bossroom.room_data.block_data.iter().enumerate() {
-    //         if *block == Blocks::Air {
-    //             continue;
-    //         }
-    //         
-    //         let block = block.clone();
-    //         // No rotation needed since we're placing it directly
-    //         
-    //         let ind = i as i32;
-    //         let x = ind % bossroom.room_data.width;
-    //         let z = (ind / bossroom.room_data.width) % bossroom.room_data.length;
-    //         let y = bossroom.room_data.bottom + ind / (bossroom.room_data.width * bossroom.room_data.length);
-    //         
-    //         // Place the block at the world position
-    //         server.world.set_block_at(block, corner.x + x, y, corner.z + z);
-    //     }
-    //     
-    //     println!("Bossroom loaded at coordinates: x={}, y={}, z={} with dimensions: {}x{}x{}", 
-    //         corner.x, corner.y, corner.z, 
-    //         server.dungeon.boss_room_width, 
-    //         server.dungeon.boss_room_length, 
-    //         server.dungeon.boss_room_height);
-    //     
-    //     // Bossroom chunks will be sent to players individually when they get near it
-    //     // No need to send to all players immediately - this saves bandwidth and memory
-    //     let bossroom_chunk_x_min = corner.x >> 4;
-    //     let bossroom_chunk_z_min = corner.z >> 4;
-    //     let bossroom_chunk_x_max = (corner.x + bossroom.room_data.width) >> 4;
-    //     let bossroom_chunk_z_max = (corner.z + bossroom.room_data.length) >> 4;
-    //     
-    //     let total_chunks = (bossroom_chunk_x_max - bossroom_chunk_x_min + 1) * (bossroom_chunk_z_max - bossroom_chunk_z_min + 1);
-    //     println!("Bossroom loaded with {} chunks ({}x{}), will be sent to players when they get near", 
-    //         total_chunks, bossroom_chunk_x_max - bossroom_chunk_x_min + 1, bossroom_chunk_z_max - bossroom_chunk_z_min + 1);
-    // }
-
-    let dungeon = &mut server.dungeon;
-    
-    for room in &mut dungeon.rooms {
-        // println!("Room: {:?} type={:?} rotation={:?} shape={:?} corner={:?}", room.segments, room.room_data.room_type, room.rotation, room.room_data.shape, room.get_corner_pos());
-        room.load_into_world(&mut server.world);
-
-        // Immediately scan crypts on world load for debug visibility
-        if room.crypt_patterns.len() > 0 && !room.crypts_checked {
-            let count = room.detect_crypts(&server.world);
-            if count == 0 {
-                room.debug_crypt_mismatch(&server.world);
-            }
-        }
-
-        
-        // Set the spawn point to be inside of the spawn room
-        if room.room_data.room_type == RoomType::Entrance {
-            server.world.set_spawn_point(
-                room.get_world_block_pos(&BlockPos {
-                    x: 15,
-                    y: 72,
-                    z: 18,
-                })
-                .as_dvec3()
-                .add_x(0.5)
-                .add_z(0.5),
-                180.0.rotate(room.rotation),
-                0.0,
-            );
-
-            // test
-            pub struct MortImpl;
-            
-            impl EntityImpl for MortImpl {
-                fn spawn(&mut self, entity: &mut Entity, buffer: &mut PacketBuffer) {
-                    // Add Mort to player list so the player entity can be spawned properly
-                    if let Some(uuid) = entity.uuid {
-                        let mort_profile = GameProfile {
-                            uuid,
-                            username: "Mort".to_string(),
-                            properties: HashMap::from([
-                                ("textures".to_string(), GameProfileProperty {
-                                    value: MORT_SKIN_VALUE.to_string(),
-                                    signature: Some(MORT_SKIN_SIGNATURE.to_string()),
-                                })
-                            ]),
-                        };
-                        
-                        let player_data = PlayerData {
-                            ping: 20, // More realistic ping for NPCs
-                            game_mode: GameType::Survival, // Use Survival instead of Creative for better modded client compatibility
-                            profile: mort_profile,
-                            display_name: Some(ChatComponentTextBuilder::new("Mort").build()),
-                        };
-                        
-                        // Send PlayerInfo Add packet
-                        buffer.write_packet(&PlayerListItem {
-                            action: VarInt(0), // ADD_PLAYER
-                            players: vec![&player_data],
-                        });
-                        
-                        // First create the hidden team to prevent vanilla nameplate from showing
-                        buffer.write_packet(&Teams {
-                            name: SizedString::truncated("npc_hide"),
-                            display_name: SizedString::truncated("npc_hide"),
-                            prefix: SizedString::truncated(""),
-                            suffix: SizedString::truncated(""),
-                            name_tag_visibility: SizedString::truncated("never"),
-                            color: 0,
-                            players: vec![],
-                            action: CREATE_TEAM,
-                            friendly_flags: 0,
-                        });
-                        
-                        // Then add Mort to the hidden team
-                        buffer.write_packet(&Teams {
-                            name: SizedString::truncated("npc_hide"),
-                            display_name: SizedString::truncated("npc_hide"),
-                            prefix: SizedString::truncated(""),
-                            suffix: SizedString::truncated(""),
-                            name_tag_visibility: SizedString::truncated("never"),
-                            color: 0,
-                            players: vec![SizedString::truncated("Mort")],
-                            action: ADD_PLAYER,
-                            friendly_flags: 0,
-                        });
-                    }
-                }
-                
-                fn tick(&mut self, _: &mut Entity, _: &mut PacketBuffer) {
-                    // rotate
-                }
-                fn interact(&mut self, _: &mut Entity, player: &mut Player, action: &EntityInteractionType) {
-                    if action == &EntityInteractionType::InteractAt {
-                        return;
-                    }
-                    player.open_ui(UI::MortReadyUpMenu);
-                }
-            }
-            
-            // Generate deterministic UUID for Mort NPC using parse_str with a fixed UUID
-            let mort_uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(); // Mort NPC UUID
-            
-            let id = server.world.spawn_entity_with_uuid(
-                room.get_world_block_pos(&BlockPos { x: 15, y: 69, z: 4 })
-                    .as_dvec3()
-                    .add(DVec3::new(0.5, 0.0, 0.5)),
-                EntityMetadata::new(EntityVariant::Player),
-                MortImpl,
-                Some(mort_uuid),
-            )?;
-            if let Some((entity, _)) = server.world.entities.get_mut(&id) {
-                entity.yaw = 0.0.rotate(room.rotation);
-            }
-            
-            // Create two-line armorstand nametag for Mort
-            // Get Mort's position first
-            let mort_pos = if let Some((entity, _)) = server.world.entities.get(&id) {
-                entity.position
-            } else {
-                // Fallback to the original position if entity not found yet
-                room.get_world_block_pos(&BlockPos { x: 15, y: 69, z: 4 })
-                    .as_dvec3()
-                    .add(DVec3::new(0.5, 0.0, 0.5))
-            };
-            
-            // Try following nametags first
-            match spawn_following_nametag(&mut server.world, id, "§bMort", 0.4) {
-                Ok(_top_nametag_id) => {
-                    // Spawn bottom nametag
-                    match spawn_following_nametag(&mut server.world, id, "§eCLICK", 0.1) {
-                        Ok(_bottom_nametag_id) => {
-                            // Both nametags spawned successfully
-                        }
-                        Err(e) => {
-                            println!("Failed to spawn bottom nametag: {}", e);
-                            // Fallback to static armorstands
-                            let _bottom_nametag_id = server.world.spawn_entity(
-                                mort_pos + DVec3::new(0.0, 0.1, 0.0),
-                                {
-                                    let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
-                                    metadata.is_invisible = true;
-                                    metadata.custom_name = Some("§eCLICK".to_string());
-                                    metadata.custom_name_visible = true;
-                                    metadata.ai_disabled = true;
-                                    metadata
-                                },
-                                NoEntityImpl,
-                            )?;
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("Failed to spawn top nametag: {}", e);
-                    // Fallback to static armorstands
-                    let _top_nametag_id = server.world.spawn_entity(
-                        mort_pos + DVec3::new(0.0, 0.4, 0.0),
-                        {
-                            let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
-                            metadata.is_invisible = true;
-                            metadata.custom_name = Some("§bMort".to_string());
-                            metadata.custom_name_visible = true;
-                            metadata.ai_disabled = true;
-                            metadata
-                        },
-                        NoEntityImpl,
-                    )?;
-                    
-                    let _bottom_nametag_id = server.world.spawn_entity(
-                        mort_pos + DVec3::new(0.0, 0.1, 0.0),
-                        {
-                            let mut metadata = EntityMetadata::new(EntityVariant::ArmorStand);
-                            metadata.is_invisible = true;
-                            metadata.custom_name = Some("§eCLICK".to_string());
-                            metadata.custom_name_visible = true;
-                            metadata.ai_disabled = true;
-                            metadata
-                        },
-                        NoEntityImpl,
-                    )?;
-                }
-            }
-            
-            // Ensure entity is properly visible first, then add team hiding if needed
-            if let Some((_entity, _)) = server.world.entities.get(&id) {
-                // Player entity should be properly registered now
-                // For modded clients, we might want to ensure no immediate team modifications
-                // that could interfere with visibility
-            }
-        }
-    }
-    
-    // Spawn locked chests for all rooms
-    for room in &dungeon.rooms {
-        room.spawn_locked_chests(
-            &mut server.world,
-            &mut dungeon.locked_chests,
-            &mut dungeon.lever_to_chests,
-        );
-    }
-    
-    // Remove vines from specific rooms and add special blocks after all rooms are loaded
-    for room in &dungeon.rooms {
-        let corner = room.get_corner_pos();
-        
-        if room.room_data.name == "Rails" {
-            // Remove vines at 15 58 15, 15 57 15, 15 56 15, 15 55 15, 15 54 15 (no rotation)
-            let vines_to_remove = [
-                (15, 58, 15),
-                (15, 57, 15),
-                (15, 56, 15),
-                (15, 55, 15),
-                (15, 54, 15),
-            ];
-            
-            for (rel_x, rel_y, rel_z) in &vines_to_remove {
-                let vine_world_pos = BlockPos {
-                    x: corner.x + *rel_x,
-                    y: *rel_y,
-                    z: corner.z + *rel_z,
-                };
-                
-                // Remove the vine block
-                server.world.set_block_at(
-                    Blocks::Air,
-                    vine_world_pos.x,
-                    vine_world_pos.y,
-                    vine_world_pos.z
-                );
-            }
-        } else if room.room_data.name == "Flags" {
-            // Remove vine at 55 88 47 (no rotation)
-            let vine_world_pos = BlockPos {
-                x: corner.x + 55,
-                y: 88,
-                z: corner.z + 47,
-            };
-            
-            // Remove the vine block
-            server.world.set_block_at(
-                Blocks::Air,
-                vine_world_pos.x,
-                vine_world_pos.y,
-                vine_world_pos.z
-            );
-        } else if room.room_data.name == "Grand Library" {
-            // Spawn torch at 47 86 15 (no rotation, like vines)
-            let torch_world_pos = BlockPos {
-                x: corner.x + 47,
-                y: 86,
-                z: corner.z + 15,
-            };
-            
-            // Spawn the torch block (default direction is up - placed on top of block below)
-            server.world.set_block_at(
-                Blocks::Torch {
-                    direction: crate::server::block::block_parameter::TorchDirection::Up,
-                },
-                torch_world_pos.x,
-                torch_world_pos.y,
-                torch_world_pos.z
-            );
-        }
-    }
-    
-    // Lever system is now integrated into room generation (like crypts and superboom walls)
-
-    for door in &dungeon.doors {
-        door.load_into_world(&mut server.world, &door_type_blocks);
-    }
+    server.dungeon.load_into_world(&mut server.world)?;
 
     // let zombie_spawn_pos = DVec3 {
     //     x: 25.0,
@@ -604,6 +197,12 @@ async fn main() -> Result<()> {
         // also needs to actually be in a vanilla adjacent way.
         for player in server.world.players.values_mut() {
             player.ticks_existed += 1;
+
+            if let Some(terminal) = &mut player.current_terminal {
+                if terminal.tick() {
+                    player.sync_inventory();
+                }
+            }
             
             // Send action bar every 5 ticks
             if player.ticks_existed % 5 == 0 {
@@ -772,10 +371,10 @@ async fn main() -> Result<()> {
 
             // Track when player first enters the dungeon (when dungeon becomes Started)
             let dungeon_state = &server.dungeon.state;
-            if matches!(dungeon_state, DungeonState::Started { .. }) {
+            if matches!(dungeon_state, DungeonState::Started { .. } | DungeonState::Boss { .. } | DungeonState::Finished { .. }) {
                 if player.dungeon_entry_tick.is_none() {
                     // Player just entered the dungeon - record the current tick and hide scoreboard
-                    if let DungeonState::Started { current_ticks } = dungeon_state {
+                    if let DungeonState::Started { current_ticks } | DungeonState::Boss { current_ticks, .. } | DungeonState::Finished { current_ticks, .. } = dungeon_state {
                         player.dungeon_entry_tick = Some(*current_ticks);
                         // Hide the scoreboard when first entering
                         use crate::net::protocol::play::clientbound::DisplayScoreboard;
@@ -791,7 +390,7 @@ async fn main() -> Result<()> {
             }
             
             // Check if we should skip scoreboard (first 5 ticks after joining dungeon)
-            let skip_scoreboard = if let (Some(entry_tick), DungeonState::Started { current_ticks }) = 
+            let skip_scoreboard = if let (Some(entry_tick), DungeonState::Started { current_ticks } | DungeonState::Boss { current_ticks, .. } | DungeonState::Finished { current_ticks, .. }) = 
                 (player.dungeon_entry_tick, dungeon_state) {
                 let ticks_since_entry = current_ticks.saturating_sub(entry_tick);
                 ticks_since_entry < 5  // Skip for first 5 ticks (0-4)
@@ -809,7 +408,7 @@ async fn main() -> Result<()> {
                 });
             } else {
                 // Show scoreboard again after 5 ticks (only once when ticks_since_entry == 5)
-                if let (Some(entry_tick), DungeonState::Started { current_ticks }) = 
+                if let (Some(entry_tick), DungeonState::Started { current_ticks } | DungeonState::Boss { current_ticks, .. } | DungeonState::Finished { current_ticks, .. }) = 
                     (player.dungeon_entry_tick, dungeon_state) {
                     let ticks_since_entry = current_ticks.saturating_sub(entry_tick);
                     if ticks_since_entry == 5 {
@@ -858,6 +457,7 @@ async fn main() -> Result<()> {
                 } else {
                     ""
                 };
+                let floor_name = server.dungeon.floor.name;
 
                 sidebar_lines.push(formatdoc! {r#"
                     §e§lSKYBLOCK
@@ -865,7 +465,7 @@ async fn main() -> Result<()> {
 
                     {current_skyblock_month}
                     §7{time}
-                     §7⏣ §cThe Catacombs §7(F7)
+                     §7⏣ §cThe Catacombs §7({floor_name})
 
                 "#});
             }
@@ -887,7 +487,7 @@ async fn main() -> Result<()> {
                         sidebar_lines.push(format!("Starting in: §a0§a:0{}", (tick_countdown / 20) + 1));
                         sidebar_lines.new_line();
                     }
-                    DungeonState::Started { current_ticks } => {
+                    DungeonState::Started { current_ticks } | DungeonState::Boss { current_ticks, .. } | DungeonState::Finished { current_ticks, .. } => {
                         // this is scuffed but it works
                         let seconds = current_ticks / 20;
                         let time = if seconds >= 60 {
@@ -904,8 +504,8 @@ async fn main() -> Result<()> {
                             let seconds = seconds % 60;
                             format!("{}{}s", if seconds < 10 { "0" } else { "" }, seconds)
                         };
-                        // TODO: display correct keys, and cleared percentage
-                        // clear percentage is based on amount of tiles that are cleared.
+                        // TODO: display correct keys
+                        let score = server.dungeon.score(server.dungeon.state.clear_ticks().unwrap_or(current_ticks));
                         sidebar_lines.push(formatdoc! {r#"
                             Keys: §c■ §c✖ §8§8■ §a0x
                             Time elapsed: §a§a{time}
@@ -914,11 +514,10 @@ async fn main() -> Result<()> {
                             §3§lSolo
 
                         "#,
-                        clear_percent = "0",
-                        score = "0",
+                        clear_percent = (server.dungeon.cleared_fraction() * 100.0).floor(),
+                        score = score.total(),
                         });
                     }
-                    DungeonState::Finished => {}
                 }
             }
 
@@ -955,11 +554,11 @@ async fn main() -> Result<()> {
             }
             
             // Apply lava boost system (only in boss rooms)
-            // let is_in_boss_room = server.dungeon.is_player_in_boss_room(player);
+            let is_in_boss_room = server.dungeon.is_player_in_boss_room(player);
             // We need to check lava in the world, but we can't borrow world while player is mutably borrowed
             // So we'll pass the world reference through the player's world_mut method
-            // let world_ref = player.world_mut();
-            // apply_lava_boost(player, world_ref, is_in_boss_room);
+            let world_ref = player.world_mut();
+            apply_lava_boost(player, world_ref, is_in_boss_room);
             
             player.last_position = player.position;
             player.flush_packets();
@@ -42,6 +42,10 @@ pub enum BlockInteractAction {
     RedstoneKeySkull {
         room_index: usize,
     },
+    // buttons and such that belong to a puzzle, the puzzle in that room handles the click
+    Puzzle,
+    // the start button and button grid of the Simon Says device in P3
+    SimonSays,
     // mainly for quick debug,
     Callback(fn(&Player, &BlockPos)),
 }
@@ -101,10 +105,14 @@ impl BlockInteractAction {
                         let _ = other_player.send_message("§cThe §c§lBLOOD DOOR §chas been opened!");
                         let _ = other_player.send_message("§5A shiver runs down your spine...");
                     }
+                    dungeon.open_blood();
                 }
             }
 
             Self::Chest { secret } => {
+                // Puzzles like Three Weirdos get to react first, solving one unlocks its chest right away
+                player.server_mut().dungeon.click_puzzle_block(player, block_pos);
+
                 // Check if this chest is locked
                 let is_locked = {
                     let dungeon = &player.server_mut().dungeon;
@@ -327,6 +335,19 @@ impl BlockInteractAction {
             }
             
             Self::Lever => {
+                // Puzzle levers are handled by the puzzle itself
+                if player.server_mut().dungeon.click_puzzle_block(player, block_pos) {
+                    return;
+                }
+                // and trap room door levers by the trap
+                if player.server_mut().dungeon.pull_trap_lever(block_pos) {
+                    return;
+                }
+                // and the P3 section levers by the P3 manager
+                if player.world_mut().p3_manager.pull_lever(player.world_mut(), player, block_pos) {
+                    return;
+                }
+
                 // Check if this lever unlocks any chests and unlock them
                 {
                     let dungeon = &mut player.server_mut().dungeon;
@@ -599,6 +620,14 @@ impl BlockInteractAction {
                 });
             }
             
+            Self::Puzzle => {
+                player.server_mut().dungeon.click_puzzle_block(player, block_pos);
+            }
+
+            Self::SimonSays => {
+                player.world_mut().p3_manager.click_simon_says(player.world_mut(), player, block_pos);
+            }
+
             Self::Callback(func) => {
                 func(player, block_pos);
             }
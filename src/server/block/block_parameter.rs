@@ -279,7 +279,7 @@ impl Rotatable for StairDirection {
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct ButtonDirection(Direction);
+pub struct ButtonDirection(pub Direction);
 
 
 // todo: fix rotation, its still broken even with fixed direction values
@@ -1,13 +1,15 @@
 #[derive(Debug)]
 pub struct Argument {
     pub name: &'static str,
+    pub required: bool,
     pub completions: Vec<String>,
 }
 
 impl Argument {
-    pub fn new(name: &'static str, _required: bool, completions: Vec<String>) -> Self {
+    pub fn new(name: &'static str, required: bool, completions: Vec<String>) -> Self {
         Self {
             name,
+            required,
             completions,
         }
     }
@@ -2,6 +2,10 @@ pub mod locraw;
 pub mod mort;
 pub mod p3;
 pub mod p3s;
+pub mod pool;
+pub mod reload_rooms;
+pub mod reroll;
+pub mod restart;
 pub mod zombie_spawn;
 pub mod spawn_zombie;
 pub(crate) mod term;
@@ -0,0 +1,54 @@
+use crate::server::commands::argument::Argument;
+use crate::server::commands::command::CommandMetadata;
+use crate::server::commands::outcome::Outcome;
+use crate::server::player::player::Player;
+use crate::server::utils::chat_component::chat_component_text::ChatComponentTextBuilder;
+use crate::server::world::World;
+
+pub struct Pool;
+
+impl CommandMetadata for Pool {
+    const NAME: &'static str = "pool";
+
+    fn run(world: &mut World, player: &mut Player, args: &[&str]) -> anyhow::Result<Outcome> {
+        let server = world.server_mut();
+
+        if args.first() == Some(&"list") {
+            let lines = server.room_pool.describe();
+            if lines.is_empty() {
+                player.send_message("§eThe room pool is empty, every room can be picked.");
+            }
+            for line in lines {
+                player.send_message(&format!("§e{}", line));
+            }
+            return Ok(Outcome::Success);
+        }
+
+        match server.room_pool.apply(args, &server.room_data_storage) {
+            Ok(message) => {
+                player.send_message(&format!("§a{}. §7Use /reroll to apply it.", message));
+                Ok(Outcome::Success)
+            }
+            Err(error) => {
+                player.send_message(&format!("§c{}", error));
+                Ok(Outcome::Failure(ChatComponentTextBuilder::new(format!("§c{}", error)).build()))
+            }
+        }
+    }
+
+    fn arguments(_: &mut World, _: &mut Player) -> Vec<Argument> {
+        vec![
+            Argument::new("action", true, vec![
+                "list".to_string(),
+                "pin".to_string(),
+                "include".to_string(),
+                "exclude".to_string(),
+                "weight".to_string(),
+                "remove".to_string(),
+                "clear".to_string(),
+            ]),
+            Argument::new("room", false, vec![]),
+            Argument::new("value", false, vec![]),
+        ]
+    }
+}
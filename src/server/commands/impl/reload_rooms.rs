@@ -0,0 +1,52 @@
+use std::mem::replace;
+use crate::dungeon::room::room_data_loader::{load_room_data, RoomDataLoad};
+use crate::server::commands::argument::Argument;
+use crate::server::commands::command::CommandMetadata;
+use crate::server::commands::outcome::Outcome;
+use crate::server::player::player::Player;
+use crate::server::world::World;
+
+pub struct ReloadRooms;
+
+impl CommandMetadata for ReloadRooms {
+    const NAME: &'static str = "reloadrooms";
+
+    fn run(world: &mut World, player: &mut Player, _: &[&str]) -> anyhow::Result<Outcome> {
+        let client_id = player.client_id;
+
+        // the rebuild moves every player, including this one, so it can't happen while the command is running.
+        world.server_mut().schedule(0, move |server| {
+            let RoomDataLoad { storage, errors, previous } = load_room_data();
+            let room_count = storage.len();
+
+            let mut messages = errors.iter()
+                .map(|error| format!("§c{:#}", error))
+                .collect::<Vec<String>>();
+
+            // the new rooms are only kept if the dungeon can be built from them,
+            // otherwise rerolls would keep failing until the next reload.
+            let old_storage = replace(&mut server.room_data_storage, storage);
+
+            match server.rebuild_dungeon(server.dungeon_seed.clone()) {
+                Ok(()) => messages.push(format!("§aReloaded {} rooms with {} errors.", room_count, errors.len())),
+                Err(error) => {
+                    server.room_data_storage = old_storage;
+                    previous.restore();
+                    messages.push(format!("§cCould not rebuild the dungeon from the {} reloaded rooms, keeping the old rooms: {:#}", room_count, error));
+                }
+            }
+
+            if let Some(player) = server.world.players.get_mut(&client_id) {
+                for message in messages.iter() {
+                    player.send_message(message);
+                }
+            }
+        });
+
+        Ok(Outcome::Success)
+    }
+
+    fn arguments(_: &mut World, _: &mut Player) -> Vec<Argument> {
+        Vec::new()
+    }
+}
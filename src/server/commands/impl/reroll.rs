@@ -0,0 +1,62 @@
+use crate::dungeon::dungeon_seed::DungeonSeed;
+use crate::dungeon::floor_profile::{FloorProfile, FLOOR_PROFILES};
+use crate::server::commands::argument::Argument;
+use crate::server::commands::command::CommandMetadata;
+use crate::server::commands::outcome::Outcome;
+use crate::server::player::player::Player;
+use crate::server::utils::chat_component::chat_component_text::ChatComponentTextBuilder;
+use crate::server::world::World;
+
+pub struct Reroll;
+
+impl CommandMetadata for Reroll {
+    const NAME: &'static str = "reroll";
+
+    fn run(world: &mut World, player: &mut Player, args: &[&str]) -> anyhow::Result<Outcome> {
+        let server = world.server_mut();
+        let current_floor = server.dungeon.floor;
+
+        // a floor name rolls a random dungeon on that floor, anything else is a seed or dungeon string
+        let seed = match args.first() {
+            Some(arg) => match FloorProfile::from_name(arg).map(|floor| Ok(DungeonSeed::random(&floor, &server.room_pool, &server.room_data_storage))).unwrap_or_else(|| DungeonSeed::parse(arg, current_floor)) {
+                Ok(seed) => seed,
+                Err(error) => {
+                    player.send_message(&format!("§c{}", error));
+                    return Ok(Outcome::Failure(ChatComponentTextBuilder::new(format!("§c{}", error)).build()));
+                }
+            },
+            None => DungeonSeed::random(&current_floor, &server.room_pool, &server.room_data_storage),
+        };
+
+        rebuild_with_seed(world, player, seed);
+        Ok(Outcome::Success)
+    }
+
+    fn arguments(_: &mut World, _: &mut Player) -> Vec<Argument> {
+        let floors = FLOOR_PROFILES.iter().map(|floor| floor.name.to_string()).collect();
+        vec![Argument::new("seed", false, floors)]
+    }
+}
+
+/// Rebuilds the dungeon once the current tick is done,
+/// since it moves every player (including the one running the command).
+pub fn rebuild_with_seed(world: &mut World, player: &mut Player, seed: DungeonSeed) {
+    let client_id = player.client_id;
+
+    world.server_mut().schedule(0, move |server| {
+        match server.rebuild_dungeon(seed) {
+            Ok(()) => {
+                // encoded after the rebuild, which pins the rooms the pool picked into the seed
+                let encoded = server.dungeon_seed.encode();
+                for player in server.world.players.values_mut() {
+                    player.send_message(&format!("§aNew dungeon! Seed: §f{}", encoded));
+                }
+            }
+            Err(error) => {
+                if let Some(player) = server.world.players.get_mut(&client_id) {
+                    player.send_message(&format!("§cFailed to build the dungeon: {:#}", error));
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,22 @@
+use crate::server::commands::argument::Argument;
+use crate::server::commands::command::CommandMetadata;
+use crate::server::commands::outcome::Outcome;
+use crate::server::commands::r#impl::reroll::rebuild_with_seed;
+use crate::server::player::player::Player;
+use crate::server::world::World;
+
+pub struct Restart;
+
+impl CommandMetadata for Restart {
+    const NAME: &'static str = "restart";
+
+    fn run(world: &mut World, player: &mut Player, _: &[&str]) -> anyhow::Result<Outcome> {
+        let seed = world.server_mut().dungeon_seed.clone();
+        rebuild_with_seed(world, player, seed);
+        Ok(Outcome::Success)
+    }
+
+    fn arguments(_: &mut World, _: &mut Player) -> Vec<Argument> {
+        Vec::new()
+    }
+}
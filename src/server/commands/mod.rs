@@ -3,8 +3,12 @@ use crate::server::commands::command::CommandMetadata;
 use crate::server::commands::outcome::Outcome;
 use crate::server::commands::r#impl::locraw::Locraw;
 use crate::server::commands::r#impl::mort::Mort;
-// use crate::server::commands::r#impl::p3::P3;
-// use crate::server::commands::r#impl::p3s::P3S;
+use crate::server::commands::r#impl::p3::P3;
+use crate::server::commands::r#impl::p3s::P3S;
+use crate::server::commands::r#impl::pool::Pool;
+use crate::server::commands::r#impl::reload_rooms::ReloadRooms;
+use crate::server::commands::r#impl::reroll::Reroll;
+use crate::server::commands::r#impl::restart::Restart;
 use crate::server::commands::r#impl::zombie_spawn::ZombieSpawn;
 use crate::server::commands::r#impl::spawn_zombie::SpawnZombie;
 use crate::server::commands::r#impl::term::Term;
@@ -23,7 +27,13 @@ crate::command_registry! {
     Locraw,
     ZombieSpawn,
     SpawnZombie,
-    Term
+    Term,
+    ReloadRooms,
+    Reroll,
+    Restart,
+    Pool,
+    P3,
+    P3S
 }
 
 impl Command {
@@ -43,7 +53,9 @@ impl Command {
                 return Ok(());
             }
 
-            let missing_args = &command_args[args.len()..];
+            let missing_args = command_args[args.len()..].iter()
+                .filter(|arg| arg.required)
+                .collect::<Vec<&Argument>>();
 
             if !missing_args.is_empty() {
                 let component =
@@ -19,6 +19,11 @@ pub enum EntityVariant {
     Bat {
         hanging: bool
     },
+    Blaze,
+    Creeper {
+        powered: bool,
+    },
+    Silverfish,
     FallingBlock,
     // NEW: a thrown ender pearl (spawned with Spawn Object)
     EnderPearl,
@@ -41,6 +46,9 @@ impl EntityVariant {
             EntityVariant::ArmorStand => 30,
             EntityVariant::Zombie { .. } => 54,
             EntityVariant::Bat { .. } => 65, // mob id (Spawn Mob space)
+            EntityVariant::Blaze => 61,
+            EntityVariant::Creeper { .. } => 50,
+            EntityVariant::Silverfish => 60,
             EntityVariant::FallingBlock => 70,
             // NEW: object type id for ender pearl (Spawn Object space, 1.8)
             // It's OK that this is also 65 - Spawn Object and Spawn Mob use different id spaces.
@@ -160,6 +168,9 @@ impl PacketSerializable for EntityMetadata {
             EntityVariant::Bat { hanging } => {
                 write_data(buf, BYTE, 16, *hanging);
             }
+            EntityVariant::Creeper { powered } => {
+                write_data(buf, BYTE, 17, *powered);
+            }
             // NEW: Ender pearls don't carry extra metadata
             EntityVariant::EnderPearl => { /* no-op */ }
             // NEW: Arrows don't carry extra metadata
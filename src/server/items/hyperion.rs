@@ -8,6 +8,9 @@ use crate::net::internal_packets::NetworkThreadMessage;
 use tokio::sync::mpsc::UnboundedSender;
 use std::f64::consts::PI;
 
+/// Damage the implosion does to every blood mob it catches, enough to one shot them up to F7.
+const EXPLOSION_DAMAGE: f64 = 1_000_000.0;
+
 pub fn on_right_click(player: &mut Player) -> anyhow::Result<()> {
     // Use the exact same teleport logic as ether transmission, but with 10 blocks
     let server = &mut player.server_mut();
@@ -146,6 +149,11 @@ fn handle_hyperion_explosion(server: &mut crate::server::server::Server, explosi
     for room_index in rooms_to_update_map {
         server.dungeon.update_map_for_room(room_index);
     }
+
+    // Blood mobs caught in the explosion get damaged as well
+    for mob_id in server.dungeon.blood_room.mobs_in(&server.world, &explosion_aabb) {
+        server.dungeon.blood_room.damage_mob(&mut server.world, mob_id, EXPLOSION_DAMAGE);
+    }
 }
 
 fn handle_hyperion_teleport(
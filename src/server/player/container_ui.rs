@@ -40,6 +40,10 @@ impl UI {
                 title: "Ready Up".to_string(),
                 slot_amount: 54,
             }),
+            UI::TerminalUI { typ: TerminalType::Melody, .. } => Some(ContainerData {
+                title: "Click the button on time!".to_string(),
+                slot_amount: 54,
+            }),
             UI::TerminalUI { typ: TerminalType::Panes, rand } => Some(ContainerData {
                 title: "Correct all the panes!".to_string(),
                 slot_amount: 45,
@@ -184,6 +188,7 @@ impl UI {
                         });
 
                         // TERMINAL COMPLETED
+                        player.world_mut().p3_manager.terminal_gui_completed(player.world_mut(), player);
                         return;
                     }
                     player.current_terminal = Some(terminal);
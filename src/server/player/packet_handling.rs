@@ -36,6 +36,9 @@ impl ProcessPacket for UseEntity {
         if let Some((entity, entity_impl)) = player.world_mut().entities.get_mut(&self.entity_id.0) {
             entity_impl.interact(entity, player, &self.action)
         }
+        if self.action == EntityInteractionType::Attack {
+            player.server_mut().dungeon.attack_entity(player, self.entity_id.0);
+        }
     }
 }
 
@@ -876,6 +879,8 @@ impl ProcessPacket for PlayerAction {
 
 impl ProcessPacket for CloseWindow {
     fn process_with_player(&self, player: &mut Player) {
+        // terminals are made fresh when opened, so a closed one doesn't need to keep ticking
+        player.current_terminal = None;
         player.open_ui(UI::None)
     }
 }
@@ -197,6 +197,9 @@ impl Player {
         
         // Check for falling blocks collision
         self.check_fallingblocks_collision();
+
+        // Puzzles like Ice Fill care about where players walk
+        self.server_mut().dungeon.puzzle_player_moved(self);
         
         // Check for lava bounce
         // self.check_lava_bounce();
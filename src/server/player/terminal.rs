@@ -3,6 +3,7 @@ use crate::net::protocol::play::clientbound::SoundEffect;
 use crate::net::protocol::play::serverbound::{ClickMode, ClickWindow};
 use crate::server::items::item_stack::ItemStack;
 use crate::server::player::player::Player;
+use crate::server::player::terminals::melody::Melody;
 use crate::server::player::terminals::order::Order;
 use crate::server::player::terminals::panes::Panes;
 use crate::server::player::terminals::rubix::Rubix;
@@ -34,7 +35,9 @@ pub struct Terminal {
     pub items: Vec<Option<ItemStack>>,
     pub typ: TerminalType,
     pub solution: HashMap<i8, i8>, // using second arg as an int for rubix and numbers
-    pub rand: i16
+    pub rand: i16,
+    /// ticks since the terminal was opened, for terminals that move on their own
+    pub ticks: u32,
 }
 
 impl Terminal {
@@ -42,6 +45,10 @@ impl Terminal {
         let pair;
         let size;
         match typ {
+            TerminalType::Melody => {
+                pair = Melody::create(rand);
+                size = 6*9;
+            }
             TerminalType::Order => {
                 pair = Order::create(rand);
                 size = 4*9;
@@ -62,10 +69,6 @@ impl Terminal {
                 pair = StartsWith::create(rand);
                 size = 6*9;
             }
-            _ => {
-                pair = Panes::create(rand);
-                size = 5*9;
-            }
         }
 
         Terminal {
@@ -73,7 +76,8 @@ impl Terminal {
             items: pair.0,
             typ,
             solution: pair.1,
-            rand
+            rand,
+            ticks: 0,
         }
     }
 
@@ -99,6 +103,9 @@ impl Terminal {
     ) -> bool {
         let slot = packet.slot_id as usize;
         match self.typ {
+            TerminalType::Melody => {
+                Melody::click_slot(self, player, slot, packet)
+            }
             TerminalType::Order => {
                 Order::click_slot(self, player, slot, packet)
             }
@@ -114,7 +121,15 @@ impl Terminal {
             TerminalType::StartsWith => {
                 StartsWith::click_slot(self, player, slot, packet)
             }
-            _ => { false }
+        }
+    }
+
+    /// called every tick while the terminal is open, returns true if the contents changed and need to be resent.
+    pub fn tick(&mut self) -> bool {
+        self.ticks += 1;
+        match self.typ {
+            TerminalType::Melody => Melody::tick(self),
+            _ => false,
         }
     }
     pub fn play_sound(&self, player: &mut Player, sound: &str) {
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use rand::Rng;
+use crate::net::protocol::play::serverbound::ClickWindow;
+use crate::server::player::terminal::{pane, Term, Terminal, TerminalType};
+use crate::server::items::item_stack::ItemStack;
+use crate::server::player::player::Player;
+use crate::server::utils::nbt::nbt::NBT;
+use crate::server::utils::sounds::Sounds;
+
+// the top row shows which column is the right one with a magenta pane,
+// in the row being played a lime pane moves back and forth over columns 1 to 5.
+// clicking the button at the end of the row while the lime pane is in the magenta column moves on to the next row,
+// after the 4th row the terminal is done.
+//
+// the state is kept in the solution map under the keys below.
+
+pub(crate) struct Melody;
+
+const SIZE: usize = 9*6;
+const ROWS: i8 = 4;
+const FIRST_COLUMN: i8 = 1;
+const LAST_COLUMN: i8 = 5;
+const BUTTON_COLUMN: i8 = 7;
+/// ticks between the lime pane moving
+const MOVE_INTERVAL: u32 = 10;
+/// a click this many ticks after the pane left the right column still counts, to make up for ping
+const GRACE_TICKS: u32 = 2;
+
+/// row being played, 0 to 3 (drawn one below that, the top row is the magenta one)
+const ROW: i8 = 0;
+const TARGET: i8 = 1;
+const POSITION: i8 = 2;
+const PREVIOUS: i8 = 3;
+/// 1 or -1
+const DIRECTION: i8 = 4;
+
+impl Term for Melody {
+    fn click_slot(terminal: &mut Terminal, player: &mut Player, slot: usize, _packet: &ClickWindow) -> bool {
+        let row = terminal.solution[&ROW];
+        if slot != slot_of(row + 1, BUTTON_COLUMN) {
+            return false;
+        }
+
+        let target = terminal.solution[&TARGET];
+        let in_time = terminal.solution[&POSITION] == target
+            || (terminal.solution[&PREVIOUS] == target && terminal.ticks % MOVE_INTERVAL < GRACE_TICKS);
+        if !in_time {
+            return false;
+        }
+
+        terminal.play_sound(player, Sounds::NotePling.id());
+        if row + 1 == ROWS {
+            player.send_message(&format!("§aMelody completed in §f{:.2}s§a!", terminal.ticks as f64 / 20.0));
+            return Self::check(terminal);
+        }
+
+        let mut rng = rand::rng();
+        terminal.solution.insert(ROW, row + 1);
+        terminal.solution.insert(TARGET, rng.random_range(FIRST_COLUMN..=LAST_COLUMN));
+        terminal.solution.insert(POSITION, FIRST_COLUMN);
+        terminal.solution.insert(PREVIOUS, FIRST_COLUMN);
+        terminal.solution.insert(DIRECTION, 1);
+        draw(terminal);
+        false
+    }
+
+    fn create(_rand: i16) -> (Vec<Option<ItemStack>>, HashMap<i8, i8>) {
+        let mut rng = rand::rng();
+        let mut map: HashMap<i8, i8> = HashMap::new();
+        map.insert(ROW, 0);
+        map.insert(TARGET, rng.random_range(FIRST_COLUMN..=LAST_COLUMN));
+        map.insert(POSITION, FIRST_COLUMN);
+        map.insert(PREVIOUS, FIRST_COLUMN);
+        map.insert(DIRECTION, 1);
+
+        let mut terminal = Terminal {
+            size: SIZE as i8,
+            items: vec![None; SIZE],
+            typ: TerminalType::Melody,
+            solution: map,
+            rand: 0,
+            ticks: 0,
+        };
+        draw(&mut terminal);
+        (terminal.items, terminal.solution)
+    }
+
+    fn check(terminal: &Terminal) -> bool {
+        terminal.solution[&ROW] + 1 == ROWS
+    }
+}
+
+impl Melody {
+    /// moves the lime pane, returns true if the contents changed.
+    pub fn tick(terminal: &mut Terminal) -> bool {
+        if !terminal.ticks.is_multiple_of(MOVE_INTERVAL) {
+            return false;
+        }
+        let position = terminal.solution[&POSITION];
+        let mut direction = terminal.solution[&DIRECTION];
+        if !(FIRST_COLUMN..=LAST_COLUMN).contains(&(position + direction)) {
+            direction = -direction;
+        }
+        terminal.solution.insert(PREVIOUS, position);
+        terminal.solution.insert(POSITION, position + direction);
+        terminal.solution.insert(DIRECTION, direction);
+        draw(terminal);
+        true
+    }
+}
+
+fn slot_of(row: i8, column: i8) -> usize {
+    (row * 9 + column) as usize
+}
+
+fn draw(terminal: &mut Terminal) {
+    let row = terminal.solution[&ROW];
+    let target = terminal.solution[&TARGET];
+    let position = terminal.solution[&POSITION];
+
+    for slot in 0..SIZE {
+        terminal.set_slot(pane(15, ""), slot);
+    }
+    for column in FIRST_COLUMN..=LAST_COLUMN {
+        if column == target {
+            terminal.set_slot(pane(2, ""), slot_of(0, column));
+        }
+        for melody_row in 0..ROWS {
+            let meta = if melody_row != row {
+                0
+            } else if column == position {
+                5
+            } else {
+                14
+            };
+            terminal.set_slot(pane(meta, ""), slot_of(melody_row + 1, column));
+        }
+    }
+    for melody_row in 0..ROWS {
+        let (meta, name) = if melody_row == row { (5, "§aClick!") } else { (14, "") };
+        terminal.set_slot(clay(meta, name), slot_of(melody_row + 1, BUTTON_COLUMN));
+    }
+}
+
+fn clay(meta: i16, name: &str) -> ItemStack {
+    ItemStack {
+        item: 159,
+        stack_size: 1,
+        metadata: meta,
+        tag_compound: Some(NBT::with_nodes(vec![
+            NBT::compound("display", vec![
+                NBT::string("Name", name)
+            ])
+        ])),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod melody;
 pub mod panes;
 pub mod order;
 pub mod rubix;
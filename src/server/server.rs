@@ -1,21 +1,28 @@
 use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_seed::DungeonSeed;
+use crate::dungeon::room::room_data::RoomData;
+use crate::dungeon::room::room_pool::RoomPool;
 use crate::net::internal_packets::{MainThreadMessage, NetworkThreadMessage};
 use crate::net::packets::packet::ProcessPacket;
 use crate::net::packets::packet_serialize::PacketSerializable;
-use crate::net::protocol::play::clientbound::{AddEffect, CustomPayload, EntityProperties, JoinGame, PlayerAbilities, PlayerListHeaderFooter, PositionLook};
+use crate::net::protocol::play::clientbound::{AddEffect, CloseWindow, CustomPayload, DestroyEntites, EntityProperties, JoinGame, Maps, PlayerAbilities, PlayerListHeaderFooter, PositionLook};
 use crate::net::var_int::VarInt;
 use crate::server::items::Item;
 use crate::server::player::attribute::{Attribute, AttributeMap, AttributeModifier};
+use crate::server::player::container_ui::UI;
 use crate::server::player::inventory::ItemSlot;
+use crate::server::player::dungeon_stats::DungeonPlayerStats;
 use crate::server::player::player::Player;
 use crate::server::utils::player_list::footer::footer;
 use crate::server::utils::player_list::header::header;
+use crate::server::utils::player_list::PlayerList;
 use crate::server::utils::tasks::Task;
-use crate::server::world;
 use crate::server::world::World;
 use crate::server::entity::entity::EntityId;
 use crate::server::utils::dvec3::DVec3;
+use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
 use anyhow::{Context, Result};
+use std::mem::{replace, take};
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
@@ -26,6 +33,13 @@ pub struct Server {
     /// however we don't really need that, so for now only 1 main world will be supported
     pub world: World,
     pub dungeon: Dungeon,
+    /// the seed the current dungeon was built from, used to restart or share it.
+    pub dungeon_seed: DungeonSeed,
+    pub room_data_storage: DeterministicHashMap<usize, RoomData>,
+    /// which rooms can be picked, applied whenever the dungeon is rebuilt.
+    pub room_pool: RoomPool,
+    /// if set, a new dungeon is rolled this many ticks after a run is finished.
+    pub auto_reroll: Option<u32>,
 
     pub tasks: Vec<Task>,
     // im not sure about having players in server directly.
@@ -34,15 +48,104 @@ impl Server {
     pub fn initialize_with_dungeon(
         network_tx: UnboundedSender<NetworkThreadMessage>,
         dungeon: Dungeon,
+        dungeon_seed: DungeonSeed,
+        room_data_storage: DeterministicHashMap<usize, RoomData>,
+        room_pool: RoomPool,
     ) -> Server {
         Server {
             network_tx,
             world: World::new(),
             dungeon,
+            dungeon_seed,
+            room_data_storage,
+            room_pool,
+            auto_reroll: None,
             tasks: Vec::new(),
         }
     }
 
+    /// Replaces the current dungeon with one built from the seed, without players having to reconnect.
+    /// If the seed can't be built the current dungeon is left untouched.
+    pub fn rebuild_dungeon(&mut self, mut seed: DungeonSeed) -> Result<()> {
+        let mut dungeon = Dungeon::from_seed(&seed, &self.room_data_storage, &self.room_pool)?;
+        if !self.room_pool.is_empty() {
+            seed.pin_rooms(&dungeon, &self.room_data_storage);
+        }
+
+        // load the new dungeon into an empty world first, the old world and tasks are only dropped once that worked.
+        // the world is loaded in place since entities keep a pointer to it,
+        // entity ids keep counting up so they can't collide with the players entity ids.
+        let mut world = World::new();
+        world.server = self;
+        world.next_entity_id = self.world.next_entity_id;
+        world.tick_count = self.world.tick_count;
+        let mut old_world = replace(&mut self.world, world);
+        // anything scheduled was for the old dungeon
+        let old_tasks = take(&mut self.tasks);
+
+        dungeon.server = self;
+        if let Err(error) = dungeon.load_into_world(&mut self.world) {
+            self.world = old_world;
+            self.tasks = old_tasks;
+            return Err(error);
+        }
+
+        let entity_ids = old_world.entities.keys().copied().collect::<Vec<EntityId>>();
+        for player in old_world.players.values_mut() {
+            player.write_packet(&DestroyEntites {
+                entities: entity_ids.iter().map(|id| VarInt(*id)).collect(),
+            });
+        }
+        self.world.players = take(&mut old_world.players);
+        self.world.player_info = replace(&mut old_world.player_info, PlayerList::new());
+
+        self.dungeon = dungeon;
+        self.dungeon_seed = seed;
+
+        let mut players = take(&mut self.world.players);
+        for player in players.values_mut() {
+            player.current_room_index = None;
+            if player.current_terminal.take().is_some() && matches!(player.current_ui, UI::TerminalUI { .. }) {
+                player.current_ui = UI::None;
+                player.write_packet(&CloseWindow {
+                    window_id: player.window_id,
+                });
+            }
+            player.has_redstone_key = false;
+            player.dungeon_entry_tick = None;
+            player.dungeon_stats = DungeonPlayerStats::default();
+
+            player.position = self.world.spawn_point;
+            player.last_position = self.world.spawn_point;
+            player.yaw = self.world.spawn_yaw;
+            player.pitch = self.world.spawn_pitch;
+            player.write_packet(&PositionLook {
+                x: player.position.x,
+                y: player.position.y,
+                z: player.position.z,
+                yaw: player.yaw,
+                pitch: player.pitch,
+                flags: 0,
+            });
+
+            self.world.send_chunks_in_view(player);
+
+            // clear whatever the map had drawn for the old dungeon
+            player.write_packet(&Maps {
+                id: 1,
+                scale: 0,
+                columns: 128,
+                rows: 128,
+                x: 0,
+                z: 0,
+                map_data: self.dungeon.map.map_data.to_vec(),
+            });
+        }
+        self.world.players = players;
+
+        Ok(())
+    }
+
     pub fn schedule(&mut self, run_in: u32, task: impl FnOnce(&mut Self) + 'static) {
         self.tasks.push(Task::new(run_in, task));
     }
@@ -148,32 +251,7 @@ impl Server {
                     flags: 0,
                 });
 
-                let chunk_x = (player.position.x.floor() as i32) >> 4;
-                let chunk_z = (player.position.z.floor() as i32) >> 4;
-                
-                let view_distance = world::VIEW_DISTANCE as i32 + 1;
-                
-                self.world.chunk_grid.for_each_in_view(
-                    chunk_x, 
-                    chunk_z,
-                    view_distance,
-                    |chunk, x, z| {
-                        player.write_packet(&chunk.get_chunk_data(x, z, true));
-    
-                        for entity_id in chunk.entities.iter_mut() {
-                            let (entity, entity_impl) = &mut self.world.entities.get_mut(&entity_id).unwrap();
-                            // Send spawn packets directly to player instead of using chunk buffer
-                            entity.write_spawn_packet(&mut player.packet_buffer);
-                            entity_impl.spawn(entity, &mut player.packet_buffer);
-                            
-                            // Resync equipment if this entity has equipment
-                            if let Some(equipment) = self.world.entity_equipment.get(&*entity_id) {
-                                use crate::server::entity::spawn_equipped::send_equipment_packets;
-                                send_equipment_packets(&mut player.packet_buffer, *entity_id, equipment);
-                            }
-                        } 
-                    }
-                );
+                self.world.send_chunks_in_view(&mut player);
 
 
                 
@@ -26,6 +26,36 @@ impl AABB {
         self.min.z <= other.max.z && self.max.z >= other.min.z
     }
 
+    /// If the line between the two points goes through the box.
+    pub fn intersects_line(&self, from: DVec3, to: DVec3) -> bool {
+        let mut enter: f64 = 0.0;
+        let mut exit: f64 = 1.0;
+        for (start, end, min, max) in [
+            (from.x, to.x, self.min.x, self.max.x),
+            (from.y, to.y, self.min.y, self.max.y),
+            (from.z, to.z, self.min.z, self.max.z),
+        ] {
+            let delta = end - start;
+            if delta == 0.0 {
+                if start < min || start > max {
+                    return false;
+                }
+                continue;
+            }
+            let (near, far) = {
+                let a = (min - start) / delta;
+                let b = (max - start) / delta;
+                if a < b { (a, b) } else { (b, a) }
+            };
+            enter = enter.max(near);
+            exit = exit.min(far);
+            if enter > exit {
+                return false;
+            }
+        }
+        true
+    }
+
     pub const fn from_height_width(height: f64, width: f64) -> Self {
         Self { 
             min: DVec3 { x: -width / 2.0, y: 0.0, z: -width / 2.0 },
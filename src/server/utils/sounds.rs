@@ -50,6 +50,7 @@ id_enum! {
         BatDeath("mob.bat.death"),
         BatHurt("mob.bat.hurt"),
         PistonIn("tile.piston.in"),
+        SilverfishHit("mob.silverfish.hit"),
         
     }
 }
\ No newline at end of file
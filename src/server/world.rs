@@ -8,16 +8,14 @@ use crate::server::chunk::chunk_grid::ChunkGrid;
 use crate::server::entity::entity::{Entity, EntityId, EntityImpl};
 use crate::server::entity::entity_metadata::{EntityMetadata, EntityVariant};
 use crate::server::entity::equipment::Equipment;
-use crate::server::entity::spawn_equipped::{CombatState, AISuspended, AttackCooldown, CurrentTarget};
+use crate::server::entity::spawn_equipped::{send_equipment_packets, CombatState, AISuspended, AttackCooldown, CurrentTarget};
 use crate::server::player::player::{ClientId, Player};
 use crate::server::server::Server;
 use crate::server::utils::dvec3::DVec3;
 use crate::server::utils::player_list::PlayerList;
 use crate::server::redstone::RedstoneSystem;
 use crate::server::block::metadata::BlockMetadata;
-// use crate::dungeon::p3::simon_says::SimonSays;
-// use crate::dungeon::p3::terminal::TerminalManager;
-// use crate::dungeon::p3::p3_manager::P3Manager;
+use crate::dungeon::p3::p3_manager::P3Manager;
 use std::collections::HashMap;
 use std::mem::take;
 use uuid::Uuid;
@@ -79,14 +77,8 @@ pub struct World {
     // Redstone system for handling power transmission
     pub redstone_system: RedstoneSystem,
     
-    // P3 Simon Says puzzle
-    // pub simon_says: SimonSays,
-    
-    // P3 Terminal system
-    // pub terminal_manager: TerminalManager,
-    
-    // P3 Manager
-    // pub p3_manager: P3Manager,
+    // P3 Manager, owns the terminals of the current section
+    pub p3_manager: P3Manager,
 }
 
 impl World {
@@ -119,9 +111,7 @@ impl World {
             scheduled_fixed_sounds: Vec::new(),
             tick_count: 0,
             redstone_system: RedstoneSystem::new(),
-            // simon_says: SimonSays::new(),
-            // terminal_manager: TerminalManager::new(),
-            // p3_manager: P3Manager::new(),
+            p3_manager: P3Manager::new(),
             
             stop_lava_flow: true, // Stop lava flow by default like Java version
         }
@@ -240,43 +230,9 @@ impl World {
         }
         self.scheduled_fixed_sounds = remaining_sounds;
         
-        // Process Simon Says puzzle timing system
-        // self.simon_says.tick(self.tick_count);
-        
-        // Process Simon Says world tick actions
-        // let actions_to_process = self.simon_says.get_pending_actions(self.tick_count);
-        
-        // for action in actions_to_process {
-        //     match action {
-        //         crate::dungeon::p3::simon_says::SolutionAction::RemoveButtons => {
-        //             let button_positions = self.simon_says.get_button_positions();
-        //             for pos in button_positions {
-        //                 self.set_block_at(crate::server::block::blocks::Blocks::Air, pos.x, pos.y, pos.z);
-        //             }
-        //         }
-        //         crate::dungeon::p3::simon_says::SolutionAction::ShowSeaLantern(pos) => {
-        //             self.set_block_at(crate::server::block::blocks::Blocks::SeaLantern, pos.x, pos.y, pos.z);
-        //         }
-        //         crate::dungeon::p3::simon_says::SolutionAction::HideSeaLantern(pos) => {
-        //             self.set_block_at(crate::server::block::blocks::Blocks::Obsidian, pos.x, pos.y, pos.z);
-        //         }
-        //         crate::dungeon::p3::simon_says::SolutionAction::ReplaceButtons => {
-        //             let button_positions = self.simon_says.get_button_positions();
-        //             for pos in button_positions {
-        //                 self.set_block_at(
-        //                     crate::server::block::blocks::Blocks::StoneButton { 
-        //                         direction: crate::server::block::block_parameter::ButtonDirection::from_meta(2), 
-        //                         powered: false 
-        //                     }, 
-        //                     pos.x, pos.y, pos.z
-        //                 );
-        //             }
-        //         }
-        //         crate::dungeon::p3::simon_says::SolutionAction::StartPuzzle => {
-        //             self.simon_says.showing_solution = false;
-        //         }
-        //     }
-        // }
+        // Process the P3 devices, Simon Says flashing its sequence
+        let server = self.server_mut();
+        self.p3_manager.tick(&mut server.world);
         
         // Process redstone system updates
         // We'll handle redstone updates in the lever interaction instead
@@ -285,6 +241,35 @@ impl World {
         Ok(())
     }
     
+    /// Sends the chunks around the player, along with the entities inside of them.
+    pub fn send_chunks_in_view(&mut self, player: &mut Player) {
+        let chunk_x = (player.position.x.floor() as i32) >> 4;
+        let chunk_z = (player.position.z.floor() as i32) >> 4;
+
+        let view_distance = VIEW_DISTANCE as i32 + 1;
+
+        self.chunk_grid.for_each_in_view(
+            chunk_x,
+            chunk_z,
+            view_distance,
+            |chunk, x, z| {
+                player.write_packet(&chunk.get_chunk_data(x, z, true));
+
+                for entity_id in chunk.entities.iter_mut() {
+                    let (entity, entity_impl) = self.entities.get_mut(entity_id).unwrap();
+                    // Send spawn packets directly to player instead of using chunk buffer
+                    entity.write_spawn_packet(&mut player.packet_buffer);
+                    entity_impl.spawn(entity, &mut player.packet_buffer);
+
+                    // Resync equipment if this entity has equipment
+                    if let Some(equipment) = self.entity_equipment.get(&*entity_id) {
+                        send_equipment_packets(&mut player.packet_buffer, *entity_id, equipment);
+                    }
+                }
+            }
+        );
+    }
+
     pub fn set_block_at(&mut self, block: Blocks, x: i32, y: i32, z: i32) {
         self.chunk_grid.set_block_at(block, x, y, z);
     }